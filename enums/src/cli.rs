@@ -0,0 +1,264 @@
+//! コマンドライン引数の解析
+
+use crate::network::{IpNetwork, NetworkError};
+use std::fmt;
+
+/// 一覧（ホストやサブネット）を表示する最大の数
+pub const LIST_LIMIT: usize = 256;
+
+/// 実行するコマンド
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Command {
+    /// デモを表示する（コマンド省略時）
+    #[default]
+    Demo,
+    /// サブネットの計算
+    Subnet(SubnetCommand),
+    /// メッセージを1行ずつ読んで実行する
+    Repl,
+}
+
+/// `subnet` コマンドの内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubnetCommand {
+    /// ネットワークのアドレス・マスク・アドレス数を表示する
+    Info(IpNetwork),
+    /// 指定したプレフィックス長のサブネットに分割する
+    Split(IpNetwork, u8),
+    /// ホストに割り当てられるアドレスを列挙する
+    Hosts(IpNetwork),
+    /// 複数のネットワークをまとめる
+    Aggregate(Vec<IpNetwork>),
+}
+
+/// コマンドライン引数のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// 値が必要なオプションに値がない
+    MissingValue(&'static str),
+    /// コマンドに必要な引数がない
+    MissingArgument(&'static str),
+    /// オプションの値が不正
+    InvalidValue(&'static str, String),
+    /// ネットワークの指定が不正
+    InvalidNetwork(String, NetworkError),
+    /// 同時に指定できないオプション
+    Conflict(&'static str, &'static str),
+    /// 不明なコマンド・オプション、または余分な引数
+    Unexpected(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::MissingValue(option) => write!(f, "{option} には値が必要です"),
+            ArgError::MissingArgument(argument) => write!(f, "{argument} を指定してください"),
+            ArgError::InvalidValue(option, value) => {
+                write!(f, "{option} の値が不正です: {value}")
+            }
+            ArgError::InvalidNetwork(value, e) => write!(f, "{value}: {e}"),
+            ArgError::Conflict(a, b) => write!(f, "{a} と {b} は同時に指定できません"),
+            ArgError::Unexpected(arg) => write!(f, "不明な引数です: {arg}"),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// 使い方の説明
+pub const USAGE: &str = "\
+使い方: enums [subnet ... | repl]
+
+コマンド:
+  （なし）                           列挙型のデモを表示する
+  subnet <ネットワーク>              アドレス・マスク・アドレス数を表示する
+  subnet <ネットワーク> --split <n>  /n のサブネットに分割する
+  subnet <ネットワーク> --hosts      ホストに割り当てられるアドレスを列挙する
+  subnet --aggregate <ネットワーク>...
+                                     ネットワークを同じ範囲を覆う最小の一覧にまとめる
+  repl                               Move(10, 20) などのメッセージを1行ずつ読んで実行する
+                                     （Quit か入力の終わりで終了）
+
+ネットワークは 10.0.0.0/8 や 2001:db8::/32 の形で指定する。
+一覧は最初の 256 個まで表示する。
+
+オプション:
+  -h, --help                         この説明を表示する";
+
+/// コマンドライン引数（プログラム名を除く）を解析する
+///
+/// `--help` が指定された場合は `Ok(None)` を返す
+///
+/// # Errors
+/// 引数が不正な場合に `ArgError` を返す
+pub fn parse_args<I>(args: I) -> Result<Option<Command>, ArgError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        None => Ok(Some(Command::Demo)),
+        Some("-h" | "--help") => Ok(None),
+        Some("subnet") => parse_subnet(args).map(|command| command.map(Command::Subnet)),
+        Some("repl") => match args.next().as_deref() {
+            None => Ok(Some(Command::Repl)),
+            Some("-h" | "--help") => Ok(None),
+            Some(other) => Err(ArgError::Unexpected(other.to_string())),
+        },
+        Some(other) => Err(ArgError::Unexpected(other.to_string())),
+    }
+}
+
+fn parse_subnet(args: impl Iterator<Item = String>) -> Result<Option<SubnetCommand>, ArgError> {
+    let mut args = args.peekable();
+    let mut networks = Vec::new();
+    let mut split = None;
+    let mut hosts = false;
+    let mut aggregate = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--split" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--split"))?;
+                let prefix = value
+                    .strip_prefix('/')
+                    .unwrap_or(&value)
+                    .parse()
+                    .map_err(|_| ArgError::InvalidValue("--split", value.clone()))?;
+                split = Some(prefix);
+            }
+            "--hosts" => hosts = true,
+            "--aggregate" => aggregate = true,
+            _ if arg.starts_with('-') => return Err(ArgError::Unexpected(arg)),
+            _ => match arg.parse::<IpNetwork>() {
+                Ok(network) => networks.push(network),
+                Err(e) => return Err(ArgError::InvalidNetwork(arg, e)),
+            },
+        }
+    }
+
+    if aggregate {
+        if split.is_some() {
+            return Err(ArgError::Conflict("--split", "--aggregate"));
+        }
+        if hosts {
+            return Err(ArgError::Conflict("--hosts", "--aggregate"));
+        }
+        if networks.is_empty() {
+            return Err(ArgError::MissingArgument("<ネットワーク>"));
+        }
+        return Ok(Some(SubnetCommand::Aggregate(networks)));
+    }
+    if split.is_some() && hosts {
+        return Err(ArgError::Conflict("--split", "--hosts"));
+    }
+
+    let mut networks = networks.into_iter();
+    let network = networks
+        .next()
+        .ok_or(ArgError::MissingArgument("<ネットワーク>"))?;
+    if let Some(extra) = networks.next() {
+        return Err(ArgError::Unexpected(extra.to_string()));
+    }
+    Ok(Some(match split {
+        Some(prefix) => SubnetCommand::Split(network, prefix),
+        None if hosts => SubnetCommand::Hosts(network),
+        None => SubnetCommand::Info(network),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Command>, ArgError> {
+        parse_args(args.iter().map(ToString::to_string))
+    }
+
+    fn net(s: &str) -> IpNetwork {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(&[]), Ok(Some(Command::Demo)));
+        assert_eq!(parse(&["--help"]), Ok(None));
+        assert_eq!(parse(&["subnet", "--help"]), Ok(None));
+        assert_eq!(parse(&["repl"]), Ok(Some(Command::Repl)));
+        assert_eq!(parse(&["repl", "-h"]), Ok(None));
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8"]),
+            Ok(Some(Command::Subnet(SubnetCommand::Info(net(
+                "10.0.0.0/8"
+            )))))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8", "--split", "/10"]),
+            Ok(Some(Command::Subnet(SubnetCommand::Split(
+                net("10.0.0.0/8"),
+                10
+            ))))
+        );
+        assert_eq!(
+            parse(&["subnet", "--hosts", "2001:db8::/126"]),
+            Ok(Some(Command::Subnet(SubnetCommand::Hosts(net(
+                "2001:db8::/126"
+            )))))
+        );
+        assert_eq!(
+            parse(&["subnet", "--aggregate", "10.0.0.0/24", "10.0.1.0/24"]),
+            Ok(Some(Command::Subnet(SubnetCommand::Aggregate(vec![
+                net("10.0.0.0/24"),
+                net("10.0.1.0/24")
+            ]))))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse(&["route"]),
+            Err(ArgError::Unexpected("route".to_string()))
+        );
+        assert_eq!(
+            parse(&["repl", "Quit"]),
+            Err(ArgError::Unexpected("Quit".to_string()))
+        );
+        assert_eq!(
+            parse(&["subnet"]),
+            Err(ArgError::MissingArgument("<ネットワーク>"))
+        );
+        assert_eq!(
+            parse(&["subnet", "--aggregate"]),
+            Err(ArgError::MissingArgument("<ネットワーク>"))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8", "10.0.0.0/9"]),
+            Err(ArgError::Unexpected("10.0.0.0/9".to_string()))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0"]),
+            Err(ArgError::InvalidNetwork(
+                "10.0.0.0".to_string(),
+                NetworkError::MissingPrefix
+            ))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8", "--split"]),
+            Err(ArgError::MissingValue("--split"))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8", "--split", "x"]),
+            Err(ArgError::InvalidValue("--split", "x".to_string()))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8", "--split", "9", "--hosts"]),
+            Err(ArgError::Conflict("--split", "--hosts"))
+        );
+        assert_eq!(
+            parse(&["subnet", "10.0.0.0/8", "--verbose"]),
+            Err(ArgError::Unexpected("--verbose".to_string()))
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! `Message` のバイナリ形式への変換
+//!
+//! 1つのメッセージを1つのフレームとし、先頭の1バイト（タグ）でバリアントを表す。
+//! 整数はすべて上位バイトが先頭（ビッグエンディアン）。
+//!
+//! | バリアント | タグ | 続くバイト列 |
+//! |------------|------|--------------|
+//! | `Quit` | `0x00` | なし |
+//! | `Move { x, y }` | `0x01` | `x`・`y`（i32 が2つ） |
+//! | `Write(text)` | `0x02` | バイト数（u32）と UTF-8 の文字列 |
+//! | `ChangeColor(r, g, b)` | `0x03` | `r`・`g`・`b`（i32 が3つ） |
+//!
+//! フレームは続けて並べられる。`Decoder` は `Read` からフレームの途中までしか届いていなくても、
+//! 残りが届くまで読み進めてメッセージを取り出す。`Write` のバイト数は送り手が自由に書けるので、
+//! `Decoder` は上限（既定は `DEFAULT_MAX_LEN`）を超えるフレームを溜め始める前にエラーにする。
+//!
+//! ```
+//! use enums::Message;
+//! use enums::codec::{decode, encode};
+//!
+//! let message = Message::Write("Hi".to_string());
+//! let bytes = encode(&message);
+//! assert_eq!(bytes, [0x02, 0, 0, 0, 2, b'H', b'i']);
+//! assert_eq!(decode(&bytes), Ok(message));
+//! ```
+
+use crate::message::Message;
+use std::fmt;
+use std::io::{self, Read};
+use std::str::Utf8Error;
+
+const TAG_QUIT: u8 = 0x00;
+const TAG_MOVE: u8 = 0x01;
+const TAG_WRITE: u8 = 0x02;
+const TAG_CHANGE_COLOR: u8 = 0x03;
+
+/// `Decoder` が1回の `read` で読むバイト数
+const READ_CHUNK: usize = 4096;
+
+/// `Decoder::new` が受け付けるフレームの最大バイト数（1 MiB）
+pub const DEFAULT_MAX_LEN: usize = 1 << 20;
+
+/// バイト列からメッセージを取り出すときのエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// フレームの途中でバイト列が終わった
+    UnexpectedEnd,
+    /// 知らないタグ
+    UnknownTag(u8),
+    /// `Write` の文字列が UTF-8 ではない
+    InvalidUtf8(Utf8Error),
+    /// フレームの後に余分なバイトがある
+    TrailingBytes(usize),
+    /// フレームが `Decoder` の上限より長い
+    FrameTooLarge {
+        /// ヘッダーから分かるフレームのバイト数
+        len: usize,
+        /// 上限のバイト数
+        max_len: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "フレームの途中でデータが終わっています"),
+            DecodeError::UnknownTag(tag) => write!(f, "不明なタグです: 0x{tag:02x}"),
+            DecodeError::InvalidUtf8(e) => write!(f, "文字列が UTF-8 ではありません: {e}"),
+            DecodeError::TrailingBytes(count) => {
+                write!(f, "フレームの後に {count} バイト余っています")
+            }
+            DecodeError::FrameTooLarge { len, max_len } => {
+                write!(
+                    f,
+                    "フレームが長すぎます: {len} バイト（上限 {max_len} バイト）"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// `Read` からメッセージを読むときのエラー
+#[derive(Debug)]
+pub enum ReadError {
+    /// 読み込みに失敗した
+    Io(io::Error),
+    /// 読んだバイト列が正しいフレームではない
+    Decode(DecodeError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "読み込みに失敗しました: {e}"),
+            ReadError::Decode(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Decode(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<DecodeError> for ReadError {
+    fn from(e: DecodeError) -> Self {
+        ReadError::Decode(e)
+    }
+}
+
+/// メッセージを1つのフレームにする
+///
+/// # Panics
+/// `Write` の文字列が `u32::MAX` バイトを超える場合
+#[must_use]
+pub fn encode(message: &Message) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(message, &mut out);
+    out
+}
+
+/// メッセージのフレームを `out` の末尾に書き足す
+///
+/// # Panics
+/// `Write` の文字列が `u32::MAX` バイトを超える場合
+pub fn encode_into(message: &Message, out: &mut Vec<u8>) {
+    match message {
+        Message::Quit => out.push(TAG_QUIT),
+        Message::Move { x, y } => {
+            out.push(TAG_MOVE);
+            out.extend_from_slice(&x.to_be_bytes());
+            out.extend_from_slice(&y.to_be_bytes());
+        }
+        Message::Write(text) => {
+            let len = u32::try_from(text.len()).expect("文字列は u32::MAX バイト以下");
+            out.push(TAG_WRITE);
+            out.extend_from_slice(&len.to_be_bytes());
+            out.extend_from_slice(text.as_bytes());
+        }
+        Message::ChangeColor(r, g, b) => {
+            out.push(TAG_CHANGE_COLOR);
+            for value in [r, g, b] {
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+}
+
+/// ちょうど1つのフレームからなるバイト列をメッセージに戻す
+///
+/// # Errors
+/// フレームが途中で終わっている、正しくない、または後に余分なバイトがある場合に `DecodeError` を返す
+pub fn decode(bytes: &[u8]) -> Result<Message, DecodeError> {
+    match decode_prefix(bytes)? {
+        Some((message, used)) if used == bytes.len() => Ok(message),
+        Some((_, used)) => Err(DecodeError::TrailingBytes(bytes.len() - used)),
+        None => Err(DecodeError::UnexpectedEnd),
+    }
+}
+
+/// バイト列の先頭のフレームをメッセージに戻し、使ったバイト数と一緒に返す
+///
+/// フレームの途中でバイト列が終わっていれば `Ok(None)` を返す（続きが届いてから呼び直す）。
+///
+/// ```
+/// use enums::Message;
+/// use enums::codec::{decode_prefix, encode};
+///
+/// let bytes = encode(&Message::Move { x: 1, y: 2 });
+/// assert_eq!(decode_prefix(&bytes[..5]), Ok(None));
+/// assert_eq!(decode_prefix(&bytes), Ok(Some((Message::Move { x: 1, y: 2 }, 9))));
+/// ```
+///
+/// # Errors
+/// タグが不明、または `Write` の文字列が UTF-8 ではない場合に `DecodeError` を返す
+pub fn decode_prefix(bytes: &[u8]) -> Result<Option<(Message, usize)>, DecodeError> {
+    let Some((&tag, rest)) = bytes.split_first() else {
+        return Ok(None);
+    };
+    let mut fields = Fields(rest);
+    let message = match tag {
+        TAG_QUIT => Some(Message::Quit),
+        TAG_MOVE => fields
+            .i32()
+            .zip(fields.i32())
+            .map(|(x, y)| Message::Move { x, y }),
+        TAG_WRITE => match fields.u32().and_then(|len| fields.take(len as usize)) {
+            Some(text) => {
+                let text = std::str::from_utf8(text).map_err(DecodeError::InvalidUtf8)?;
+                Some(Message::Write(text.to_string()))
+            }
+            None => None,
+        },
+        TAG_CHANGE_COLOR => match (fields.i32(), fields.i32(), fields.i32()) {
+            (Some(r), Some(g), Some(b)) => Some(Message::ChangeColor(r, g, b)),
+            _ => None,
+        },
+        _ => return Err(DecodeError::UnknownTag(tag)),
+    };
+    Ok(message.map(|message| (message, bytes.len() - fields.0.len())))
+}
+
+/// 先頭のフレームのバイト数（ヘッダーがそろっていなければ `None`）
+///
+/// `Write` は文字列のバイト数まで届けば、文字列の本体が届く前に分かる
+fn frame_len(bytes: &[u8]) -> Result<Option<usize>, DecodeError> {
+    let Some((&tag, rest)) = bytes.split_first() else {
+        return Ok(None);
+    };
+    let len = match tag {
+        TAG_QUIT => Some(1),
+        TAG_MOVE => Some(1 + 2 * 4),
+        TAG_WRITE => Fields(rest)
+            .u32()
+            .map(|len| usize::try_from(len).map_or(usize::MAX, |len| len.saturating_add(1 + 4))),
+        TAG_CHANGE_COLOR => Some(1 + 3 * 4),
+        _ => return Err(DecodeError::UnknownTag(tag)),
+    };
+    Ok(len)
+}
+
+/// フレームのタグより後ろを先頭から読み進める（足りなければ `None`）
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (head, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)
+            .map(|bytes| bytes.try_into().expect("N バイト取り出した"))
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.array().map(i32::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+}
+
+/// `Read` からフレームを順に読んでメッセージに戻す
+///
+/// 読んだバイト列を溜めておき、フレームがそろうたびにメッセージを返す。
+/// `read` が1バイトずつしか返さなくても、フレームの途中で止まっても正しく読める。
+/// 上限より長いフレームは、ヘッダーを読んだところで `DecodeError::FrameTooLarge` にする。
+///
+/// ```
+/// use enums::Message;
+/// use enums::codec::{Decoder, encode};
+///
+/// let mut bytes = encode(&Message::Quit);
+/// bytes.extend(encode(&Message::ChangeColor(255, 0, 0)));
+/// let messages: Vec<Message> = Decoder::new(&bytes[..]).map(Result::unwrap).collect();
+/// assert_eq!(messages, [Message::Quit, Message::ChangeColor(255, 0, 0)]);
+/// ```
+#[derive(Debug)]
+pub struct Decoder<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// 受け付けるフレームの最大バイト数
+    max_len: usize,
+    /// エラーを返した後は何も返さない（フレームの区切りが分からなくなるため）
+    failed: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    /// `reader` から読むデコーダーを作る（フレームの上限は `DEFAULT_MAX_LEN` バイト）
+    pub fn new(reader: R) -> Self {
+        Self::with_max_len(reader, DEFAULT_MAX_LEN)
+    }
+
+    /// `reader` から読み、`max_len` バイトを超えるフレームをエラーにするデコーダーを作る
+    ///
+    /// ```
+    /// use enums::Message;
+    /// use enums::codec::{DecodeError, Decoder, ReadError, encode};
+    ///
+    /// let bytes = encode(&Message::Write("長い文字列".to_string()));
+    /// let mut decoder = Decoder::with_max_len(&bytes[..], 8);
+    /// assert!(matches!(
+    ///     decoder.read_message(),
+    ///     Err(ReadError::Decode(DecodeError::FrameTooLarge { len: 20, max_len: 8 }))
+    /// ));
+    /// ```
+    pub fn with_max_len(reader: R, max_len: usize) -> Self {
+        Decoder {
+            reader,
+            buffer: Vec::new(),
+            max_len,
+            failed: false,
+        }
+    }
+
+    /// 次のメッセージを読む
+    ///
+    /// フレームの区切りでデータが終われば `Ok(None)` を返す。
+    ///
+    /// # Errors
+    /// 読み込みに失敗した場合、フレームが正しくないか上限より長い場合、
+    /// フレームの途中でデータが終わった場合に `ReadError` を返す。その後は `Ok(None)` を返す
+    pub fn read_message(&mut self) -> Result<Option<Message>, ReadError> {
+        if self.failed {
+            return Ok(None);
+        }
+        let result = self.fill_and_decode();
+        self.failed = result.is_err();
+        result
+    }
+
+    /// まだメッセージにしていないバイト数
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// 元の `Read` を返す（溜めていたバイト列は捨てる）
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn fill_and_decode(&mut self) -> Result<Option<Message>, ReadError> {
+        let mut chunk = [0; READ_CHUNK];
+        loop {
+            // 長すぎるフレームは本体を溜め始める前に断る
+            if let Some(len) = frame_len(&self.buffer)?.filter(|&len| len > self.max_len) {
+                return Err(DecodeError::FrameTooLarge {
+                    len,
+                    max_len: self.max_len,
+                }
+                .into());
+            }
+            if let Some((message, used)) = decode_prefix(&self.buffer)? {
+                self.buffer.drain(..used);
+                return Ok(Some(message));
+            }
+            match self.reader.read(&mut chunk) {
+                Ok(0) if self.buffer.is_empty() => return Ok(None),
+                Ok(0) => return Err(DecodeError::UnexpectedEnd.into()),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = Result<Message, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_message().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_layout() {
+        assert_eq!(encode(&Message::Quit), [0x00]);
+        assert_eq!(
+            encode(&Message::Move { x: 10, y: -1 }),
+            [0x01, 0, 0, 0, 10, 0xff, 0xff, 0xff, 0xff]
+        );
+        assert_eq!(
+            encode(&Message::Write("é".to_string())),
+            [0x02, 0, 0, 0, 2, 0xc3, 0xa9]
+        );
+        assert_eq!(
+            encode(&Message::ChangeColor(255, 0, 256)),
+            [0x03, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(&[]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(decode(&[0x01, 0, 0, 0]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            decode(&[0x02, 0, 0, 0, 3, b'a']),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(decode(&[0x04]), Err(DecodeError::UnknownTag(0x04)));
+        assert_eq!(decode(&[0x00, 0x00]), Err(DecodeError::TrailingBytes(1)));
+        assert!(matches!(
+            decode(&[0x02, 0, 0, 0, 1, 0xff]),
+            Err(DecodeError::InvalidUtf8(_))
+        ));
+        assert_eq!(
+            DecodeError::UnknownTag(0xab).to_string(),
+            "不明なタグです: 0xab"
+        );
+    }
+
+    #[test]
+    fn test_decoder_stops_after_error() {
+        let bytes = [0x00, 0x07, 0x00];
+        let mut decoder = Decoder::new(&bytes[..]);
+        assert_eq!(decoder.read_message().unwrap(), Some(Message::Quit));
+        assert!(matches!(
+            decoder.read_message(),
+            Err(ReadError::Decode(DecodeError::UnknownTag(0x07)))
+        ));
+        assert!(decoder.next().is_none());
+    }
+
+    #[test]
+    fn test_frame_len() {
+        assert_eq!(frame_len(&[]), Ok(None));
+        assert_eq!(frame_len(&[0x00]), Ok(Some(1)));
+        assert_eq!(frame_len(&[0x01]), Ok(Some(9)));
+        assert_eq!(frame_len(&[0x02, 0, 0]), Ok(None));
+        assert_eq!(frame_len(&[0x02, 0, 0, 1, 0]), Ok(Some(261)));
+        assert_eq!(frame_len(&[0x03]), Ok(Some(13)));
+        assert_eq!(frame_len(&[0x04]), Err(DecodeError::UnknownTag(0x04)));
+        for message in [
+            Message::Quit,
+            Message::Move { x: 1, y: 2 },
+            Message::Write("é".to_string()),
+            Message::ChangeColor(1, 2, 3),
+        ] {
+            let bytes = encode(&message);
+            assert_eq!(frame_len(&bytes), Ok(Some(bytes.len())), "{message:?}");
+        }
+    }
+
+    #[test]
+    fn test_decoder_truncated_stream() {
+        let mut decoder = Decoder::new(&[0x00, 0x01, 0, 0][..]);
+        assert_eq!(decoder.read_message().unwrap(), Some(Message::Quit));
+        assert!(matches!(
+            decoder.read_message(),
+            Err(ReadError::Decode(DecodeError::UnexpectedEnd))
+        ));
+        assert_eq!(decoder.buffered(), 3);
+    }
+}
@@ -0,0 +1,437 @@
+//! `std::net` の型・バイト列・整数との変換と、serde による直列化
+//!
+//! `std::net::{IpAddr, Ipv4Addr, Ipv6Addr}`・`[u8; 4]`・`u32`・`[u8; 16]`・`[u16; 8]`・`u128`
+//! と相互に変換できる。バイト列と整数は上位バイトが先頭（ネットワークバイトオーダー）。
+//! 情報を失わない向きは `From`、`Ipv6Addr`（と `IpAddr`）からゾーン ID を持たない型への変換は
+//! `TryFrom` で、ゾーン ID があれば `ZoneError` になる。ゾーン ID を捨ててよいときは
+//! `without_zone()` で外してから変換する。
+//!
+//! `serde` 機能を有効にすると `Serialize` / `Deserialize` を実装する。JSON などの人が読む形式では
+//! 文字列（`"2001:db8::1"`）、それ以外の形式ではオクテットの並び（`IpAddr` は `V4` / `V6` の列挙型）にする。
+//!
+//! ```
+//! use enums::{IpAddr, Ipv4Addr};
+//! use std::net;
+//!
+//! let addr = Ipv4Addr::from([192, 0, 2, 1]);
+//! assert_eq!(u32::from(addr), 0xc000_0201);
+//! let std_addr = net::IpAddr::try_from(IpAddr::V4(addr)).unwrap();
+//! assert_eq!(std_addr, net::Ipv4Addr::new(192, 0, 2, 1));
+//! assert_eq!(IpAddr::from(std_addr), IpAddr::V4(addr));
+//!
+//! let scoped: IpAddr = "fe80::1%eth0".parse().unwrap();
+//! assert!(net::IpAddr::try_from(scoped).is_err());
+//! assert_eq!(net::IpAddr::try_from(scoped.without_zone()).unwrap().to_string(), "fe80::1");
+//! ```
+
+use crate::ip::{IpAddr, Ipv4Addr, Ipv6Addr, Zone};
+use std::fmt;
+use std::net;
+
+/// ゾーン ID を持つアドレスを、ゾーン ID を表せない型に変換しようとしたエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZoneError {
+    /// 変換できなかったアドレスのゾーン ID
+    pub zone: Zone,
+}
+
+impl fmt::Display for ZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ゾーン ID（%{}）を持つアドレスは変換できません（without_zone() で外せます）",
+            self.zone
+        )
+    }
+}
+
+impl std::error::Error for ZoneError {}
+
+/// ゾーン ID がなければ8つのグループを返す
+fn zoneless_segments(addr: Ipv6Addr) -> Result<[u16; 8], ZoneError> {
+    match addr.zone {
+        Some(zone) => Err(ZoneError { zone }),
+        None => Ok(addr.segments),
+    }
+}
+
+impl From<[u8; 4]> for Ipv4Addr {
+    fn from([a, b, c, d]: [u8; 4]) -> Self {
+        Ipv4Addr { a, b, c, d }
+    }
+}
+
+impl From<Ipv4Addr> for [u8; 4] {
+    fn from(addr: Ipv4Addr) -> Self {
+        addr.octets()
+    }
+}
+
+impl From<u32> for Ipv4Addr {
+    fn from(bits: u32) -> Self {
+        Ipv4Addr::from_bits(bits)
+    }
+}
+
+impl From<Ipv4Addr> for u32 {
+    fn from(addr: Ipv4Addr) -> Self {
+        addr.to_bits()
+    }
+}
+
+impl From<net::Ipv4Addr> for Ipv4Addr {
+    fn from(addr: net::Ipv4Addr) -> Self {
+        Ipv4Addr::from(addr.octets())
+    }
+}
+
+impl From<Ipv4Addr> for net::Ipv4Addr {
+    fn from(addr: Ipv4Addr) -> Self {
+        net::Ipv4Addr::from(addr.octets())
+    }
+}
+
+impl From<[u8; 16]> for Ipv6Addr {
+    fn from(octets: [u8; 16]) -> Self {
+        Ipv6Addr::from_bits(u128::from_be_bytes(octets))
+    }
+}
+
+impl TryFrom<Ipv6Addr> for [u8; 16] {
+    type Error = ZoneError;
+
+    fn try_from(addr: Ipv6Addr) -> Result<Self, ZoneError> {
+        zoneless_segments(addr).map(|segments| Ipv6Addr::new(segments).octets())
+    }
+}
+
+impl From<[u16; 8]> for Ipv6Addr {
+    fn from(segments: [u16; 8]) -> Self {
+        Ipv6Addr::new(segments)
+    }
+}
+
+impl TryFrom<Ipv6Addr> for [u16; 8] {
+    type Error = ZoneError;
+
+    fn try_from(addr: Ipv6Addr) -> Result<Self, ZoneError> {
+        zoneless_segments(addr)
+    }
+}
+
+impl From<u128> for Ipv6Addr {
+    fn from(bits: u128) -> Self {
+        Ipv6Addr::from_bits(bits)
+    }
+}
+
+impl TryFrom<Ipv6Addr> for u128 {
+    type Error = ZoneError;
+
+    fn try_from(addr: Ipv6Addr) -> Result<Self, ZoneError> {
+        zoneless_segments(addr).map(|segments| Ipv6Addr::new(segments).to_bits())
+    }
+}
+
+impl From<net::Ipv6Addr> for Ipv6Addr {
+    fn from(addr: net::Ipv6Addr) -> Self {
+        Ipv6Addr::new(addr.segments())
+    }
+}
+
+/// `std::net::Ipv6Addr` はゾーン ID を持たないので、ゾーン ID があれば失敗する
+impl TryFrom<Ipv6Addr> for net::Ipv6Addr {
+    type Error = ZoneError;
+
+    fn try_from(addr: Ipv6Addr) -> Result<Self, ZoneError> {
+        zoneless_segments(addr).map(net::Ipv6Addr::from)
+    }
+}
+
+impl From<Ipv4Addr> for IpAddr {
+    fn from(addr: Ipv4Addr) -> Self {
+        IpAddr::V4(addr)
+    }
+}
+
+impl From<Ipv6Addr> for IpAddr {
+    fn from(addr: Ipv6Addr) -> Self {
+        IpAddr::V6(addr)
+    }
+}
+
+impl From<[u8; 4]> for IpAddr {
+    fn from(octets: [u8; 4]) -> Self {
+        IpAddr::V4(octets.into())
+    }
+}
+
+impl From<[u8; 16]> for IpAddr {
+    fn from(octets: [u8; 16]) -> Self {
+        IpAddr::V6(octets.into())
+    }
+}
+
+impl From<[u16; 8]> for IpAddr {
+    fn from(segments: [u16; 8]) -> Self {
+        IpAddr::V6(segments.into())
+    }
+}
+
+impl From<net::Ipv4Addr> for IpAddr {
+    fn from(addr: net::Ipv4Addr) -> Self {
+        IpAddr::V4(addr.into())
+    }
+}
+
+impl From<net::Ipv6Addr> for IpAddr {
+    fn from(addr: net::Ipv6Addr) -> Self {
+        IpAddr::V6(addr.into())
+    }
+}
+
+impl From<net::IpAddr> for IpAddr {
+    fn from(addr: net::IpAddr) -> Self {
+        match addr {
+            net::IpAddr::V4(addr) => IpAddr::V4(addr.into()),
+            net::IpAddr::V6(addr) => IpAddr::V6(addr.into()),
+        }
+    }
+}
+
+impl TryFrom<IpAddr> for net::IpAddr {
+    type Error = ZoneError;
+
+    fn try_from(addr: IpAddr) -> Result<Self, ZoneError> {
+        match addr {
+            IpAddr::V4(addr) => Ok(net::IpAddr::V4(addr.into())),
+            IpAddr::V6(addr) => addr.try_into().map(net::IpAddr::V6),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use crate::ip::{IpAddr, Ipv4Addr, Ipv6Addr, Zone};
+    use serde::de::{self, Deserialize, Deserializer, EnumAccess, VariantAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::str::FromStr;
+
+    /// 人が読む形式の文字列を `FromStr` で解析する
+    struct FromStrVisitor<T>(&'static str, PhantomData<T>);
+
+    impl<T> Visitor<'_> for FromStrVisitor<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(self.0)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+            v.parse().map_err(|e| E::custom(format_args!("{v}: {e}")))
+        }
+    }
+
+    fn deserialize_str<'de, D, T>(deserializer: D, expecting: &'static str) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        deserializer.deserialize_str(FromStrVisitor(expecting, PhantomData))
+    }
+
+    impl Serialize for Ipv4Addr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                self.octets().serialize(serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Ipv4Addr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserialize_str(deserializer, "IPv4 アドレスの文字列")
+            } else {
+                <[u8; 4]>::deserialize(deserializer).map(Ipv4Addr::from)
+            }
+        }
+    }
+
+    /// ゾーン ID を失わないように、オクテットの後にゾーン ID を並べる
+    impl Serialize for Ipv6Addr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                (self.octets(), self.zone.as_deref()).serialize(serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Ipv6Addr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                return deserialize_str(deserializer, "IPv6 アドレスの文字列");
+            }
+            let (octets, zone) = <([u8; 16], Option<String>)>::deserialize(deserializer)?;
+            let zone = zone
+                .map(|zone| zone.parse::<Zone>().map_err(de::Error::custom))
+                .transpose()?;
+            Ok(Ipv6Addr {
+                zone,
+                ..Ipv6Addr::from(octets)
+            })
+        }
+    }
+
+    impl Serialize for IpAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                return serializer.collect_str(self);
+            }
+            match self {
+                IpAddr::V4(addr) => serializer.serialize_newtype_variant("IpAddr", 0, "V4", addr),
+                IpAddr::V6(addr) => serializer.serialize_newtype_variant("IpAddr", 1, "V6", addr),
+            }
+        }
+    }
+
+    /// `IpAddr` のバリアント名（番号でも名前でも受け付ける）
+    enum Variant {
+        V4,
+        V6,
+    }
+
+    const VARIANTS: &[&str] = &["V4", "V6"];
+
+    impl<'de> Deserialize<'de> for Variant {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct VariantVisitor;
+
+            impl Visitor<'_> for VariantVisitor {
+                type Value = Variant;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("`V4` か `V6`")
+                }
+
+                fn visit_u64<E: de::Error>(self, v: u64) -> Result<Variant, E> {
+                    match v {
+                        0 => Ok(Variant::V4),
+                        1 => Ok(Variant::V6),
+                        _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                    }
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Variant, E> {
+                    match v {
+                        "V4" => Ok(Variant::V4),
+                        "V6" => Ok(Variant::V6),
+                        _ => Err(E::unknown_variant(v, VARIANTS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(VariantVisitor)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for IpAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct IpAddrVisitor;
+
+            impl<'de> Visitor<'de> for IpAddrVisitor {
+                type Value = IpAddr;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("IPv4 か IPv6 のアドレス")
+                }
+
+                fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<IpAddr, A::Error> {
+                    match data.variant()? {
+                        (Variant::V4, addr) => addr.newtype_variant().map(IpAddr::V4),
+                        (Variant::V6, addr) => addr.newtype_variant().map(IpAddr::V6),
+                    }
+                }
+            }
+
+            if deserializer.is_human_readable() {
+                deserialize_str(deserializer, "IP アドレスの文字列")
+            } else {
+                deserializer.deserialize_enum("IpAddr", VARIANTS, IpAddrVisitor)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_conversions() {
+        let addr = Ipv4Addr::new(192, 0, 2, 1);
+        assert_eq!(Ipv4Addr::from([192, 0, 2, 1]), addr);
+        assert_eq!(<[u8; 4]>::from(addr), [192, 0, 2, 1]);
+        assert_eq!(Ipv4Addr::from(0xc000_0201), addr);
+        assert_eq!(u32::from(addr), 0xc000_0201);
+        assert_eq!(net::Ipv4Addr::from(addr), net::Ipv4Addr::new(192, 0, 2, 1));
+        assert_eq!(Ipv4Addr::from(net::Ipv4Addr::new(192, 0, 2, 1)), addr);
+        assert_eq!(IpAddr::from([192, 0, 2, 1]), IpAddr::V4(addr));
+    }
+
+    #[test]
+    fn test_ipv6_conversions() {
+        let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let octets = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        assert_eq!(Ipv6Addr::from(octets), addr);
+        assert_eq!(<[u8; 16]>::try_from(addr), Ok(octets));
+        assert_eq!(Ipv6Addr::from([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]), addr);
+        assert_eq!(Ipv6Addr::from(0x2001_0db8_u128 << 96 | 1), addr);
+        assert_eq!(u128::try_from(addr), Ok(0x2001_0db8_u128 << 96 | 1));
+        assert_eq!(
+            <[u16; 8]>::try_from(addr),
+            Ok([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1])
+        );
+        let std_addr = net::Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+        assert_eq!(net::Ipv6Addr::try_from(addr), Ok(std_addr));
+        assert_eq!(IpAddr::from(std_addr), IpAddr::V6(addr));
+    }
+
+    #[test]
+    fn test_zone_is_not_dropped() {
+        let scoped: Ipv6Addr = "fe80::1%eth0".parse().unwrap();
+        let error = ZoneError {
+            zone: "eth0".parse().unwrap(),
+        };
+        assert_eq!(<[u8; 16]>::try_from(scoped), Err(error));
+        assert_eq!(<[u16; 8]>::try_from(scoped), Err(error));
+        assert_eq!(u128::try_from(scoped), Err(error));
+        assert_eq!(net::Ipv6Addr::try_from(scoped), Err(error));
+        assert_eq!(net::IpAddr::try_from(IpAddr::V6(scoped)), Err(error));
+        assert_eq!(
+            error.to_string(),
+            "ゾーン ID（%eth0）を持つアドレスは変換できません（without_zone() で外せます）"
+        );
+
+        // 明示的に外せば変換でき、戻すとゾーン ID のないアドレスになる
+        let std_addr = net::IpAddr::try_from(IpAddr::V6(scoped).without_zone()).unwrap();
+        assert_eq!(std_addr, "fe80::1".parse::<net::IpAddr>().unwrap());
+        assert_eq!(IpAddr::from(std_addr), "fe80::1".parse().unwrap());
+        assert_eq!(scoped.without_zone().zone, None);
+        assert_eq!(
+            scoped.to_bits(),
+            u128::try_from(scoped.without_zone()).unwrap()
+        );
+    }
+}
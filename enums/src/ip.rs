@@ -0,0 +1,814 @@
+//! IP アドレスの型と文字列からの解析
+//!
+//! `std::net` と同じ規則で文字列を解析する。IPv4 は10進数4つのドット区切り（先頭の 0 は不可）、
+//! IPv6 は 16進数8グループのコロン区切りで、`::` による0の省略、末尾32ビットへの IPv4 の埋め込み
+//! （`::ffff:1.2.3.4`）に対応する。`std::net` と違い、IPv6 のゾーン ID（`fe80::1%eth0`）も受け付ける。
+//!
+//! ゾーン ID は固定長の `Zone` に持つので、`Ipv6Addr`・`IpAddr` も `Ipv4Addr` と同じく `Copy` になる。
+//!
+//! 表示は `std::net` と同じく、IPv6 を RFC 5952 の正規形（小文字、最も長い 0 の並びを `::` に省略、
+//! IPv4 射影アドレスは `::ffff:1.2.3.4`）で行う。`{:#}` では IPv6 を省略せずに4桁ずつ表示する。
+
+use std::fmt::{self, Write};
+use std::ops::{Deref, Range};
+use std::str::FromStr;
+
+/// IPv4 アドレスを表す構造体
+/// 4つの 8ビット整数（オクテット）で構成される
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv4Addr {
+    pub a: u8,
+    pub b: u8,
+    pub c: u8,
+    pub d: u8,
+}
+
+/// IPv6 アドレスを表す構造体
+/// 8つの 16ビット整数で構成される
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv6Addr {
+    pub segments: [u16; 8],
+    /// ゾーン ID（`fe80::1%eth0` の `eth0`）
+    pub zone: Option<Zone>,
+}
+
+/// IPv6 アドレスのゾーン ID（`fe80::1%eth0` の `eth0`）
+///
+/// RFC 3986 の unreserved な ASCII 文字を `Zone::MAX_LEN` バイトまで、固定長の配列に持つ。
+/// `Deref<Target = str>` なので文字列として扱える。
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Zone {
+    /// 先頭 `len` バイトがゾーン ID、残りは 0
+    bytes: [u8; Zone::MAX_LEN],
+    len: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IpAddr {
+    V4(Ipv4Addr),
+    V6(Ipv6Addr),
+}
+
+/// アドレスの解析エラー
+///
+/// `position` は入力の先頭からのバイト位置（0 始まり）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// 入力が空
+    Empty,
+    /// アドレスに使えない文字
+    InvalidCharacter { position: usize, ch: char },
+    /// IPv4 のオクテットが空（`1..2.3` など）
+    EmptyOctet { position: usize },
+    /// IPv4 のオクテットが 255 を超える
+    OctetOverflow { position: usize },
+    /// IPv4 のオクテットの先頭に 0 がある（8進数と紛らわしいので受け付けない）
+    LeadingZero { position: usize },
+    /// IPv4 のオクテットが4つではない
+    WrongOctetCount { found: usize },
+    /// IPv6 のグループが空（`::` 以外で `:` が続く、先頭や末尾が `:` 1つ）
+    EmptyGroup { position: usize },
+    /// IPv6 のグループが 16進数4桁を超える
+    GroupTooLong { position: usize },
+    /// IPv6 のグループが8つを超える（`::` は少なくとも1グループを表す）
+    TooManyGroups,
+    /// `::` がないのに IPv6 のグループが8つに足りない
+    TooFewGroups { found: usize },
+    /// `::` が2回以上ある
+    MultipleCompressions { position: usize },
+    /// IPv4 を埋め込めるのは IPv6 アドレスの末尾だけ
+    MisplacedIpv4 { position: usize },
+    /// `%` の後にゾーン ID がない
+    EmptyZone { position: usize },
+    /// ゾーン ID が `Zone::MAX_LEN` バイトを超える（`position` はゾーン ID の開始位置）
+    ZoneTooLong { position: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "アドレスが空です"),
+            ParseError::InvalidCharacter { position, ch } => {
+                write!(f, "位置 {position}: '{ch}' はアドレスに使えません")
+            }
+            ParseError::EmptyOctet { position } => {
+                write!(f, "位置 {position}: オクテットが空です")
+            }
+            ParseError::OctetOverflow { position } => {
+                write!(f, "位置 {position}: オクテットが 255 を超えています")
+            }
+            ParseError::LeadingZero { position } => {
+                write!(f, "位置 {position}: オクテットの先頭に 0 があります")
+            }
+            ParseError::WrongOctetCount { found } => {
+                write!(f, "IPv4 のオクテットは4つ必要です（{found} 個あります）")
+            }
+            ParseError::EmptyGroup { position } => write!(f, "位置 {position}: グループが空です"),
+            ParseError::GroupTooLong { position } => {
+                write!(f, "位置 {position}: グループが 16進数4桁を超えています")
+            }
+            ParseError::TooManyGroups => write!(f, "IPv6 のグループが多すぎます"),
+            ParseError::TooFewGroups { found } => {
+                write!(f, "IPv6 のグループは8つ必要です（{found} 個あります）")
+            }
+            ParseError::MultipleCompressions { position } => {
+                write!(f, "位置 {position}: `::` は1回しか使えません")
+            }
+            ParseError::MisplacedIpv4 { position } => {
+                write!(f, "位置 {position}: IPv4 を埋め込めるのは末尾だけです")
+            }
+            ParseError::EmptyZone { position } => {
+                write!(f, "位置 {position}: ゾーン ID が空です")
+            }
+            ParseError::ZoneTooLong { position } => {
+                write!(
+                    f,
+                    "位置 {position}: ゾーン ID が {} バイトを超えています",
+                    Zone::MAX_LEN
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Ipv4Addr {
+    /// 4つのオクテットからアドレスを作る
+    #[must_use]
+    pub const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Ipv4Addr { a, b, c, d }
+    }
+
+    /// 4つのオクテットを返す
+    #[must_use]
+    pub const fn octets(&self) -> [u8; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+
+    /// 32ビットの整数からアドレスを作る（上位バイトが先頭のオクテット）
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        let [a, b, c, d] = bits.to_be_bytes();
+        Ipv4Addr { a, b, c, d }
+    }
+
+    /// アドレスを 32ビットの整数にする
+    #[must_use]
+    pub const fn to_bits(&self) -> u32 {
+        u32::from_be_bytes(self.octets())
+    }
+}
+
+impl Ipv6Addr {
+    /// 8つのグループからアドレスを作る（ゾーン ID なし）
+    #[must_use]
+    pub const fn new(segments: [u16; 8]) -> Self {
+        Ipv6Addr {
+            segments,
+            zone: None,
+        }
+    }
+
+    /// 128ビットの整数からアドレスを作る（ゾーン ID なし）
+    #[must_use]
+    pub const fn from_bits(bits: u128) -> Self {
+        let mut segments = [0; 8];
+        let mut i = 0;
+        while i < segments.len() {
+            #[allow(clippy::cast_possible_truncation)] // 16ビットずつ取り出す
+            let segment = (bits >> (112 - 16 * i)) as u16;
+            segments[i] = segment;
+            i += 1;
+        }
+        Ipv6Addr::new(segments)
+    }
+
+    /// 16個のオクテットを返す（ゾーン ID は含まない）
+    #[must_use]
+    pub const fn octets(&self) -> [u8; 16] {
+        self.to_bits().to_be_bytes()
+    }
+
+    /// アドレスを 128ビットの整数にする（ゾーン ID は含まない）
+    #[must_use]
+    pub const fn to_bits(&self) -> u128 {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < self.segments.len() {
+            bits = (bits << 16) | self.segments[i] as u128;
+            i += 1;
+        }
+        bits
+    }
+
+    /// ゾーン ID を外したアドレスを返す
+    ///
+    /// `std::net::Ipv6Addr` や整数への変換はゾーン ID があると失敗するので、捨ててよいときはこれを通す。
+    ///
+    /// ```
+    /// use enums::Ipv6Addr;
+    /// use std::net;
+    ///
+    /// let scoped: Ipv6Addr = "fe80::1%eth0".parse().unwrap();
+    /// assert!(net::Ipv6Addr::try_from(scoped).is_err());
+    /// let addr = net::Ipv6Addr::try_from(scoped.without_zone()).unwrap();
+    /// assert_eq!(addr, net::Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+    /// ```
+    #[must_use]
+    pub const fn without_zone(self) -> Self {
+        Ipv6Addr::new(self.segments)
+    }
+
+    /// IPv4 射影アドレス（`::ffff:a.b.c.d`）なら埋め込まれた IPv4 アドレスを返す
+    #[must_use]
+    pub const fn to_ipv4_mapped(&self) -> Option<Ipv4Addr> {
+        match self.segments {
+            [0, 0, 0, 0, 0, 0xffff, ab, cd] => {
+                let [a, b] = ab.to_be_bytes();
+                let [c, d] = cd.to_be_bytes();
+                Some(Ipv4Addr { a, b, c, d })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl IpAddr {
+    /// IPv6 ならゾーン ID を外したアドレスを返す（IPv4 はそのまま）
+    #[must_use]
+    pub const fn without_zone(self) -> Self {
+        match self {
+            IpAddr::V4(addr) => IpAddr::V4(addr),
+            IpAddr::V6(addr) => IpAddr::V6(addr.without_zone()),
+        }
+    }
+}
+
+impl Zone {
+    /// ゾーン ID の最大のバイト数（Linux のインターフェース名の上限 `IFNAMSIZ - 1` と同じ）
+    pub const MAX_LEN: usize = 15;
+
+    /// ゾーン ID の文字列
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // 解析で ASCII の文字だけを入れるので、UTF-8 として失敗することはない
+        std::str::from_utf8(&self.bytes[..usize::from(self.len)]).unwrap_or_default()
+    }
+}
+
+impl Deref for Zone {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// 文字列と同じく `"eth0"` と表示する
+impl fmt::Debug for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for Zone {
+    type Err = ParseError;
+
+    /// `%` を除いたゾーン ID を解析する
+    ///
+    /// ```
+    /// use enums::Zone;
+    ///
+    /// let zone: Zone = "eth0".parse().unwrap();
+    /// assert_eq!(&*zone, "eth0");
+    /// assert!("a-very-long-interface".parse::<Zone>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_zone(s, 0)
+    }
+}
+
+/// 最も長い 0 のグループの並び（2つ以上）の範囲を返す（同じ長さなら先の方）
+fn longest_zero_run(segments: &[u16; 8]) -> Option<Range<usize>> {
+    let mut longest: Option<Range<usize>> = None;
+    let mut start = 0;
+    while start < segments.len() {
+        if segments[start] != 0 {
+            start += 1;
+            continue;
+        }
+        let end = segments[start..]
+            .iter()
+            .position(|&s| s != 0)
+            .map_or(segments.len(), |len| start + len);
+        if end - start >= 2 && longest.as_ref().is_none_or(|run| end - start > run.len()) {
+            longest = Some(start..end);
+        }
+        start = end;
+    }
+    longest
+}
+
+/// グループを `:` でつないで書き出す
+fn write_groups(out: &mut String, segments: &[u16]) -> fmt::Result {
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            out.push(':');
+        }
+        write!(out, "{segment:x}")?;
+    }
+    Ok(())
+}
+
+/// 幅や寄せの指定（`{:>15}` など）は `std::net` と同じくアドレス全体に適用する
+impl fmt::Display for Ipv4Addr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{}.{}.{}.{}", self.a, self.b, self.c, self.d))
+    }
+}
+
+/// RFC 5952 の正規形で表示する（`{:#}` なら `0000:0000:...` の省略しない形）
+impl fmt::Display for Ipv6Addr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut text = String::new();
+        if f.alternate() {
+            for (i, segment) in self.segments.iter().enumerate() {
+                if i > 0 {
+                    text.push(':');
+                }
+                write!(text, "{segment:04x}")?;
+            }
+        } else if let Some(v4) = self.to_ipv4_mapped() {
+            write!(text, "::ffff:{v4}")?;
+        } else if let Some(run) = longest_zero_run(&self.segments) {
+            write_groups(&mut text, &self.segments[..run.start])?;
+            text.push_str("::");
+            write_groups(&mut text, &self.segments[run.end..])?;
+        } else {
+            write_groups(&mut text, &self.segments)?;
+        }
+        if let Some(zone) = &self.zone {
+            write!(text, "%{zone}")?;
+        }
+        f.pad(&text)
+    }
+}
+
+/// `IpAddr` enum に Display トレイトを実装
+impl fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpAddr::V4(addr) => addr.fmt(f),
+            IpAddr::V6(addr) => addr.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Ipv4Addr {
+    type Err = ParseError;
+
+    /// `192.168.0.1` の形式を解析する
+    ///
+    /// ```
+    /// use enums::Ipv4Addr;
+    ///
+    /// assert_eq!("192.168.0.1".parse(), Ok(Ipv4Addr::new(192, 168, 0, 1)));
+    /// assert!("192.168.0.256".parse::<Ipv4Addr>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let octets = parse_octets(s, 0)?;
+        Ok(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+    }
+}
+
+impl FromStr for Ipv6Addr {
+    type Err = ParseError;
+
+    /// `2001:db8::1`・`::ffff:192.0.2.1`・`fe80::1%eth0` などの形式を解析する
+    ///
+    /// ```
+    /// use enums::Ipv6Addr;
+    ///
+    /// let addr: Ipv6Addr = "::ffff:192.0.2.1".parse().unwrap();
+    /// assert_eq!(addr.segments, [0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0201]);
+    ///
+    /// let scoped: Ipv6Addr = "fe80::1%eth0".parse().unwrap();
+    /// assert_eq!(scoped.zone.as_deref(), Some("eth0"));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let (addr, zone) = match s.find('%') {
+            Some(i) => (&s[..i], Some(parse_zone(&s[i + 1..], i + 1)?)),
+            None => (s, None),
+        };
+        Ok(Ipv6Addr {
+            segments: parse_segments(addr)?,
+            zone,
+        })
+    }
+}
+
+impl FromStr for IpAddr {
+    type Err = ParseError;
+
+    /// `:` を含めば IPv6、含まなければ IPv4 として解析する
+    ///
+    /// ```
+    /// use enums::IpAddr;
+    ///
+    /// assert!(matches!("127.0.0.1".parse(), Ok(IpAddr::V4(_))));
+    /// assert!(matches!("::1".parse(), Ok(IpAddr::V6(_))));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains(':') {
+            s.parse().map(IpAddr::V6)
+        } else {
+            s.parse().map(IpAddr::V4)
+        }
+    }
+}
+
+/// `valid` を満たさない文字があれば、その位置の `InvalidCharacter` を返す
+fn check_chars(part: &str, offset: usize, valid: impl Fn(char) -> bool) -> Result<(), ParseError> {
+    match part.char_indices().find(|&(_, ch)| !valid(ch)) {
+        Some((i, ch)) => Err(ParseError::InvalidCharacter {
+            position: offset + i,
+            ch,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// `a.b.c.d` を解析する（`offset` は入力全体の中での `s` の開始位置）
+fn parse_octets(s: &str, offset: usize) -> Result<[u8; 4], ParseError> {
+    let mut octets = [0; 4];
+    let mut position = offset;
+    for (i, part) in s.split('.').enumerate() {
+        if i == octets.len() {
+            return Err(ParseError::WrongOctetCount {
+                found: s.split('.').count(),
+            });
+        }
+        octets[i] = parse_octet(part, position)?;
+        position += part.len() + 1;
+    }
+    let found = s.split('.').count();
+    if found != octets.len() {
+        return Err(ParseError::WrongOctetCount { found });
+    }
+    Ok(octets)
+}
+
+fn parse_octet(part: &str, position: usize) -> Result<u8, ParseError> {
+    if part.is_empty() {
+        return Err(ParseError::EmptyOctet { position });
+    }
+    check_chars(part, position, |ch| ch.is_ascii_digit())?;
+    if part.len() > 1 && part.starts_with('0') {
+        return Err(ParseError::LeadingZero { position });
+    }
+    part.parse()
+        .map_err(|_| ParseError::OctetOverflow { position })
+}
+
+/// ゾーン ID を解析する（RFC 3986 の unreserved な文字だけを受け付ける）
+fn parse_zone(zone: &str, position: usize) -> Result<Zone, ParseError> {
+    if zone.is_empty() {
+        return Err(ParseError::EmptyZone { position });
+    }
+    check_chars(zone, position, |ch| {
+        ch.is_ascii_alphanumeric() || matches!(ch, '-' | '.' | '_' | '~')
+    })?;
+    if zone.len() > Zone::MAX_LEN {
+        return Err(ParseError::ZoneTooLong { position });
+    }
+    let mut bytes = [0; Zone::MAX_LEN];
+    bytes[..zone.len()].copy_from_slice(zone.as_bytes());
+    Ok(Zone {
+        bytes,
+        len: u8::try_from(zone.len()).expect("MAX_LEN 以下"),
+    })
+}
+
+/// ゾーン ID を除いた IPv6 アドレスを8つのグループに解析する
+fn parse_segments(s: &str) -> Result<[u16; 8], ParseError> {
+    let mut segments = [0; 8];
+    if let Some(i) = s.find("::") {
+        let tail_start = i + 2;
+        if let Some(j) = s[tail_start..].find("::") {
+            return Err(ParseError::MultipleCompressions {
+                position: tail_start + j,
+            });
+        }
+        let head = parse_groups(&s[..i], 0, false)?;
+        let tail = parse_groups(&s[tail_start..], tail_start, true)?;
+        // `::` は少なくとも1つの 0 のグループを表す
+        if head.len() + tail.len() >= segments.len() {
+            return Err(ParseError::TooManyGroups);
+        }
+        segments[..head.len()].copy_from_slice(&head);
+        let zeros_end = segments.len() - tail.len();
+        segments[zeros_end..].copy_from_slice(&tail);
+    } else {
+        let groups = parse_groups(s, 0, true)?;
+        if groups.len() > segments.len() {
+            return Err(ParseError::TooManyGroups);
+        }
+        if groups.len() < segments.len() {
+            return Err(ParseError::TooFewGroups {
+                found: groups.len(),
+            });
+        }
+        segments.copy_from_slice(&groups);
+    }
+    Ok(segments)
+}
+
+/// `:` 区切りのグループを解析する
+///
+/// `ipv4_at_end` が真なら、最後のグループに埋め込まれた IPv4 を2グループとして受け付ける
+fn parse_groups(s: &str, offset: usize, ipv4_at_end: bool) -> Result<Vec<u16>, ParseError> {
+    let mut groups = Vec::new();
+    if s.is_empty() {
+        return Ok(groups);
+    }
+    let count = s.split(':').count();
+    let mut position = offset;
+    for (i, group) in s.split(':').enumerate() {
+        if group.contains('.') {
+            if !ipv4_at_end || i + 1 != count {
+                return Err(ParseError::MisplacedIpv4 { position });
+            }
+            let octets = parse_octets(group, position)?;
+            groups.push(u16::from_be_bytes([octets[0], octets[1]]));
+            groups.push(u16::from_be_bytes([octets[2], octets[3]]));
+            break;
+        }
+        if group.is_empty() {
+            return Err(ParseError::EmptyGroup { position });
+        }
+        check_chars(group, position, |ch| ch.is_ascii_hexdigit())?;
+        if group.len() > 4 {
+            return Err(ParseError::GroupTooLong { position });
+        }
+        groups.push(u16::from_str_radix(group, 16).expect("16進数4桁以内"));
+        position += group.len() + 1;
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v6(s: &str) -> Result<[u16; 8], ParseError> {
+        s.parse::<Ipv6Addr>().map(|addr| addr.segments)
+    }
+
+    #[test]
+    fn test_parse_ipv4() {
+        assert_eq!("127.0.0.1".parse(), Ok(Ipv4Addr::new(127, 0, 0, 1)));
+        assert_eq!("0.0.0.0".parse(), Ok(Ipv4Addr::new(0, 0, 0, 0)));
+        assert_eq!(
+            "255.255.255.255".parse(),
+            Ok(Ipv4Addr::new(255, 255, 255, 255))
+        );
+    }
+
+    #[test]
+    fn test_parse_ipv4_errors() {
+        let cases = [
+            ("", ParseError::Empty),
+            ("1.2.3", ParseError::WrongOctetCount { found: 3 }),
+            ("1.2.3.4.5", ParseError::WrongOctetCount { found: 5 }),
+            ("1..3.4", ParseError::EmptyOctet { position: 2 }),
+            ("1.2.3.", ParseError::EmptyOctet { position: 6 }),
+            ("1.2.256.4", ParseError::OctetOverflow { position: 4 }),
+            ("1.2.03.4", ParseError::LeadingZero { position: 4 }),
+            (
+                "1.2.3.x",
+                ParseError::InvalidCharacter {
+                    position: 6,
+                    ch: 'x',
+                },
+            ),
+            (
+                " 1.2.3.4",
+                ParseError::InvalidCharacter {
+                    position: 0,
+                    ch: ' ',
+                },
+            ),
+            (
+                "1.2.3.４",
+                ParseError::InvalidCharacter {
+                    position: 6,
+                    ch: '４',
+                },
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<Ipv4Addr>(), Err(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_ipv6() {
+        assert_eq!(v6("::"), Ok([0; 8]));
+        assert_eq!(v6("::1"), Ok([0, 0, 0, 0, 0, 0, 0, 1]));
+        assert_eq!(v6("1::"), Ok([1, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(
+            v6("2001:DB8::8:800:200C:417A"),
+            Ok([0x2001, 0xdb8, 0, 0, 0x8, 0x800, 0x200c, 0x417a])
+        );
+        assert_eq!(v6("1:2:3:4:5:6:7:8"), Ok([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(v6("1:2:3:4:5:6:7::"), Ok([1, 2, 3, 4, 5, 6, 7, 0]));
+        assert_eq!(v6("::2:3:4:5:6:7:8"), Ok([0, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(
+            v6("::ffff:1.2.3.4"),
+            Ok([0, 0, 0, 0, 0, 0xffff, 0x0102, 0x0304])
+        );
+        assert_eq!(
+            v6("1:2:3:4:5:6:1.2.3.4"),
+            Ok([1, 2, 3, 4, 5, 6, 0x0102, 0x0304])
+        );
+    }
+
+    #[test]
+    fn test_parse_ipv6_zone() {
+        let addr: Ipv6Addr = "fe80::1%eth0".parse().unwrap();
+        assert_eq!(addr.segments, [0xfe80, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(addr.zone.as_deref(), Some("eth0"));
+        assert_eq!(
+            "fe80::1%".parse::<Ipv6Addr>(),
+            Err(ParseError::EmptyZone { position: 8 })
+        );
+        assert_eq!(
+            "fe80::1%eth/0".parse::<Ipv6Addr>(),
+            Err(ParseError::InvalidCharacter {
+                position: 11,
+                ch: '/'
+            })
+        );
+        let longest = "a".repeat(Zone::MAX_LEN);
+        let addr: Ipv6Addr = format!("fe80::1%{longest}").parse().unwrap();
+        assert_eq!(addr.zone.as_deref(), Some(longest.as_str()));
+        assert_eq!(
+            format!("fe80::1%{longest}b").parse::<Ipv6Addr>(),
+            Err(ParseError::ZoneTooLong { position: 8 })
+        );
+        assert_eq!(
+            "fe80::1%a%b".parse::<Ipv6Addr>(),
+            Err(ParseError::InvalidCharacter {
+                position: 9,
+                ch: '%'
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ipv6_errors() {
+        let cases = [
+            ("", ParseError::Empty),
+            ("1:2:3:4:5:6:7", ParseError::TooFewGroups { found: 7 }),
+            ("1:2:3:4:5:6:7:8:9", ParseError::TooManyGroups),
+            ("1:2:3:4:5:6:7:8::", ParseError::TooManyGroups),
+            ("1:2:3:4::5:6:7:8", ParseError::TooManyGroups),
+            ("1::2::3", ParseError::MultipleCompressions { position: 4 }),
+            (":::", ParseError::EmptyGroup { position: 2 }),
+            (":1::2", ParseError::EmptyGroup { position: 0 }),
+            ("1::2:", ParseError::EmptyGroup { position: 5 }),
+            ("12345::", ParseError::GroupTooLong { position: 0 }),
+            ("1.2.3.4::", ParseError::MisplacedIpv4 { position: 0 }),
+            ("::1.2.3.4:5", ParseError::MisplacedIpv4 { position: 2 }),
+            ("::1.2.3.256", ParseError::OctetOverflow { position: 8 }),
+            ("1:2:3:4:5:6:7:1.2.3.4", ParseError::TooManyGroups),
+            (
+                "::g",
+                ParseError::InvalidCharacter {
+                    position: 2,
+                    ch: 'g',
+                },
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(v6(input), Err(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_ip_addr_dispatch() {
+        assert_eq!(
+            "10.0.0.1".parse(),
+            Ok(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+        );
+        assert_eq!(
+            "::1".parse(),
+            Ok(IpAddr::V6(Ipv6Addr::new([0, 0, 0, 0, 0, 0, 0, 1])))
+        );
+        assert_eq!("".parse::<IpAddr>(), Err(ParseError::Empty));
+        assert_eq!(
+            "10.0.0.1%eth0".parse::<IpAddr>(),
+            Err(ParseError::InvalidCharacter {
+                position: 8,
+                ch: '%'
+            })
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for input in [
+            "192.0.2.1",
+            "1:2:3:4:5:6:7:8",
+            "fe80::1%eth0",
+            "::ffff:1.2.3.4",
+        ] {
+            let addr: IpAddr = input.parse().unwrap();
+            assert_eq!(addr.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_display_rfc5952() {
+        // RFC 5952 4章の例
+        let cases = [
+            ("2001:0db8:0000:0000:0000:0000:0000:0001", "2001:db8::1"),
+            ("2001:db8:0:0:0:0:2:1", "2001:db8::2:1"),
+            ("2001:db8:0:1:1:1:1:1", "2001:db8:0:1:1:1:1:1"),
+            ("2001:0:0:1:0:0:0:1", "2001:0:0:1::1"),
+            ("2001:db8:0:0:1:0:0:1", "2001:db8::1:0:0:1"),
+            ("2001:DB8::AAAA", "2001:db8::aaaa"),
+            ("0:0:0:0:0:0:0:0", "::"),
+            ("0:0:0:0:0:0:0:1", "::1"),
+            ("1:0:0:0:0:0:0:0", "1::"),
+            ("0:0:0:0:0:ffff:c000:0201", "::ffff:192.0.2.1"),
+            ("::c000:201", "::c000:201"),
+            ("::ffff:0:c000:201", "::ffff:0:c000:201"),
+        ];
+        for (input, expected) in cases {
+            let addr: Ipv6Addr = input.parse().unwrap();
+            assert_eq!(addr.to_string(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_display_alternate_and_padding() {
+        let addr: IpAddr = "2001:db8::1%eth0".parse().unwrap();
+        assert_eq!(
+            format!("{addr:#}"),
+            "2001:0db8:0000:0000:0000:0000:0000:0001%eth0"
+        );
+        assert_eq!(
+            format!(
+                "{:#}",
+                Ipv6Addr::new([0, 0, 0, 0, 0, 0xffff, 0xc000, 0x201])
+            ),
+            "0000:0000:0000:0000:0000:ffff:c000:0201"
+        );
+        assert_eq!(
+            format!("[{:>12}]", IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            "[    10.0.0.1]"
+        );
+        assert_eq!(format!("[{:<6}]", Ipv6Addr::new([0; 8])), "[::    ]");
+        assert_eq!(
+            format!("[{:*^7}]", Ipv6Addr::new([0, 0, 0, 0, 0, 0, 0, 1])),
+            "[**::1**]"
+        );
+    }
+
+    #[test]
+    fn test_bits_round_trip() {
+        let v4 = Ipv4Addr::new(192, 0, 2, 1);
+        assert_eq!(v4.to_bits(), 0xc000_0201);
+        assert_eq!(Ipv4Addr::from_bits(0xc000_0201), v4);
+        let v6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        assert_eq!(v6.to_bits(), 0x2001_0db8_0000_0000_0000_0000_0000_0001);
+        assert_eq!(Ipv6Addr::from_bits(v6.to_bits()), v6);
+        assert_eq!(Ipv6Addr::from_bits(u128::MAX).segments, [0xffff; 8]);
+        assert_eq!(
+            v6.octets(),
+            [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_to_ipv4_mapped() {
+        let mapped: Ipv6Addr = "::ffff:10.0.0.1".parse().unwrap();
+        assert_eq!(mapped.to_ipv4_mapped(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+        let compatible: Ipv6Addr = "::10.0.0.1".parse().unwrap();
+        assert_eq!(compatible.to_ipv4_mapped(), None);
+    }
+}
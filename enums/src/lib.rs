@@ -0,0 +1,31 @@
+//! 列挙型のサンプル
+//!
+//! IPv4 / IPv6 アドレスを表す `IpAddr` と、その文字列からの解析、
+//! CIDR 表記のネットワーク `IpNetwork` とサブネットの計算、IANA の特別用途アドレスの登録表による
+//! アドレスの分類を提供する。`std::net` の型や整数との変換、`serde` 機能による直列化にも対応する。
+//! また、いろいろな形のバリアントを持つ `Message` と、その文字列・バイナリ形式との変換、
+//! メッセージを1行ずつ読んで実行する REPL を提供する。
+//!
+//! ```
+//! use enums::IpAddr;
+//!
+//! let home: IpAddr = "127.0.0.1".parse().unwrap();
+//! let loopback: IpAddr = "::1".parse().unwrap();
+//! assert_eq!(home.to_string(), "127.0.0.1");
+//! assert!(matches!(loopback, IpAddr::V6(_)));
+//! ```
+
+pub mod cli;
+pub mod codec;
+pub mod convert;
+pub mod ip;
+pub mod message;
+pub mod network;
+pub mod repl;
+pub mod special;
+
+pub use convert::ZoneError;
+pub use ip::{IpAddr, Ipv4Addr, Ipv6Addr, ParseError, Zone};
+pub use message::{Message, ParseMessageError};
+pub use network::{IpNetwork, NetworkError};
+pub use special::{Purpose, Scope, SpecialPurpose};
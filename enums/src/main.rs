@@ -1,57 +1,9 @@
+use enums::cli::{self, Command, SubnetCommand};
+use enums::network::{self, IpNetwork, NetworkError};
+use enums::{IpAddr, Ipv4Addr, Ipv6Addr, Message};
+use enums::{codec, repl};
 use std::fmt;
-
-/// IPv4 アドレスを表す構造体
-/// 4つの 8ビット整数（オクテット）で構成される
-struct Ipv4Addr {
-    a: u8,
-    b: u8,
-    c: u8,
-    d: u8,
-}
-
-/// IPv6 アドレスを表す構造体
-/// 8つの 16ビット整数で構成される
-struct Ipv6Addr {
-    segments: [u16; 8],
-}
-
-enum IpAddr {
-    V4(Ipv4Addr),
-    V6(Ipv6Addr),
-}
-
-/// IpAddr enum に Display トレイトを実装
-impl fmt::Display for IpAddr {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            IpAddr::V4(addr) => write!(f, "{}.{}.{}.{}", addr.a, addr.b, addr.c, addr.d),
-            IpAddr::V6(addr) => {
-                let segments: Vec<String> =
-                    addr.segments.iter().map(|s| format!("{:x}", s)).collect();
-                write!(f, "{}", segments.join(":"))
-            }
-        }
-    }
-}
-
-enum Message {
-    Quit,
-    Move { x: i32, y: i32 },
-    Write(String),
-    ChangeColor(i32, i32, i32),
-}
-
-/// Message enum に Display トレイトを実装
-impl fmt::Display for Message {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Message::Quit => write!(f, "Quit"),
-            Message::Move { x, y } => write!(f, "Move({}, {})", x, y),
-            Message::Write(s) => write!(f, "Write({})", s),
-            Message::ChangeColor(r, g, b) => write!(f, "ChangeColor({}, {}, {})", r, g, b),
-        }
-    }
-}
+use std::io::{self, IsTerminal};
 
 enum Coin {
     Penny,
@@ -86,6 +38,97 @@ impl fmt::Display for Coin {
 }
 
 fn main() {
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Some(command)) => command,
+        Ok(None) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            eprintln!("{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Demo => demo(),
+        Command::Subnet(subnet) => {
+            if let Err(e) = run_subnet(&subnet) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Command::Repl => {
+            let stdin = io::stdin();
+            let prompt = stdin.is_terminal();
+            if let Err(e) = repl::run(stdin.lock(), io::stdout().lock(), prompt) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// subnet コマンドを実行する
+fn run_subnet(command: &SubnetCommand) -> Result<(), NetworkError> {
+    match command {
+        SubnetCommand::Info(network) => print_info(network),
+        SubnetCommand::Split(network, prefix) => {
+            let subnets = network.subnets(*prefix)?;
+            let count = 1_u128.checked_shl(u32::from(prefix - network.prefix()));
+            print_list(subnets, count);
+        }
+        SubnetCommand::Hosts(network) => print_list(network.hosts(), network.host_count()),
+        SubnetCommand::Aggregate(networks) => {
+            for network in network::aggregate(networks) {
+                println!("{network}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_info(network: &IpNetwork) {
+    let count = |count: Option<u128>| count.map_or("2^128".to_string(), |n| n.to_string());
+    println!("ネットワーク: {network}");
+    println!("ネットマスク: {}", network.netmask());
+    println!("ホストマスク: {}", network.hostmask());
+    println!("ネットワークアドレス: {}", network.network());
+    if network.is_ipv4() {
+        println!("ブロードキャスト: {}", network.broadcast());
+    } else {
+        println!("最後のアドレス: {}", network.broadcast());
+    }
+    let mut hosts = network.hosts();
+    if let Some(first) = hosts.next() {
+        // ホストが1つだけ（/32・/128）なら最初と最後は同じ
+        let last = hosts.next_back().unwrap_or(first);
+        println!("ホスト: {first} 〜 {last}");
+    }
+    println!("アドレス数: {}", count(network.address_count()));
+    println!("ホスト数: {}", count(network.host_count()));
+    if let Some(supernet) = network.supernet() {
+        println!("上位ネットワーク: {supernet}");
+    }
+}
+
+/// 一覧を最初の `cli::LIST_LIMIT` 個まで表示する（`total` は全体の数、u128 を超えれば `None`）
+fn print_list<T: fmt::Display>(items: impl Iterator<Item = T>, total: Option<u128>) {
+    for item in items.take(cli::LIST_LIMIT) {
+        println!("{item}");
+    }
+    match total {
+        Some(total) if total > cli::LIST_LIMIT as u128 => {
+            println!("…（全 {total} 個のうち最初の {} 個）", cli::LIST_LIMIT);
+        }
+        None => println!("…（全 2^128 個のうち最初の {} 個）", cli::LIST_LIMIT),
+        _ => {}
+    }
+}
+
+/// 列挙型のデモ
+fn demo() {
     // IPv4: 127.0.0.1
     let home = IpAddr::V4(Ipv4Addr {
         a: 127,
@@ -97,11 +140,54 @@ fn main() {
     // IPv6: ::1
     let loopback = IpAddr::V6(Ipv6Addr {
         segments: [0, 0, 0, 0, 0, 0, 0, 1],
+        zone: None,
     });
 
     // Display トレイトで IpAddr を表示
     println!("home: {}", home);
     println!("loopback: {}", loopback);
+    println!("loopback（省略なし）: {loopback:#}");
+
+    // IANA の特別用途アドレスの登録表による分類
+    for (name, addr) in [("home", &home), ("loopback", &loopback)] {
+        let purpose = addr.special_purpose().map_or("なし".to_string(), |entry| {
+            format!("{}（{}）", entry.name, entry.rfc)
+        });
+        println!(
+            "{name}: 登録表 = {purpose}, スコープ = {}, is_loopback = {}, is_private = {}, is_global = {}",
+            addr.scope(),
+            addr.is_loopback(),
+            addr.is_private(),
+            addr.is_global()
+        );
+    }
+
+    // 文字列から IpAddr を解析
+    for input in [
+        "192.168.0.1",
+        "2001:db8::8:800:200c:417a",
+        "::ffff:192.0.2.1",
+        "fe80::1%eth0",
+        "192.168.0.256",
+        "1::2::3",
+    ] {
+        match input.parse::<IpAddr>() {
+            Ok(addr) => println!("parse {input:?} => {addr}"),
+            Err(e) => println!("parse {input:?} => エラー: {e}"),
+        }
+    }
+
+    // IpNetwork でサブネットを計算
+    let lan: IpNetwork = "192.168.0.0/16".parse().expect("正しいネットワーク");
+    let host: IpAddr = "192.168.10.20".parse().expect("正しいアドレス");
+    println!("{lan} は {host} を含む: {}", lan.contains(&host));
+    println!("{lan} は {home} を含む: {}", lan.contains(&home));
+    let subnets: Vec<String> = lan
+        .subnets(18)
+        .expect("/16 は /18 に分割できる")
+        .map(|subnet| subnet.to_string())
+        .collect();
+    println!("{lan} を /18 に分割: {}", subnets.join(", "));
 
     // Message enum の使用例
     let quit = Message::Quit;
@@ -114,6 +200,14 @@ fn main() {
     println!("write: {}", write);
     println!("color: {}", color);
 
+    // Message をバイナリ形式にして戻す
+    for message in [quit, move_msg, write, color] {
+        let bytes = codec::encode(&message);
+        let hex: Vec<String> = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        let decoded = codec::decode(&bytes).expect("エンコードしたフレームは戻せる");
+        println!("{message} => [{}] => {decoded}", hex.join(" "));
+    }
+
     // Coin enum と for ループの使用例
     let coins = [Coin::Penny, Coin::Nickel, Coin::Dime, Coin::Quarter];
     for coin in coins {
@@ -0,0 +1,412 @@
+//! いろいろな形のバリアントを持つ列挙型 `Message` と、その文字列表現
+//!
+//! `Display` は `Quit`・`Move(10, 20)`・`Write(Hello)`・`ChangeColor(255, 0, 0)` の形で表示し、
+//! `FromStr` はその逆の解析をする。`Write` の文字列に含まれる `\`・`(`・`)`・`,` と改行は
+//! `\` でエスケープして表示する（`Write(a\, b)`）ので、どんな文字列でも表示して解析し直すと元に戻る。
+
+use std::fmt::{self, Write as _};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// 4種類のバリアント（ユニット、構造体、タプル）を持つメッセージ
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Message {
+    /// ユニットバリアント
+    Quit,
+    /// 構造体バリアント
+    Move { x: i32, y: i32 },
+    /// タプルバリアント
+    Write(String),
+    /// タプルバリアント
+    ChangeColor(i32, i32, i32),
+}
+
+/// メッセージの解析エラー
+///
+/// `position` は入力の先頭からのバイト位置（0 始まり）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMessageError {
+    /// 入力が空
+    Empty,
+    /// 知らないバリアント名
+    UnknownVariant(String),
+    /// 決まった文字（`(` や `)`）がない
+    Expected { position: usize, ch: char },
+    /// 引数の数が違う
+    WrongArgumentCount {
+        variant: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// 引数が i32 の整数ではない
+    InvalidNumber {
+        position: usize,
+        source: ParseIntError,
+    },
+    /// `Write` の文字列の中のエスケープされていない `(`・`)`・`,`
+    UnescapedCharacter { position: usize, ch: char },
+    /// `\` の後に続けられない文字（`None` は入力の末尾）
+    InvalidEscape { position: usize, ch: Option<char> },
+    /// `)` の後に余分な文字がある
+    TrailingCharacters { position: usize },
+}
+
+impl fmt::Display for ParseMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseMessageError::Empty => write!(f, "メッセージが空です"),
+            ParseMessageError::UnknownVariant(name) => write!(
+                f,
+                "不明なメッセージです: {name}（Quit・Move・Write・ChangeColor のどれか）"
+            ),
+            ParseMessageError::Expected { position, ch } => {
+                write!(f, "位置 {position}: '{ch}' が必要です")
+            }
+            ParseMessageError::WrongArgumentCount {
+                variant,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{variant} の引数は {expected} 個必要です（{found} 個あります）"
+            ),
+            ParseMessageError::InvalidNumber { position, source } => {
+                write!(f, "位置 {position}: 整数ではありません（{source}）")
+            }
+            ParseMessageError::UnescapedCharacter { position, ch } => {
+                write!(f, "位置 {position}: '{ch}' は \\{ch} と書いてください")
+            }
+            ParseMessageError::InvalidEscape {
+                position,
+                ch: Some(ch),
+            } => write!(f, "位置 {position}: \\{ch} というエスケープはありません"),
+            ParseMessageError::InvalidEscape { position, ch: None } => {
+                write!(f, "位置 {position}: \\ の後に文字がありません")
+            }
+            ParseMessageError::TrailingCharacters { position } => {
+                write!(f, "位置 {position}: メッセージの後に余分な文字があります")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseMessageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseMessageError::InvalidNumber { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Message enum に Display トレイトを実装
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Quit => write!(f, "Quit"),
+            Message::Move { x, y } => write!(f, "Move({x}, {y})"),
+            Message::Write(s) => {
+                f.write_str("Write(")?;
+                write_escaped(f, s)?;
+                f.write_char(')')
+            }
+            Message::ChangeColor(r, g, b) => write!(f, "ChangeColor({r}, {g}, {b})"),
+        }
+    }
+}
+
+/// `Write` の文字列を、解析し直せるようにエスケープして書く
+fn write_escaped(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for ch in s.chars() {
+        match ch {
+            '\\' | '(' | ')' | ',' => write!(f, "\\{ch}")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            _ => f.write_char(ch)?,
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for Message {
+    type Err = ParseMessageError;
+
+    /// `Display` と同じ形式（`Quit`・`Move(10, 20)`・`Write(Hello)`・`ChangeColor(255, 0, 0)`）を解析する
+    ///
+    /// 整数の引数の前後の空白は無視する。`Write` の文字列はそのまま使うので、
+    /// `(`・`)`・`,`・`\` は `\` を前に付け、改行は `\n`・`\r` と書く。
+    ///
+    /// ```
+    /// use enums::Message;
+    ///
+    /// assert_eq!("Move(10, 20)".parse(), Ok(Message::Move { x: 10, y: 20 }));
+    /// assert_eq!("Write(a\\, b)".parse(), Ok(Message::Write("a, b".to_string())));
+    ///
+    /// let message = Message::Write("f(x)".to_string());
+    /// assert_eq!(message.to_string(), "Write(f\\(x\\))");
+    /// assert_eq!(message.to_string().parse(), Ok(message));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseMessageError::Empty);
+        }
+        let (name, args) = match s.find('(') {
+            Some(i) => (&s[..i], Some(i + 1)),
+            None => (s, None),
+        };
+        let variant = match name {
+            "Quit" => return parse_quit(args),
+            "Move" => "Move",
+            "Write" => "Write",
+            "ChangeColor" => "ChangeColor",
+            _ => return Err(ParseMessageError::UnknownVariant(name.to_string())),
+        };
+        let Some(start) = args else {
+            return Err(ParseMessageError::Expected {
+                position: s.len(),
+                ch: '(',
+            });
+        };
+        if variant == "Write" {
+            let (text, end) = parse_text(s, start)?;
+            check_end(s, end)?;
+            return Ok(Message::Write(text));
+        }
+
+        let end = s[start..]
+            .find(')')
+            .map(|i| start + i)
+            .ok_or(ParseMessageError::Expected {
+                position: s.len(),
+                ch: ')',
+            })?;
+        check_end(s, end + 1)?;
+        let numbers = parse_numbers(s, start, end)?;
+        match (variant, numbers.as_slice()) {
+            ("Move", &[x, y]) => Ok(Message::Move { x, y }),
+            ("ChangeColor", &[r, g, b]) => Ok(Message::ChangeColor(r, g, b)),
+            _ => Err(ParseMessageError::WrongArgumentCount {
+                variant,
+                expected: if variant == "Move" { 2 } else { 3 },
+                found: numbers.len(),
+            }),
+        }
+    }
+}
+
+/// `Quit` は引数を取らないので `(` があればエラーにする
+fn parse_quit(args: Option<usize>) -> Result<Message, ParseMessageError> {
+    match args {
+        Some(start) => Err(ParseMessageError::TrailingCharacters {
+            position: start - 1,
+        }),
+        None => Ok(Message::Quit),
+    }
+}
+
+/// `end` 以降に文字があればエラーにする
+fn check_end(s: &str, end: usize) -> Result<(), ParseMessageError> {
+    if end < s.len() {
+        Err(ParseMessageError::TrailingCharacters { position: end })
+    } else {
+        Ok(())
+    }
+}
+
+/// `s[start..end]` をカンマで区切って i32 の並びにする（`Move()` のような空の引数は0個）
+fn parse_numbers(s: &str, start: usize, end: usize) -> Result<Vec<i32>, ParseMessageError> {
+    if s[start..end].trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut numbers = Vec::new();
+    let mut position = start;
+    for field in s[start..end].split(',') {
+        let leading = field.len() - field.trim_start().len();
+        let number = field
+            .trim()
+            .parse()
+            .map_err(|source| ParseMessageError::InvalidNumber {
+                position: position + leading,
+                source,
+            })?;
+        numbers.push(number);
+        position += field.len() + 1;
+    }
+    Ok(numbers)
+}
+
+/// `s[start..]` のエスケープされた文字列を `)` まで読み、文字列と `)` の次の位置を返す
+fn parse_text(s: &str, start: usize) -> Result<(String, usize), ParseMessageError> {
+    let mut text = String::new();
+    let mut chars = s[start..].char_indices().map(|(i, ch)| (start + i, ch));
+    while let Some((position, ch)) = chars.next() {
+        match ch {
+            ')' => return Ok((text, position + 1)),
+            '(' | ',' => return Err(ParseMessageError::UnescapedCharacter { position, ch }),
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('\\' | '(' | ')' | ','))) => text.push(escaped),
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 'r')) => text.push('\r'),
+                Some((_, other)) => {
+                    return Err(ParseMessageError::InvalidEscape {
+                        position,
+                        ch: Some(other),
+                    });
+                }
+                None => return Err(ParseMessageError::InvalidEscape { position, ch: None }),
+            },
+            _ => text.push(ch),
+        }
+    }
+    Err(ParseMessageError::Expected {
+        position: s.len(),
+        ch: ')',
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Message, ParseMessageError> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_parse_display_format() {
+        assert_eq!(parse("Quit"), Ok(Message::Quit));
+        assert_eq!(parse("Move(10, 20)"), Ok(Message::Move { x: 10, y: 20 }));
+        assert_eq!(parse("Move(-1,2)"), Ok(Message::Move { x: -1, y: 2 }));
+        assert_eq!(parse("Move( 3 ,  4 )"), Ok(Message::Move { x: 3, y: 4 }));
+        assert_eq!(
+            parse("Write(Hello)"),
+            Ok(Message::Write("Hello".to_string()))
+        );
+        assert_eq!(parse("Write()"), Ok(Message::Write(String::new())));
+        assert_eq!(
+            parse("Write( a b )"),
+            Ok(Message::Write(" a b ".to_string()))
+        );
+        assert_eq!(
+            parse("ChangeColor(255, 0, 0)"),
+            Ok(Message::ChangeColor(255, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(
+            parse(r"Write(\(a\, b\) \\ c\nd\r)"),
+            Ok(Message::Write("(a, b) \\ c\nd\r".to_string()))
+        );
+        assert_eq!(
+            Message::Write("(a, b) \\ c\nd\r".to_string()).to_string(),
+            r"Write(\(a\, b\) \\ c\nd\r)"
+        );
+        assert_eq!(
+            Message::Write("Hello".to_string()).to_string(),
+            "Write(Hello)"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use ParseMessageError as E;
+        assert_eq!(parse(""), Err(E::Empty));
+        assert_eq!(parse("Jump(1)"), Err(E::UnknownVariant("Jump".to_string())));
+        assert_eq!(parse("quit"), Err(E::UnknownVariant("quit".to_string())));
+        assert_eq!(parse(" Quit"), Err(E::UnknownVariant(" Quit".to_string())));
+        assert_eq!(parse("Quit()"), Err(E::TrailingCharacters { position: 4 }));
+        assert_eq!(
+            parse("Move"),
+            Err(E::Expected {
+                position: 4,
+                ch: '('
+            })
+        );
+        assert_eq!(
+            parse("Move(1, 2"),
+            Err(E::Expected {
+                position: 9,
+                ch: ')'
+            })
+        );
+        assert_eq!(
+            parse("Write(abc"),
+            Err(E::Expected {
+                position: 9,
+                ch: ')'
+            })
+        );
+        assert_eq!(
+            parse("Move(1, 2) "),
+            Err(E::TrailingCharacters { position: 10 })
+        );
+        assert_eq!(
+            parse("Write(a))"),
+            Err(E::TrailingCharacters { position: 8 })
+        );
+        assert_eq!(
+            parse("Move(1)"),
+            Err(E::WrongArgumentCount {
+                variant: "Move",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            parse("ChangeColor()"),
+            Err(E::WrongArgumentCount {
+                variant: "ChangeColor",
+                expected: 3,
+                found: 0
+            })
+        );
+        assert!(matches!(
+            parse("Move(1, x)"),
+            Err(E::InvalidNumber { position: 8, .. })
+        ));
+        assert!(matches!(
+            parse("Move(1, 2147483648)"),
+            Err(E::InvalidNumber { position: 8, .. })
+        ));
+        assert_eq!(
+            parse("Write(a, b)"),
+            Err(E::UnescapedCharacter {
+                position: 7,
+                ch: ','
+            })
+        );
+        assert_eq!(
+            parse(r"Write(\t)"),
+            Err(E::InvalidEscape {
+                position: 6,
+                ch: Some('t')
+            })
+        );
+        assert_eq!(
+            parse(r"Write(\"),
+            Err(E::InvalidEscape {
+                position: 6,
+                ch: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let message = |s: &str| parse(s).unwrap_err().to_string();
+        assert_eq!(
+            message("Write(a, b)"),
+            "位置 7: ',' は \\, と書いてください"
+        );
+        assert_eq!(
+            message("Move(1)"),
+            "Move の引数は 2 個必要です（1 個あります）"
+        );
+        assert_eq!(
+            message("Move(1, x)"),
+            "位置 8: 整数ではありません（invalid digit found in string）"
+        );
+    }
+}
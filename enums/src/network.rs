@@ -0,0 +1,613 @@
+//! CIDR 表記の IP ネットワークとサブネットの計算
+//!
+//! `10.0.0.0/8` や `2001:db8::/32` のように、アドレスと先頭から何ビットがネットワーク部かを表す
+//! プレフィックス長の組を扱う。アドレスは内部で整数（IPv4 は下位 32ビット）にして計算する。
+
+use crate::ip::{IpAddr, Ipv4Addr, Ipv6Addr, ParseError};
+use std::fmt;
+use std::str::FromStr;
+
+/// アドレスの種類（並べたときに IPv4 が先に来る）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    /// アドレスのビット数
+    const fn width(self) -> u8 {
+        match self {
+            Family::V4 => 32,
+            Family::V6 => 128,
+        }
+    }
+
+    /// 全てのビットが 1 の値
+    const fn all_ones(self) -> u128 {
+        match self {
+            Family::V4 => u32::MAX as u128,
+            Family::V6 => u128::MAX,
+        }
+    }
+
+    /// 先頭の `prefix` ビットが 1 のマスク
+    const fn netmask(self, prefix: u8) -> u128 {
+        if prefix == 0 {
+            0
+        } else {
+            (u128::MAX << (self.width() - prefix)) & self.all_ones()
+        }
+    }
+
+    fn addr(self, bits: u128) -> IpAddr {
+        match self {
+            Family::V4 => IpAddr::V4(Ipv4Addr::from_bits(
+                u32::try_from(bits).expect("IPv4 のアドレスは 32ビット"),
+            )),
+            Family::V6 => IpAddr::V6(Ipv6Addr::from_bits(bits)),
+        }
+    }
+}
+
+/// アドレスの種類と整数の値（ゾーン ID は無視する）
+fn family_and_bits(addr: &IpAddr) -> (Family, u128) {
+    match addr {
+        IpAddr::V4(addr) => (Family::V4, u128::from(addr.to_bits())),
+        IpAddr::V6(addr) => (Family::V6, addr.to_bits()),
+    }
+}
+
+/// IP ネットワーク（ネットワークアドレスとプレフィックス長）
+///
+/// 並べると IPv4 が先、次にネットワークアドレスの小さい順、同じなら大きいネットワークが先になる。
+///
+/// ```
+/// use enums::{IpAddr, IpNetwork};
+///
+/// let network: IpNetwork = "192.168.1.0/24".parse().unwrap();
+/// let host: IpAddr = "192.168.1.10".parse().unwrap();
+/// assert!(network.contains(&host));
+/// assert_eq!(network.broadcast().to_string(), "192.168.1.255");
+/// assert_eq!(network.host_count(), Some(254));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IpNetwork {
+    family: Family,
+    bits: u128,
+    prefix: u8,
+}
+
+/// ネットワークの解析・計算のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// アドレスの部分が不正
+    Address(ParseError),
+    /// `/` とプレフィックス長がない
+    MissingPrefix,
+    /// プレフィックス長が 0〜255 の10進数ではない
+    InvalidPrefix(String),
+    /// プレフィックス長がアドレスのビット数を超える
+    PrefixTooLong { prefix: u8, max: u8 },
+    /// 分割後のプレフィックス長が元のネットワークより短い
+    PrefixTooShort { prefix: u8, min: u8 },
+    /// ネットワークにゾーン ID はつけられない
+    ZoneNotAllowed,
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkError::Address(e) => write!(f, "アドレスが不正です: {e}"),
+            NetworkError::MissingPrefix => {
+                write!(
+                    f,
+                    "プレフィックス長がありません（10.0.0.0/8 の形で指定してください）"
+                )
+            }
+            NetworkError::InvalidPrefix(prefix) => {
+                write!(f, "プレフィックス長が不正です: {prefix}")
+            }
+            NetworkError::PrefixTooLong { prefix, max } => {
+                write!(f, "プレフィックス長 /{prefix} は /{max} を超えています")
+            }
+            NetworkError::PrefixTooShort { prefix, min } => {
+                write!(f, "プレフィックス長 /{prefix} は /{min} より短くできません")
+            }
+            NetworkError::ZoneNotAllowed => write!(f, "ネットワークにゾーン ID はつけられません"),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+impl From<ParseError> for NetworkError {
+    fn from(e: ParseError) -> Self {
+        NetworkError::Address(e)
+    }
+}
+
+impl IpNetwork {
+    /// アドレスとプレフィックス長からネットワークを作る
+    ///
+    /// ホスト部のビット（`192.168.1.10/24` の `.10`）は 0 にする。
+    ///
+    /// # Errors
+    /// プレフィックス長がアドレスのビット数を超える場合や、アドレスにゾーン ID がある場合
+    pub fn new(addr: &IpAddr, prefix: u8) -> Result<Self, NetworkError> {
+        if matches!(addr, IpAddr::V6(v6) if v6.zone.is_some()) {
+            return Err(NetworkError::ZoneNotAllowed);
+        }
+        let (family, bits) = family_and_bits(addr);
+        Self::from_parts(family, bits, prefix)
+    }
+
+    fn from_parts(family: Family, bits: u128, prefix: u8) -> Result<Self, NetworkError> {
+        if prefix > family.width() {
+            return Err(NetworkError::PrefixTooLong {
+                prefix,
+                max: family.width(),
+            });
+        }
+        Ok(IpNetwork {
+            family,
+            bits: bits & family.netmask(prefix),
+            prefix,
+        })
+    }
+
+    /// IPv4 のネットワークなら真
+    #[must_use]
+    pub fn is_ipv4(&self) -> bool {
+        self.family == Family::V4
+    }
+
+    /// プレフィックス長
+    #[must_use]
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// アドレスのビット数（IPv4 は 32、IPv6 は 128）
+    #[must_use]
+    pub fn max_prefix(&self) -> u8 {
+        self.family.width()
+    }
+
+    /// ネットワークアドレス（ホスト部が全て 0）
+    #[must_use]
+    pub fn network(&self) -> IpAddr {
+        self.family.addr(self.bits)
+    }
+
+    /// ブロードキャストアドレス（ホスト部が全て 1、IPv6 では最後のアドレス）
+    #[must_use]
+    pub fn broadcast(&self) -> IpAddr {
+        self.family.addr(self.last_bits())
+    }
+
+    /// ネットマスク（`/24` なら `255.255.255.0`）
+    #[must_use]
+    pub fn netmask(&self) -> IpAddr {
+        self.family.addr(self.family.netmask(self.prefix))
+    }
+
+    /// ホストマスク（`/24` なら `0.0.0.255`）
+    #[must_use]
+    pub fn hostmask(&self) -> IpAddr {
+        self.family.addr(self.hostmask_bits())
+    }
+
+    fn hostmask_bits(&self) -> u128 {
+        !self.family.netmask(self.prefix) & self.family.all_ones()
+    }
+
+    fn last_bits(&self) -> u128 {
+        self.bits | self.hostmask_bits()
+    }
+
+    /// アドレスの数（`::/0` は u128 に収まらないので `None`）
+    #[must_use]
+    pub fn address_count(&self) -> Option<u128> {
+        1_u128.checked_shl(u32::from(self.max_prefix() - self.prefix))
+    }
+
+    /// ホストに割り当てられるアドレスの数（`hosts` が返す数）
+    #[must_use]
+    pub fn host_count(&self) -> Option<u128> {
+        match self.family {
+            Family::V4 if self.prefix <= 30 => self.address_count().map(|count| count - 2),
+            _ => self.address_count(),
+        }
+    }
+
+    /// アドレスがこのネットワークに含まれるなら真（種類が違えば偽、ゾーン ID は無視する）
+    #[must_use]
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        let (family, bits) = family_and_bits(addr);
+        family == self.family && bits & self.family.netmask(self.prefix) == self.bits
+    }
+
+    /// `other` がこのネットワークに含まれる（同じか、より小さいサブネット）なら真
+    #[must_use]
+    pub fn contains_network(&self, other: &IpNetwork) -> bool {
+        other.family == self.family
+            && other.prefix >= self.prefix
+            && other.bits & self.family.netmask(self.prefix) == self.bits
+    }
+
+    /// ホストに割り当てられるアドレスを順に返す
+    ///
+    /// IPv4 の `/30` 以下ではネットワークアドレスとブロードキャストアドレスを除く。
+    /// `/31`（RFC 3021 の2点間リンク）・`/32` と IPv6 では全てのアドレスを返す。
+    #[must_use]
+    pub fn hosts(&self) -> Hosts {
+        let (first, last) = if self.family == Family::V4 && self.prefix <= 30 {
+            (self.bits + 1, self.last_bits() - 1)
+        } else {
+            (self.bits, self.last_bits())
+        };
+        Hosts {
+            family: self.family,
+            next: first,
+            last,
+            done: false,
+        }
+    }
+
+    /// プレフィックス長 `prefix` のサブネットに分割する
+    ///
+    /// ```
+    /// use enums::IpNetwork;
+    ///
+    /// let network: IpNetwork = "10.0.0.0/22".parse().unwrap();
+    /// let subnets: Vec<String> = network.subnets(24).unwrap().map(|n| n.to_string()).collect();
+    /// assert_eq!(subnets, ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24"]);
+    /// ```
+    ///
+    /// # Errors
+    /// `prefix` が元のプレフィックス長より短い場合や、アドレスのビット数を超える場合
+    pub fn subnets(&self, prefix: u8) -> Result<Subnets, NetworkError> {
+        if prefix < self.prefix {
+            return Err(NetworkError::PrefixTooShort {
+                prefix,
+                min: self.prefix,
+            });
+        }
+        let first = Self::from_parts(self.family, self.bits, prefix)?;
+        let last = Self::from_parts(self.family, self.last_bits(), prefix)?;
+        Ok(Subnets {
+            next: first,
+            last_bits: last.bits,
+            done: false,
+        })
+    }
+
+    /// プレフィックス長を1つ短くしたネットワーク（`/0` なら `None`）
+    #[must_use]
+    pub fn supernet(&self) -> Option<IpNetwork> {
+        let prefix = self.prefix.checked_sub(1)?;
+        Self::from_parts(self.family, self.bits, prefix).ok()
+    }
+}
+
+/// `10.0.0.0/8` の形で表示する
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.network(), self.prefix)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = NetworkError;
+
+    /// `10.0.0.0/8`・`2001:db8::/32` の形を解析する（ホスト部は 0 にする）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = s.split_once('/').ok_or(NetworkError::MissingPrefix)?;
+        let addr: IpAddr = addr.parse()?;
+        if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(NetworkError::InvalidPrefix(prefix.to_string()));
+        }
+        let prefix = prefix
+            .parse()
+            .map_err(|_| NetworkError::InvalidPrefix(prefix.to_string()))?;
+        IpNetwork::new(&addr, prefix)
+    }
+}
+
+/// `IpNetwork::hosts` が返すイテレータ
+#[derive(Debug, Clone)]
+pub struct Hosts {
+    family: Family,
+    next: u128,
+    last: u128,
+    done: bool,
+}
+
+impl Iterator for Hosts {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<IpAddr> {
+        if self.done {
+            return None;
+        }
+        let bits = self.next;
+        if bits == self.last {
+            self.done = true;
+        } else {
+            self.next += 1;
+        }
+        Some(self.family.addr(bits))
+    }
+}
+
+impl DoubleEndedIterator for Hosts {
+    fn next_back(&mut self) -> Option<IpAddr> {
+        if self.done {
+            return None;
+        }
+        let bits = self.last;
+        if bits == self.next {
+            self.done = true;
+        } else {
+            self.last -= 1;
+        }
+        Some(self.family.addr(bits))
+    }
+}
+
+/// `IpNetwork::subnets` が返すイテレータ
+#[derive(Debug, Clone)]
+pub struct Subnets {
+    next: IpNetwork,
+    last_bits: u128,
+    done: bool,
+}
+
+impl Iterator for Subnets {
+    type Item = IpNetwork;
+
+    fn next(&mut self) -> Option<IpNetwork> {
+        if self.done {
+            return None;
+        }
+        let network = self.next;
+        if network.bits == self.last_bits {
+            self.done = true;
+        } else {
+            self.next.bits = network.last_bits() + 1;
+        }
+        Some(network)
+    }
+}
+
+/// ネットワークの一覧を、同じアドレスを覆う最小の一覧にまとめる（経路集約）
+///
+/// 他に含まれるネットワークを取り除き、隣り合う同じ大きさのネットワークを1つ上にまとめることを
+/// 繰り返す。結果は並べた順（IPv4 が先）になる。
+///
+/// ```
+/// use enums::network::aggregate;
+/// use enums::IpNetwork;
+///
+/// let networks: Vec<IpNetwork> = ["10.0.0.0/24", "10.0.1.0/24", "10.0.0.128/25"]
+///     .iter()
+///     .map(|s| s.parse().unwrap())
+///     .collect();
+/// assert_eq!(aggregate(&networks), ["10.0.0.0/23".parse().unwrap()]);
+/// ```
+#[must_use]
+pub fn aggregate(networks: &[IpNetwork]) -> Vec<IpNetwork> {
+    let mut sorted = networks.to_vec();
+    sorted.sort();
+    let mut merged: Vec<IpNetwork> = Vec::new();
+    for network in sorted {
+        // 同じアドレスなら大きいネットワークが先に来るので、直前のものだけ調べればよい
+        if merged
+            .last()
+            .is_some_and(|last| last.contains_network(&network))
+        {
+            continue;
+        }
+        merged.push(network);
+        while let [.., left, right] = merged[..] {
+            let parent = left.supernet();
+            if left.prefix != right.prefix || parent.is_none() || parent != right.supernet() {
+                break;
+            }
+            merged.truncate(merged.len() - 2);
+            merged.extend(parent);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(s: &str) -> IpNetwork {
+        s.parse().unwrap()
+    }
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(net("10.0.0.0/8").to_string(), "10.0.0.0/8");
+        assert_eq!(net("192.168.1.10/24").to_string(), "192.168.1.0/24");
+        assert_eq!(net("2001:db8::1/32").to_string(), "2001:db8::/32");
+        assert_eq!(net("0.0.0.0/0").to_string(), "0.0.0.0/0");
+        assert_eq!(net("::/0").to_string(), "::/0");
+        assert_eq!(net("::1/128").to_string(), "::1/128");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("10.0.0.0", NetworkError::MissingPrefix),
+            ("10.0.0.0/", NetworkError::InvalidPrefix(String::new())),
+            ("10.0.0.0/+8", NetworkError::InvalidPrefix("+8".to_string())),
+            (
+                "10.0.0.0/256",
+                NetworkError::InvalidPrefix("256".to_string()),
+            ),
+            (
+                "10.0.0.0/33",
+                NetworkError::PrefixTooLong {
+                    prefix: 33,
+                    max: 32,
+                },
+            ),
+            (
+                "::/129",
+                NetworkError::PrefixTooLong {
+                    prefix: 129,
+                    max: 128,
+                },
+            ),
+            ("fe80::%eth0/64", NetworkError::ZoneNotAllowed),
+            (
+                "10.0.0/8",
+                NetworkError::Address(ParseError::WrongOctetCount { found: 3 }),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<IpNetwork>(), Err(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_addresses_and_masks() {
+        let network = net("172.16.5.4/20");
+        assert_eq!(network.network(), addr("172.16.0.0"));
+        assert_eq!(network.broadcast(), addr("172.16.15.255"));
+        assert_eq!(network.netmask(), addr("255.255.240.0"));
+        assert_eq!(network.hostmask(), addr("0.0.15.255"));
+        assert_eq!(network.address_count(), Some(4096));
+        assert_eq!(network.host_count(), Some(4094));
+
+        let v6 = net("2001:db8:abcd::/48");
+        assert_eq!(
+            v6.broadcast(),
+            addr("2001:db8:abcd:ffff:ffff:ffff:ffff:ffff")
+        );
+        assert_eq!(v6.netmask(), addr("ffff:ffff:ffff::"));
+        assert_eq!(v6.address_count(), Some(1 << 80));
+        assert_eq!(net("::/0").address_count(), None);
+        assert_eq!(net("0.0.0.0/0").netmask(), addr("0.0.0.0"));
+        assert_eq!(net("0.0.0.0/0").host_count(), Some((1 << 32) - 2));
+    }
+
+    #[test]
+    fn test_contains() {
+        let network = net("192.168.0.0/16");
+        assert!(network.contains(&addr("192.168.255.255")));
+        assert!(!network.contains(&addr("192.169.0.0")));
+        assert!(!network.contains(&addr("::ffff:192.168.0.1")));
+        assert!(net("fe80::/10").contains(&addr("fe80::1%eth0")));
+        assert!(net("0.0.0.0/0").contains(&addr("255.255.255.255")));
+
+        assert!(network.contains_network(&net("192.168.10.0/24")));
+        assert!(network.contains_network(&network));
+        assert!(!network.contains_network(&net("192.0.0.0/8")));
+        assert!(!net("::/0").contains_network(&network));
+    }
+
+    #[test]
+    fn test_hosts() {
+        let hosts: Vec<String> = net("10.0.0.0/29").hosts().map(|a| a.to_string()).collect();
+        assert_eq!(
+            hosts,
+            [
+                "10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.4", "10.0.0.5", "10.0.0.6"
+            ]
+        );
+        assert_eq!(net("10.0.0.0/31").hosts().count(), 2);
+        assert_eq!(
+            net("10.0.0.7/32").hosts().collect::<Vec<_>>(),
+            [addr("10.0.0.7")]
+        );
+        assert_eq!(net("2001:db8::/126").hosts().count(), 4);
+
+        let mut all = net("0.0.0.0/0").hosts();
+        assert_eq!(all.next(), Some(addr("0.0.0.1")));
+        assert_eq!(all.next_back(), Some(addr("255.255.255.254")));
+        let mut everything = net("::/0").hosts();
+        assert_eq!(
+            everything.next_back(),
+            Some(addr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"))
+        );
+    }
+
+    #[test]
+    fn test_subnets() {
+        let subnets: Vec<IpNetwork> = net("192.168.0.0/24").subnets(26).unwrap().collect();
+        assert_eq!(
+            subnets,
+            [
+                "192.168.0.0/26",
+                "192.168.0.64/26",
+                "192.168.0.128/26",
+                "192.168.0.192/26"
+            ]
+            .map(net)
+        );
+        assert_eq!(
+            net("10.0.0.0/8").subnets(8).unwrap().collect::<Vec<_>>(),
+            [net("10.0.0.0/8")]
+        );
+        assert_eq!(net("::/0").subnets(1).unwrap().count(), 2);
+        assert_eq!(net("255.255.255.0/24").subnets(32).unwrap().count(), 256);
+        assert_eq!(
+            net("10.0.0.0/8").subnets(7).err(),
+            Some(NetworkError::PrefixTooShort { prefix: 7, min: 8 })
+        );
+        assert_eq!(
+            net("10.0.0.0/8").subnets(33).err(),
+            Some(NetworkError::PrefixTooLong {
+                prefix: 33,
+                max: 32
+            })
+        );
+    }
+
+    #[test]
+    fn test_supernet() {
+        assert_eq!(net("10.1.0.0/16").supernet(), Some(net("10.0.0.0/15")));
+        assert_eq!(net("0.0.0.0/0").supernet(), None);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let networks = [
+            "10.0.3.0/24",
+            "10.0.0.0/24",
+            "2001:db8:1::/48",
+            "10.0.1.0/24",
+            "10.0.2.0/25",
+            "10.0.2.128/25",
+            "10.0.1.64/26",
+            "2001:db8::/48",
+            "192.168.0.0/24",
+            "10.0.0.0/24",
+        ]
+        .map(net);
+        assert_eq!(
+            aggregate(&networks),
+            ["10.0.0.0/22", "192.168.0.0/24", "2001:db8::/47"].map(net)
+        );
+        // 隣り合っていても上位のネットワークの境界をまたぐものはまとめない
+        assert_eq!(
+            aggregate(&["10.0.1.0/24", "10.0.2.0/24"].map(net)),
+            ["10.0.1.0/24", "10.0.2.0/24"].map(net)
+        );
+        assert_eq!(
+            aggregate(&["0.0.0.0/1", "128.0.0.0/1"].map(net)),
+            [net("0.0.0.0/0")]
+        );
+        assert_eq!(aggregate(&[]), []);
+    }
+}
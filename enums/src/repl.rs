@@ -0,0 +1,142 @@
+//! メッセージを1行ずつ読んで実行する REPL
+//!
+//! 1行を `Message` として解析し（`Move(10, 20)` など）、`State` に送って結果を表示する。
+//! 解析できない行はエラーを表示して次の行を読む。`Quit` か入力の終わりで終了する。
+
+use crate::message::Message;
+use std::io::{self, BufRead, Write};
+
+/// メッセージを受け取る側の状態
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct State {
+    /// 現在の位置（`Move` で変わる）
+    pub position: (i32, i32),
+    /// 現在の色（`ChangeColor` で変わる）
+    pub color: (i32, i32, i32),
+    /// `Write` で書かれた文字列
+    pub written: Vec<String>,
+    /// `Quit` を受け取ったら真
+    pub quit: bool,
+}
+
+impl State {
+    /// メッセージを処理して、結果の説明を返す
+    pub fn dispatch(&mut self, message: Message) -> String {
+        match message {
+            Message::Quit => {
+                self.quit = true;
+                "終了します".to_string()
+            }
+            Message::Move { x, y } => {
+                self.position = (x, y);
+                format!("({x}, {y}) に移動しました")
+            }
+            Message::Write(text) => {
+                let reply = format!("書き込み: {text}");
+                self.written.push(text);
+                reply
+            }
+            Message::ChangeColor(r, g, b) => {
+                self.color = (r, g, b);
+                format!("色を ({r}, {g}, {b}) に変えました")
+            }
+        }
+    }
+}
+
+/// `input` から1行ずつメッセージを読み、結果を `output` に書く
+///
+/// `prompt` が真なら各行を読む前に `> ` を表示する（端末から読むとき）。
+/// 空行は読み飛ばし、行末の `\r` は取り除く。終了したときの状態を返す。
+///
+/// ```
+/// use enums::repl;
+///
+/// let input = "Move(1, 2)\nWrite(a\\, b)\nQuit\nWrite(読まれない)\n";
+/// let mut output = Vec::new();
+/// let state = repl::run(input.as_bytes(), &mut output, false).unwrap();
+/// assert_eq!(state.position, (1, 2));
+/// assert_eq!(state.written, ["a, b"]);
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "(1, 2) に移動しました\n書き込み: a, b\n終了します\n"
+/// );
+/// ```
+///
+/// # Errors
+/// 入力の読み込みや出力の書き込みに失敗した場合に `io::Error` を返す
+pub fn run<R: BufRead, W: Write>(input: R, mut output: W, prompt: bool) -> io::Result<State> {
+    let mut state = State::default();
+    let mut lines = input.lines();
+    while !state.quit {
+        if prompt {
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            if prompt {
+                // 端末で Ctrl-D を押したとき、プロンプトの後で改行する
+                writeln!(output)?;
+            }
+            break;
+        };
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if line.is_empty() {
+            continue;
+        }
+        match line.parse::<Message>() {
+            Ok(message) => writeln!(output, "{}", state.dispatch(message))?,
+            Err(e) => writeln!(output, "エラー: {e}")?,
+        }
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_lines(input: &str, prompt: bool) -> (State, String) {
+        let mut output = Vec::new();
+        let state = run(input.as_bytes(), &mut output, prompt).unwrap();
+        (state, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut state = State::default();
+        assert_eq!(
+            state.dispatch(Message::ChangeColor(0, 128, 255)),
+            "色を (0, 128, 255) に変えました"
+        );
+        assert_eq!(
+            state.dispatch(Message::Write("x".to_string())),
+            "書き込み: x"
+        );
+        assert_eq!(state.color, (0, 128, 255));
+        assert_eq!(state.written, ["x"]);
+        assert!(!state.quit);
+        assert_eq!(state.dispatch(Message::Quit), "終了します");
+        assert!(state.quit);
+    }
+
+    #[test]
+    fn test_run_skips_errors_and_blank_lines() {
+        let (state, output) = run_lines("\r\nJump(1)\r\nMove(3, 4)\r\n", false);
+        assert_eq!(state.position, (3, 4));
+        assert!(!state.quit);
+        assert_eq!(
+            output,
+            "エラー: 不明なメッセージです: Jump（Quit・Move・Write・ChangeColor のどれか）\n\
+             (3, 4) に移動しました\n"
+        );
+    }
+
+    #[test]
+    fn test_run_with_prompt() {
+        let (_, output) = run_lines("Quit\n", true);
+        assert_eq!(output, "> 終了します\n");
+        let (_, output) = run_lines("", true);
+        assert_eq!(output, "> \n");
+    }
+}
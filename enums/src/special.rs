@@ -0,0 +1,441 @@
+//! IANA の特別用途アドレスの登録表とアドレスの分類
+//!
+//! IANA IPv4 / IPv6 Special-Purpose Address Registry の各行（廃止された行を除く）を表として持ち、
+//! `IpAddr` の `is_loopback` や `scope` などの分類はこの表を引いて決める。
+//! アドレスが複数の行に含まれる場合（`192.0.0.9` は `192.0.0.0/24` と `192.0.0.9/32`）は
+//! プレフィックス長の最も長い行を使う。マルチキャストは登録表にないので別の規則で分類する。
+
+use crate::ip::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::network::IpNetwork;
+use std::fmt;
+use std::sync::LazyLock;
+
+/// 特別用途のアドレスの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Purpose {
+    /// 未指定のアドレス（`0.0.0.0`, `::`）
+    Unspecified,
+    /// このネットワーク（`0.0.0.0/8`）
+    ThisNetwork,
+    /// ループバック
+    Loopback,
+    /// プライベートアドレス（RFC 1918、ユニークローカル）
+    Private,
+    /// キャリアグレード NAT の共有アドレス
+    Shared,
+    /// リンクローカル
+    LinkLocal,
+    /// ドキュメント用
+    Documentation,
+    /// ベンチマーク用
+    Benchmarking,
+    /// 限定ブロードキャスト
+    Broadcast,
+    /// 将来のための予約
+    Reserved,
+    /// IPv4 射影アドレス
+    Ipv4Mapped,
+    /// IPv4 と IPv6 の変換・トンネル
+    Translation,
+    /// その他のプロトコル用の割り当て（エニーキャスト・AS112 など）
+    Protocol,
+}
+
+/// アドレスが届く範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Scope {
+    /// 自分自身（未指定・ループバック、インターフェースローカルのマルチキャスト）
+    Host,
+    /// 同じリンク（リンクローカル・ブロードキャスト、リンクローカルのマルチキャスト）
+    Link,
+    /// 組織の中（プライベート・共有アドレス、組織ローカルまでのマルチキャスト）
+    Private,
+    /// インターネット全体
+    Global,
+    /// インターネットでは使われない（ドキュメント用・予約済みなど）
+    Reserved,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scope::Host => write!(f, "ホスト内"),
+            Scope::Link => write!(f, "リンク内"),
+            Scope::Private => write!(f, "組織内"),
+            Scope::Global => write!(f, "グローバル"),
+            Scope::Reserved => write!(f, "予約済み"),
+        }
+    }
+}
+
+/// 特別用途アドレスの登録表の1行
+///
+/// `source` から `reserved_by_protocol` は IANA の表の列と同じ。`globally_reachable` の
+/// `None` は表の「N/A」（そのブロックを含むより大きいブロックの値に従う）。
+#[allow(clippy::struct_excessive_bools)] // IANA の表の真偽値の列をそのまま持つ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecialPurpose {
+    /// アドレスブロック（CIDR 表記）
+    pub network: &'static str,
+    /// 名前
+    pub name: &'static str,
+    /// 定めている RFC
+    pub rfc: &'static str,
+    /// 送信元アドレスとして使えるか
+    pub source: bool,
+    /// 宛先アドレスとして使えるか
+    pub destination: bool,
+    /// ルーターが転送してよいか
+    pub forwardable: bool,
+    /// インターネット全体から届くか
+    pub globally_reachable: Option<bool>,
+    /// プロトコルの仕様で予約されているか
+    pub reserved_by_protocol: bool,
+    /// 種類
+    pub purpose: Purpose,
+}
+
+macro_rules! flag {
+    (T) => {
+        true
+    };
+    (F) => {
+        false
+    };
+}
+
+macro_rules! reach {
+    (T) => {
+        Some(true)
+    };
+    (F) => {
+        Some(false)
+    };
+    (NA) => {
+        None
+    };
+}
+
+/// 表の行を `ネットワーク 名前 RFC [送信元 宛先 転送 到達 予約] 種類;` の形で書く
+macro_rules! registry {
+    ($($network:literal $name:literal $rfc:literal
+        [$source:ident $destination:ident $forwardable:ident $reachable:ident $reserved:ident]
+        $purpose:ident;)*) => {
+        &[$(SpecialPurpose {
+            network: $network,
+            name: $name,
+            rfc: $rfc,
+            source: flag!($source),
+            destination: flag!($destination),
+            forwardable: flag!($forwardable),
+            globally_reachable: reach!($reachable),
+            reserved_by_protocol: flag!($reserved),
+            purpose: Purpose::$purpose,
+        }),*]
+    };
+}
+
+/// IANA IPv4 / IPv6 Special-Purpose Address Registry
+#[rustfmt::skip]
+pub const REGISTRY: &[SpecialPurpose] = registry! {
+    // IPv4
+    "0.0.0.0/8"          "This network"                        "RFC 791"   [T F F F T] ThisNetwork;
+    "0.0.0.0/32"         "This host on this network"           "RFC 1122"  [T F F F T] Unspecified;
+    "10.0.0.0/8"         "Private-Use"                         "RFC 1918"  [T T T F F] Private;
+    "100.64.0.0/10"      "Shared Address Space"                "RFC 6598"  [T T T F F] Shared;
+    "127.0.0.0/8"        "Loopback"                            "RFC 1122"  [F F F F T] Loopback;
+    "169.254.0.0/16"     "Link Local"                          "RFC 3927"  [T T F F T] LinkLocal;
+    "172.16.0.0/12"      "Private-Use"                         "RFC 1918"  [T T T F F] Private;
+    "192.0.0.0/24"       "IETF Protocol Assignments"           "RFC 6890"  [F F F F F] Protocol;
+    "192.0.0.0/29"       "IPv4 Service Continuity Prefix"      "RFC 7335"  [T T T F F] Translation;
+    "192.0.0.8/32"       "IPv4 dummy address"                  "RFC 7600"  [T F F F F] Protocol;
+    "192.0.0.9/32"       "Port Control Protocol Anycast"       "RFC 7723"  [T T T T F] Protocol;
+    "192.0.0.10/32"      "Traversal Using Relays around NAT Anycast" "RFC 8155" [T T T T F] Protocol;
+    "192.0.0.170/32"     "NAT64/DNS64 Discovery"               "RFC 8880"  [F F F F T] Translation;
+    "192.0.0.171/32"     "NAT64/DNS64 Discovery"               "RFC 8880"  [F F F F T] Translation;
+    "192.0.2.0/24"       "Documentation (TEST-NET-1)"          "RFC 5737"  [F F F F F] Documentation;
+    "192.31.196.0/24"    "AS112-v4"                            "RFC 7535"  [T T T T F] Protocol;
+    "192.52.193.0/24"    "AMT"                                 "RFC 7450"  [T T T T F] Protocol;
+    "192.88.99.2/32"     "6a44-relay anycast address"          "RFC 6751"  [T T T F F] Translation;
+    "192.168.0.0/16"     "Private-Use"                         "RFC 1918"  [T T T F F] Private;
+    "192.175.48.0/24"    "Direct Delegation AS112 Service"     "RFC 7534"  [T T T T F] Protocol;
+    "198.18.0.0/15"      "Benchmarking"                        "RFC 2544"  [T T T F F] Benchmarking;
+    "198.51.100.0/24"    "Documentation (TEST-NET-2)"          "RFC 5737"  [F F F F F] Documentation;
+    "203.0.113.0/24"     "Documentation (TEST-NET-3)"          "RFC 5737"  [F F F F F] Documentation;
+    "240.0.0.0/4"        "Reserved"                            "RFC 1112"  [F F F F T] Reserved;
+    "255.255.255.255/32" "Limited Broadcast"                   "RFC 919"   [F T F F T] Broadcast;
+    // IPv6
+    "::1/128"            "Loopback Address"                    "RFC 4291"  [F F F F T] Loopback;
+    "::/128"             "Unspecified Address"                 "RFC 4291"  [T F F F T] Unspecified;
+    "::ffff:0:0/96"      "IPv4-mapped Address"                 "RFC 4291"  [F F F F T] Ipv4Mapped;
+    "64:ff9b::/96"       "IPv4-IPv6 Translat."                 "RFC 6052"  [T T T T F] Translation;
+    "64:ff9b:1::/48"     "IPv4-IPv6 Translat."                 "RFC 8215"  [T T T F F] Translation;
+    "100::/64"           "Discard-Only Address Block"          "RFC 6666"  [T T T F F] Protocol;
+    "2001::/23"          "IETF Protocol Assignments"           "RFC 2928"  [F F F F F] Protocol;
+    "2001::/32"          "TEREDO"                              "RFC 4380"  [T T T NA F] Translation;
+    "2001:1::1/128"      "Port Control Protocol Anycast"       "RFC 7723"  [T T T T F] Protocol;
+    "2001:1::2/128"      "Traversal Using Relays around NAT Anycast" "RFC 8155" [T T T T F] Protocol;
+    "2001:1::3/128"      "DNS-SD Service Registration Protocol Anycast" "RFC 9665" [T T T T F] Protocol;
+    "2001:2::/48"        "Benchmarking"                        "RFC 5180"  [T T T F F] Benchmarking;
+    "2001:3::/32"        "AMT"                                 "RFC 7450"  [T T T T F] Protocol;
+    "2001:4:112::/48"    "AS112-v6"                            "RFC 7535"  [T T T T F] Protocol;
+    "2001:20::/28"       "ORCHIDv2"                            "RFC 7343"  [T T T T F] Protocol;
+    "2001:30::/28"       "Drone Remote ID Protocol Entity Tags (DETs) Prefix" "RFC 9374" [T T T T F] Protocol;
+    "2001:db8::/32"      "Documentation"                       "RFC 3849"  [F F F F F] Documentation;
+    "2002::/16"          "6to4"                                "RFC 3056"  [T T T NA F] Translation;
+    "2620:4f:8000::/48"  "Direct Delegation AS112 Service"     "RFC 7534"  [T T T T F] Protocol;
+    "3fff::/20"          "Documentation"                       "RFC 9637"  [F F F F F] Documentation;
+    "5f00::/16"          "Segment Routing (SRv6) SIDs"         "RFC 9602"  [T T T F F] Protocol;
+    "fc00::/7"           "Unique-Local"                        "RFC 4193"  [T T T F F] Private;
+    "fe80::/10"          "Link-Local Unicast"                  "RFC 4291"  [T T F F T] LinkLocal;
+};
+
+/// 登録表の行をネットワークにして、プレフィックス長の長い順に並べたもの
+static BY_PREFIX: LazyLock<Vec<(IpNetwork, &'static SpecialPurpose)>> = LazyLock::new(|| {
+    let mut entries: Vec<_> = REGISTRY
+        .iter()
+        .map(|entry| (entry.network(), entry))
+        .collect();
+    entries.sort_by_key(|(network, _)| std::cmp::Reverse(network.prefix()));
+    entries
+});
+
+impl SpecialPurpose {
+    /// アドレスブロックを `IpNetwork` にする
+    ///
+    /// # Panics
+    /// 表のアドレスブロックが CIDR 表記として正しくない場合（テストで確かめている）
+    #[must_use]
+    pub fn network(&self) -> IpNetwork {
+        self.network
+            .parse()
+            .expect("登録表のアドレスブロックは正しい")
+    }
+}
+
+/// アドレスを含む登録表の行を、プレフィックス長の長い順に返す
+fn entries(addr: &IpAddr) -> impl Iterator<Item = &'static SpecialPurpose> + '_ {
+    BY_PREFIX
+        .iter()
+        .filter(|(network, _)| network.contains(addr))
+        .map(|&(_, entry)| entry)
+}
+
+/// マルチキャストアドレスの届く範囲（IPv4 は RFC 5771・RFC 2365、IPv6 は RFC 4291 のスコープ）
+fn multicast_scope(addr: &IpAddr) -> Scope {
+    match addr {
+        IpAddr::V4(Ipv4Addr {
+            a: 224, b: 0, c: 0, ..
+        }) => Scope::Link,
+        IpAddr::V4(Ipv4Addr { a: 239, .. }) => Scope::Private,
+        IpAddr::V4(_) => Scope::Global,
+        IpAddr::V6(Ipv6Addr { segments, .. }) => match segments[0] & 0xf {
+            0x1 => Scope::Host,
+            0x2 => Scope::Link,
+            0x3..=0x8 => Scope::Private,
+            0xe => Scope::Global,
+            _ => Scope::Reserved,
+        },
+    }
+}
+
+impl IpAddr {
+    /// アドレスを含む登録表の行（複数あればプレフィックス長の最も長い行）
+    ///
+    /// ```
+    /// use enums::IpAddr;
+    ///
+    /// let addr: IpAddr = "192.168.1.1".parse().unwrap();
+    /// let entry = addr.special_purpose().unwrap();
+    /// assert_eq!((entry.network, entry.name, entry.rfc), ("192.168.0.0/16", "Private-Use", "RFC 1918"));
+    /// ```
+    #[must_use]
+    pub fn special_purpose(&self) -> Option<&'static SpecialPurpose> {
+        entries(self).next()
+    }
+
+    fn purpose(&self) -> Option<Purpose> {
+        self.special_purpose().map(|entry| entry.purpose)
+    }
+
+    /// 未指定のアドレス（`0.0.0.0`, `::`）なら真
+    #[must_use]
+    pub fn is_unspecified(&self) -> bool {
+        self.purpose() == Some(Purpose::Unspecified)
+    }
+
+    /// ループバックアドレス（`127.0.0.0/8`, `::1`）なら真
+    #[must_use]
+    pub fn is_loopback(&self) -> bool {
+        self.purpose() == Some(Purpose::Loopback)
+    }
+
+    /// プライベートアドレス（RFC 1918 の3つのブロック、IPv6 のユニークローカル `fc00::/7`）なら真
+    #[must_use]
+    pub fn is_private(&self) -> bool {
+        self.purpose() == Some(Purpose::Private)
+    }
+
+    /// リンクローカルのユニキャストアドレス（`169.254.0.0/16`, `fe80::/10`）なら真
+    #[must_use]
+    pub fn is_link_local(&self) -> bool {
+        self.purpose() == Some(Purpose::LinkLocal)
+    }
+
+    /// ドキュメント用のアドレス（`192.0.2.0/24`, `2001:db8::/32` など）なら真
+    #[must_use]
+    pub fn is_documentation(&self) -> bool {
+        self.purpose() == Some(Purpose::Documentation)
+    }
+
+    /// ベンチマーク用のアドレス（`198.18.0.0/15`, `2001:2::/48`）なら真
+    #[must_use]
+    pub fn is_benchmarking(&self) -> bool {
+        self.purpose() == Some(Purpose::Benchmarking)
+    }
+
+    /// 限定ブロードキャストアドレス（`255.255.255.255`）なら真
+    #[must_use]
+    pub fn is_broadcast(&self) -> bool {
+        self.purpose() == Some(Purpose::Broadcast)
+    }
+
+    /// マルチキャストアドレス（`224.0.0.0/4`, `ff00::/8`）なら真
+    #[must_use]
+    pub fn is_multicast(&self) -> bool {
+        match self {
+            IpAddr::V4(addr) => addr.a >> 4 == 0xe,
+            IpAddr::V6(addr) => addr.segments[0] >> 8 == 0xff,
+        }
+    }
+
+    /// インターネット全体から届くアドレスなら真（`scope() == Scope::Global`）
+    ///
+    /// ```
+    /// use enums::IpAddr;
+    ///
+    /// let global = |s: &str| s.parse::<IpAddr>().unwrap().is_global();
+    /// assert!(global("8.8.8.8"));
+    /// assert!(global("2606:4700::1111"));
+    /// assert!(!global("10.0.0.1"));
+    /// assert!(!global("2001:db8::1"));
+    /// assert!(global("192.0.0.9")); // 192.0.0.0/24 の中のエニーキャスト
+    /// ```
+    #[must_use]
+    pub fn is_global(&self) -> bool {
+        self.scope() == Scope::Global
+    }
+
+    /// アドレスの届く範囲
+    ///
+    /// 登録表にないユニキャストアドレスは `Global`。登録表の行の種類が未指定・ループバックなら `Host`、
+    /// リンクローカル・ブロードキャストなら `Link`、プライベート・共有アドレスなら `Private`、
+    /// それ以外は「インターネット全体から届くか」の列に従って `Global` か `Reserved` になる
+    /// （「N/A」の行はそれを含むより大きい行に従う）。
+    #[must_use]
+    pub fn scope(&self) -> Scope {
+        if self.is_multicast() {
+            return multicast_scope(self);
+        }
+        let Some(entry) = self.special_purpose() else {
+            return Scope::Global;
+        };
+        match entry.purpose {
+            Purpose::Unspecified | Purpose::ThisNetwork | Purpose::Loopback => Scope::Host,
+            Purpose::LinkLocal | Purpose::Broadcast => Scope::Link,
+            Purpose::Private | Purpose::Shared => Scope::Private,
+            _ => match entries(self).find_map(|entry| entry.globally_reachable) {
+                Some(false) => Scope::Reserved,
+                Some(true) | None => Scope::Global,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_registry_is_well_formed() {
+        for entry in REGISTRY {
+            // ホスト部のビットが 0 のアドレスで書かれている
+            let (address, _) = entry.network.split_once('/').unwrap();
+            assert_eq!(entry.network().network(), addr(address), "{}", entry.name);
+        }
+        // 同じブロックが2回出てこない
+        for (i, a) in REGISTRY.iter().enumerate() {
+            assert!(REGISTRY[i + 1..].iter().all(|b| a.network != b.network));
+        }
+    }
+
+    #[test]
+    fn test_most_specific_entry() {
+        let name = |s: &str| addr(s).special_purpose().map(|entry| entry.name);
+        assert_eq!(name("192.0.0.9"), Some("Port Control Protocol Anycast"));
+        assert_eq!(name("192.0.0.100"), Some("IETF Protocol Assignments"));
+        assert_eq!(name("192.0.0.1"), Some("IPv4 Service Continuity Prefix"));
+        assert_eq!(name("0.0.0.0"), Some("This host on this network"));
+        assert_eq!(name("0.1.2.3"), Some("This network"));
+        assert_eq!(name("2001::1"), Some("TEREDO"));
+        assert_eq!(name("2001:1::1"), Some("Port Control Protocol Anycast"));
+        assert_eq!(name("8.8.8.8"), None);
+        assert_eq!(name("fe80::1%eth0"), Some("Link-Local Unicast"));
+    }
+
+    #[test]
+    fn test_predicates() {
+        assert!(addr("0.0.0.0").is_unspecified());
+        assert!(addr("::").is_unspecified());
+        assert!(!addr("0.0.0.1").is_unspecified());
+        assert!(addr("127.255.0.1").is_loopback());
+        assert!(addr("::1").is_loopback());
+        assert!(!addr("::ffff:127.0.0.1").is_loopback());
+        assert!(addr("172.31.255.255").is_private());
+        assert!(!addr("172.32.0.0").is_private());
+        assert!(addr("fd12:3456::1").is_private());
+        assert!(addr("169.254.1.1").is_link_local());
+        assert!(addr("febf::1").is_link_local());
+        assert!(!addr("fec0::1").is_link_local());
+        assert!(addr("203.0.113.7").is_documentation());
+        assert!(addr("3fff:ff::1").is_documentation());
+        assert!(addr("198.19.255.255").is_benchmarking());
+        assert!(addr("255.255.255.255").is_broadcast());
+        assert!(addr("224.0.0.1").is_multicast());
+        assert!(addr("239.255.255.255").is_multicast());
+        assert!(!addr("240.0.0.0").is_multicast());
+        assert!(addr("ff02::1").is_multicast());
+    }
+
+    #[test]
+    fn test_scope() {
+        let scope = |s: &str| addr(s).scope();
+        assert_eq!(scope("127.0.0.1"), Scope::Host);
+        assert_eq!(scope("::"), Scope::Host);
+        assert_eq!(scope("169.254.0.1"), Scope::Link);
+        assert_eq!(scope("255.255.255.255"), Scope::Link);
+        assert_eq!(scope("10.1.2.3"), Scope::Private);
+        assert_eq!(scope("100.64.0.1"), Scope::Private);
+        assert_eq!(scope("fc00::1"), Scope::Private);
+        assert_eq!(scope("192.0.2.1"), Scope::Reserved);
+        assert_eq!(scope("240.0.0.1"), Scope::Reserved);
+        assert_eq!(scope("192.0.0.10"), Scope::Global);
+        assert_eq!(scope("1.1.1.1"), Scope::Global);
+        // TEREDO は N/A なので 2001::/23 に従い、6to4 は含む行がないので Global
+        assert_eq!(scope("2001::1"), Scope::Reserved);
+        assert_eq!(scope("2002::1"), Scope::Global);
+        // マルチキャスト
+        assert_eq!(scope("224.0.0.251"), Scope::Link);
+        assert_eq!(scope("239.1.1.1"), Scope::Private);
+        assert_eq!(scope("233.252.0.1"), Scope::Global);
+        assert_eq!(scope("ff01::1"), Scope::Host);
+        assert_eq!(scope("ff02::fb"), Scope::Link);
+        assert_eq!(scope("ff05::2"), Scope::Private);
+        assert_eq!(scope("ff0e::1"), Scope::Global);
+        assert_eq!(scope("ff0f::1"), Scope::Reserved);
+    }
+}
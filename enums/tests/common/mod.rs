@@ -0,0 +1,81 @@
+//! 結合テストで共有する乱数列とデータの生成
+//!
+//! 各テストファイルから `mod common;` で読み込む。使わない関数があるファイルもあるので
+//! `dead_code` の警告は出さない。
+
+#![allow(dead_code)]
+
+use enums::Message;
+
+/// 1〜4バイトの文字と制御文字（バイナリ形式のテスト用）
+pub const MULTIBYTE_CHARS: &[char] = &['a', 'Z', ' ', '(', ',', ')', '\0', '\n', 'é', 'あ', '😀'];
+
+/// エスケープが必要な文字や空白・多バイト文字（表示と解析のテスト用）
+pub const ESCAPED_CHARS: &[char] = &[
+    'a', 'Z', ' ', '(', ')', ',', '\\', '\n', '\r', '\t', 'n', 'é', 'あ', '😀',
+];
+
+/// テスト用の決まった乱数列（線形合同法）
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    pub fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        self.0 >> 16
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        let n = u64::try_from(n).expect("usize は 64 ビット以下");
+        usize::try_from(self.next() % n).expect("n 未満")
+    }
+
+    /// `below` の `u8` 版（プレフィックス長などに使う）
+    pub fn below_u8(&mut self, n: u8) -> u8 {
+        u8::try_from(self.below(usize::from(n))).expect("n 未満")
+    }
+
+    pub fn v4(&mut self) -> u32 {
+        #[allow(clippy::cast_possible_truncation)] // 下位32ビットを使う
+        let bits = self.next() as u32;
+        bits
+    }
+
+    pub fn v6(&mut self) -> u128 {
+        (u128::from(self.next()) << 96) ^ (u128::from(self.next()) << 48) ^ u128::from(self.next())
+    }
+
+    /// 0・±1・最小値・最大値を多めに混ぜた i32
+    pub fn i32(&mut self) -> i32 {
+        match self.below(6) {
+            0 => [0, 1, -1, i32::MIN, i32::MAX][self.below(5)],
+            _ => {
+                // 下位32ビットを使う
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                let value = self.next() as i32;
+                value
+            }
+        }
+    }
+
+    /// `chars` の文字を `max_len` 個未満並べた文字列
+    pub fn text(&mut self, chars: &[char], max_len: usize) -> String {
+        let len = self.below(max_len);
+        (0..len).map(|_| chars[self.below(chars.len())]).collect()
+    }
+
+    /// `Write` の文字列は `text(chars, max_len)` で作る
+    pub fn message(&mut self, chars: &[char], max_len: usize) -> Message {
+        match self.below(4) {
+            0 => Message::Quit,
+            1 => Message::Move {
+                x: self.i32(),
+                y: self.i32(),
+            },
+            2 => Message::Write(self.text(chars, max_len)),
+            _ => Message::ChangeColor(self.i32(), self.i32(), self.i32()),
+        }
+    }
+}
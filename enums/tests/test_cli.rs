@@ -0,0 +1,162 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// `enums` コマンドを引数つきで実行する
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_enums"))
+        .args(args)
+        .output()
+        .expect("enums を実行できません")
+}
+
+/// `enums` コマンドを標準入力に `input` を与えて実行する
+fn run_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enums"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("enums を実行できません");
+    child
+        .stdin
+        .take()
+        .expect("標準入力")
+        .write_all(input.as_bytes())
+        .expect("標準入力に書き込めません");
+    child.wait_with_output().expect("enums の終了を待てません")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).expect("標準出力は UTF-8")
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).expect("標準エラー出力は UTF-8")
+}
+
+// =============================================================================
+// subnet コマンドのテスト
+// =============================================================================
+
+#[test]
+fn test_subnet_info() {
+    let output = run(&["subnet", "192.168.1.10/24"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "\
+ネットワーク: 192.168.1.0/24
+ネットマスク: 255.255.255.0
+ホストマスク: 0.0.0.255
+ネットワークアドレス: 192.168.1.0
+ブロードキャスト: 192.168.1.255
+ホスト: 192.168.1.1 〜 192.168.1.254
+アドレス数: 256
+ホスト数: 254
+上位ネットワーク: 192.168.0.0/23
+"
+    );
+}
+
+#[test]
+fn test_subnet_split() {
+    let output = run(&["subnet", "2001:db8::/32", "--split", "34"]);
+    assert_eq!(
+        stdout(&output),
+        "2001:db8::/34\n2001:db8:4000::/34\n2001:db8:8000::/34\n2001:db8:c000::/34\n"
+    );
+}
+
+#[test]
+fn test_subnet_hosts_are_limited() {
+    let output = run(&["subnet", "10.0.0.0/16", "--hosts"]);
+    let text = stdout(&output);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 257);
+    assert_eq!(lines[0], "10.0.0.1");
+    assert_eq!(lines[255], "10.0.1.0");
+    assert_eq!(lines[256], "…（全 65534 個のうち最初の 256 個）");
+}
+
+#[test]
+fn test_subnet_aggregate() {
+    let output = run(&[
+        "subnet",
+        "--aggregate",
+        "10.0.1.0/24",
+        "10.0.0.0/24",
+        "10.0.0.128/25",
+        "::/1",
+        "8000::/1",
+    ]);
+    assert_eq!(stdout(&output), "10.0.0.0/23\n::/0\n");
+}
+
+// =============================================================================
+// repl コマンドのテスト
+// =============================================================================
+
+#[test]
+fn test_repl() {
+    let input = "\
+Move(10, 20)
+ChangeColor(255, 0, 0)
+Write(Hello\\, world!)
+
+Move(1)
+Quit
+Write(読まれない)
+";
+    let output = run_with_input(&["repl"], input);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "\
+(10, 20) に移動しました
+色を (255, 0, 0) に変えました
+書き込み: Hello, world!
+エラー: Move の引数は 2 個必要です（1 個あります）
+終了します
+"
+    );
+}
+
+#[test]
+fn test_repl_ends_at_eof() {
+    let output = run_with_input(&["repl"], "Write(a)");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "書き込み: a\n");
+}
+
+// =============================================================================
+// 終了コードテスト
+// =============================================================================
+
+#[test]
+fn test_invalid_split_exits_with_1() {
+    let output = run(&["subnet", "10.0.0.0/8", "--split", "7"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "Error: プレフィックス長 /7 は /8 より短くできません\n"
+    );
+}
+
+#[test]
+fn test_invalid_arguments_exit_with_2() {
+    for args in [
+        &["subnet"][..],
+        &["subnet", "10.0.0.0"],
+        &["subnet", "10.0.0.0/33"],
+        &["subnet", "10.0.0.0/8", "--split"],
+        &["route"],
+        &["repl", "Quit"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        let message = stderr(&output);
+        assert!(message.starts_with("Error: "), "{args:?}");
+        assert!(message.contains("使い方: enums"), "{args:?}");
+    }
+}
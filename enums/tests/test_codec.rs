@@ -0,0 +1,240 @@
+mod common;
+
+use common::{Lcg, MULTIBYTE_CHARS};
+use enums::Message;
+use enums::codec::{
+    DEFAULT_MAX_LEN, DecodeError, Decoder, ReadError, decode, decode_prefix, encode,
+};
+use std::io::{self, Read};
+
+/// 毎回ばらばらの長さしか返さず、ときどき `Interrupted` になる `Read`
+struct ChoppyReader<'a> {
+    bytes: &'a [u8],
+    rng: Lcg,
+}
+
+impl Read for ChoppyReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.rng.below(8) == 0 {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let n = (self.rng.below(7) + 1).min(buf.len()).min(self.bytes.len());
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        self.bytes = &self.bytes[n..];
+        Ok(n)
+    }
+}
+
+// =============================================================================
+// 1つのフレームの往復
+// =============================================================================
+
+#[test]
+fn test_random_messages_round_trip() {
+    let mut rng = Lcg(3);
+    for _ in 0..20_000 {
+        let message = rng.message(MULTIBYTE_CHARS, 20);
+        let bytes = encode(&message);
+        assert_eq!(decode(&bytes), Ok(message.clone()), "{bytes:02x?}");
+        assert_eq!(
+            decode_prefix(&bytes),
+            Ok(Some((message, bytes.len()))),
+            "{bytes:02x?}"
+        );
+    }
+}
+
+#[test]
+fn test_every_prefix_is_incomplete() {
+    let mut rng = Lcg(9);
+    for _ in 0..2_000 {
+        let bytes = encode(&rng.message(MULTIBYTE_CHARS, 20));
+        for end in 0..bytes.len() {
+            assert_eq!(
+                decode_prefix(&bytes[..end]),
+                Ok(None),
+                "{bytes:02x?}[..{end}]"
+            );
+            assert_eq!(decode(&bytes[..end]), Err(DecodeError::UnexpectedEnd));
+        }
+    }
+}
+
+#[test]
+fn test_random_bytes_decode_canonically() {
+    // どんなバイト列でも panic せず、戻せたならエンコードし直すと元のバイト列になる
+    let mut rng = Lcg(17);
+    for _ in 0..50_000 {
+        let len = rng.below(16);
+        let mut bytes: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        if let Some(tag) = bytes.first_mut() {
+            *tag %= 5;
+        }
+        if bytes.len() > 4 && bytes[0] == 0x02 {
+            // 長さを小さくして、Write が最後まで読める場合も作る
+            bytes[1..4].fill(0);
+            bytes[4] %= 12;
+        }
+        match decode_prefix(&bytes) {
+            Ok(Some((message, used))) => assert_eq!(encode(&message), bytes[..used]),
+            Ok(None) => {}
+            Err(DecodeError::UnknownTag(tag)) => assert_eq!(tag, 0x04),
+            Err(DecodeError::InvalidUtf8(_)) => assert_eq!(bytes[0], 0x02),
+            Err(e) => panic!("{bytes:02x?}: {e}"),
+        }
+    }
+}
+
+// =============================================================================
+// Read からの読み込み
+// =============================================================================
+
+#[test]
+fn test_decoder_reads_partial_frames() {
+    let mut rng = Lcg(29);
+    for _ in 0..200 {
+        let messages: Vec<Message> = (0..rng.below(50))
+            .map(|_| rng.message(MULTIBYTE_CHARS, 20))
+            .collect();
+        let bytes: Vec<u8> = messages.iter().flat_map(encode).collect();
+        let reader = ChoppyReader {
+            bytes: &bytes,
+            rng: Lcg(rng.next()),
+        };
+        let decoded: Vec<Message> = Decoder::new(reader).map(Result::unwrap).collect();
+        assert_eq!(decoded, messages);
+    }
+}
+
+#[test]
+fn test_decoder_truncated_in_frame() {
+    let mut rng = Lcg(31);
+    for _ in 0..500 {
+        let mut messages: Vec<Message> = (0..rng.below(5))
+            .map(|_| rng.message(MULTIBYTE_CHARS, 20))
+            .collect();
+        // 途中で切れるように、最後は2バイト以上のフレームにする
+        messages.push(Message::Write(rng.text(MULTIBYTE_CHARS, 20)));
+        let mut bytes: Vec<u8> = messages.iter().flat_map(encode).collect();
+        let last = encode(messages.last().unwrap()).len();
+        // 最後のフレームを途中で切る（1バイト以上残す）
+        bytes.truncate(bytes.len() - last + 1 + rng.below(last - 1));
+        let reader = ChoppyReader {
+            bytes: &bytes,
+            rng: Lcg(rng.next()),
+        };
+        let results: Vec<_> = Decoder::new(reader).collect();
+        let (error, decoded) = results.split_last().unwrap();
+        assert!(matches!(
+            error,
+            Err(ReadError::Decode(DecodeError::UnexpectedEnd))
+        ));
+        let decoded: Vec<&Message> = decoded.iter().map(|r| r.as_ref().unwrap()).collect();
+        assert_eq!(
+            decoded,
+            messages[..messages.len() - 1].iter().collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_decoder_io_error() {
+    struct Broken;
+
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("接続が切れました"))
+        }
+    }
+
+    let error = Decoder::new(Broken).next().unwrap().unwrap_err();
+    assert!(matches!(error, ReadError::Io(_)));
+    assert_eq!(
+        error.to_string(),
+        "読み込みに失敗しました: 接続が切れました"
+    );
+}
+
+// =============================================================================
+// フレームの上限
+// =============================================================================
+
+/// ヘッダーの後に終わりなく同じバイトを返し、読まれたバイト数を数える `Read`
+struct Endless {
+    header: Vec<u8>,
+    read: usize,
+}
+
+impl Read for Endless {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        for byte in buf.iter_mut() {
+            *byte = self.header.get(self.read).copied().unwrap_or(b'a');
+            self.read += 1;
+        }
+        Ok(buf.len())
+    }
+}
+
+#[test]
+fn test_decoder_rejects_huge_length_before_buffering() {
+    // 4 GiB 近い長さを名乗る Write は、本体を溜める前にエラーになる
+    let mut reader = Endless {
+        header: vec![0x02, 0xff, 0xff, 0xff, 0xff],
+        read: 0,
+    };
+    let mut decoder = Decoder::new(&mut reader);
+    let error = decoder.read_message().unwrap_err();
+    assert!(matches!(
+        error,
+        ReadError::Decode(DecodeError::FrameTooLarge {
+            len: 4_294_967_300,
+            max_len: DEFAULT_MAX_LEN
+        })
+    ));
+    assert_eq!(
+        error.to_string(),
+        "フレームが長すぎます: 4294967300 バイト（上限 1048576 バイト）"
+    );
+    assert!(decoder.buffered() <= 4096);
+    assert!(decoder.next().is_none());
+    assert!(reader.read <= 4096);
+}
+
+#[test]
+fn test_decoder_max_len_boundary() {
+    let mut rng = Lcg(47);
+    for _ in 0..500 {
+        let messages: Vec<Message> = (0..rng.below(10))
+            .map(|_| rng.message(MULTIBYTE_CHARS, 20))
+            .collect();
+        let bytes: Vec<u8> = messages.iter().flat_map(encode).collect();
+        let max_len = rng.below(32);
+        let reader = ChoppyReader {
+            bytes: &bytes,
+            rng: Lcg(rng.next()),
+        };
+        // 上限以下のフレームは読め、最初の長すぎるフレームでエラーになって止まる
+        let results: Vec<_> = Decoder::with_max_len(reader, max_len).collect();
+        let fits = messages
+            .iter()
+            .take_while(|message| encode(message).len() <= max_len)
+            .count();
+        let decoded: Vec<&Message> = results[..fits]
+            .iter()
+            .map(|r| r.as_ref().unwrap())
+            .collect();
+        assert_eq!(decoded, messages[..fits].iter().collect::<Vec<_>>());
+        match messages.get(fits) {
+            Some(message) => {
+                assert_eq!(results.len(), fits + 1);
+                let len = encode(message).len();
+                assert!(matches!(
+                    results[fits],
+                    Err(ReadError::Decode(DecodeError::FrameTooLarge { len: l, max_len: m }))
+                        if l == len && m == max_len
+                ));
+            }
+            None => assert_eq!(results.len(), fits),
+        }
+    }
+}
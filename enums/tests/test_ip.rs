@@ -0,0 +1,324 @@
+mod common;
+
+use common::Lcg;
+use enums::{IpAddr, Ipv4Addr, Ipv6Addr, Zone};
+use std::net;
+
+/// 解析結果を `std::net` と比べる（ゾーン ID は `std::net` が扱わないので別に確かめる）
+fn check(input: &str) {
+    let ours = input.parse::<Ipv4Addr>().ok().map(|addr| addr.octets());
+    let expected = input
+        .parse::<net::Ipv4Addr>()
+        .ok()
+        .map(|addr| addr.octets());
+    assert_eq!(ours, expected, "Ipv4Addr: {input:?}");
+
+    let (addr, zone) = match input.split_once('%') {
+        Some((addr, zone)) => (addr, Some(zone)),
+        None => (input, None),
+    };
+    let zone_ok = zone.is_none_or(|zone| {
+        (1..=Zone::MAX_LEN).contains(&zone.len())
+            && zone
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || "-._~".contains(ch))
+    });
+    let ours = input.parse::<Ipv6Addr>().ok();
+    let expected = addr
+        .parse::<net::Ipv6Addr>()
+        .ok()
+        .filter(|_| zone_ok)
+        .map(|addr| addr.segments());
+    assert_eq!(
+        ours.as_ref().map(|addr| addr.segments),
+        expected,
+        "Ipv6Addr: {input:?}"
+    );
+    if let Some(ours) = ours {
+        assert_eq!(ours.zone.as_deref(), zone, "zone: {input:?}");
+    }
+
+    if zone.is_none() {
+        let ours = match input.parse::<IpAddr>() {
+            Ok(IpAddr::V4(addr)) => Some(net::IpAddr::from(addr.octets())),
+            Ok(IpAddr::V6(addr)) => Some(net::IpAddr::from(addr.segments)),
+            Err(_) => None,
+        };
+        assert_eq!(ours, input.parse::<net::IpAddr>().ok(), "IpAddr: {input:?}");
+    }
+}
+
+const VECTORS: &[&str] = &[
+    // IPv4
+    "0.0.0.0",
+    "127.0.0.1",
+    "255.255.255.255",
+    "192.168.0.1",
+    "1.2.3.4",
+    "10.0.0.255",
+    "256.0.0.0",
+    "1.2.3",
+    "1.2.3.4.5",
+    "1..2.3",
+    ".1.2.3",
+    "1.2.3.",
+    "01.2.3.4",
+    "1.2.3.00",
+    "1.2.3.0x1",
+    "1.2.3.-1",
+    "+1.2.3.4",
+    " 1.2.3.4",
+    "1.2.3.4 ",
+    "1.2.3.4\n",
+    "1.2.3.1000",
+    "1.2.3.4%eth0",
+    "０.0.0.0",
+    "",
+    ".",
+    "...",
+    // IPv6
+    "::",
+    "::1",
+    "1::",
+    ":::",
+    "::::",
+    ":",
+    "1:2:3:4:5:6:7:8",
+    "1:2:3:4:5:6:7:8:9",
+    "1:2:3:4:5:6:7",
+    "1:2:3:4:5:6:7::",
+    "::2:3:4:5:6:7:8",
+    "1::3:4:5:6:7:8",
+    "1:2:3:4:5:6:7:8::",
+    "::1:2:3:4:5:6:7:8",
+    "1:2:3:4::5:6:7:8",
+    "1::2::3",
+    "1:::2",
+    ":1::2",
+    "1::2:",
+    "2001:db8::8:800:200c:417a",
+    "2001:DB8::8:800:200C:417A",
+    "ff01::101",
+    "fe80::1",
+    "0:0:0:0:0:0:0:1",
+    "0000:0000:0000:0000:0000:0000:0000:0001",
+    "00000::",
+    "12345::",
+    "::ffff",
+    "::fffff",
+    "::g",
+    "::ffff:1.2.3.4",
+    "::ffff:192.0.2.128",
+    "::1.2.3.4",
+    "::1.2.3",
+    "::1.2.3.4.5",
+    "::1.2.3.256",
+    "::01.2.3.4",
+    "1:2:3:4:5:6:1.2.3.4",
+    "1:2:3:4:5:6:7:1.2.3.4",
+    "1:2:3:4:5::1.2.3.4",
+    "1:2:3:4:5:6::1.2.3.4",
+    "1.2.3.4::",
+    "::1.2.3.4:5",
+    "::1.2.3.4::",
+    "1.2.3.4:1::",
+    "64:ff9b::192.0.2.33",
+    "2001:db8:85a3::8a2e:370:7334",
+    "[::1]",
+    "::1/128",
+    "fe80::1%eth0",
+    "fe80::1%1",
+    "fe80::1%en0.1",
+    "fe80::1%",
+    "fe80::1%eth/0",
+    "fe80::1%a%b",
+    "%eth0",
+    "::ffff:1.2.3.4%lo",
+    "1:2:3:4:5:6:7:8%zone",
+    "fe80::1%abcdefghijklmno",
+    "fe80::1%abcdefghijklmnop",
+];
+
+// =============================================================================
+// std::net との比較
+// =============================================================================
+
+#[test]
+fn test_vectors_match_std() {
+    for input in VECTORS {
+        check(input);
+    }
+}
+
+#[test]
+fn test_random_addresses_match_std() {
+    let mut rng = Lcg(1);
+    for _ in 0..5_000 {
+        let v4 = net::Ipv4Addr::from(rng.next() as u32);
+        check(&v4.to_string());
+
+        let mut segments = [0_u16; 8];
+        for segment in &mut segments {
+            // 0 を多めにして `::` の省略が起きやすくする
+            *segment = if rng.below(3) == 0 {
+                0
+            } else {
+                rng.next() as u16
+            };
+        }
+        let v6 = net::Ipv6Addr::from(segments);
+        // 省略形・省略なし・大文字・4桁ゼロ埋め・末尾 IPv4
+        let expanded: Vec<String> = segments.iter().map(|s| format!("{s:x}")).collect();
+        let padded: Vec<String> = segments.iter().map(|s| format!("{s:04X}")).collect();
+        let [a, b] = segments[6].to_be_bytes();
+        let [c, d] = segments[7].to_be_bytes();
+        let with_v4 = format!("{}:{a}.{b}.{c}.{d}", expanded[..6].join(":"));
+        for input in [
+            v6.to_string(),
+            expanded.join(":"),
+            padded.join(":"),
+            with_v4,
+        ] {
+            check(&input);
+            assert_eq!(
+                input.parse::<Ipv6Addr>().map(|addr| addr.segments),
+                Ok(segments),
+                "{input:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_mutated_vectors_match_std() {
+    // 正しいアドレスの1文字を消す・置き換える・差し込む
+    const ALPHABET: &[char] = &['0', '1', '9', 'a', 'f', 'F', 'g', ':', '.', '%', ' '];
+    for input in VECTORS {
+        let chars: Vec<char> = input.chars().collect();
+        for i in 0..=chars.len() {
+            if i < chars.len() {
+                let mut removed = chars.clone();
+                removed.remove(i);
+                check(&removed.iter().collect::<String>());
+            }
+            for &ch in ALPHABET {
+                let mut inserted = chars.clone();
+                inserted.insert(i, ch);
+                check(&inserted.iter().collect::<String>());
+                if i < chars.len() {
+                    let mut replaced = chars.clone();
+                    replaced[i] = ch;
+                    check(&replaced.iter().collect::<String>());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_random_strings_match_std() {
+    const ALPHABET: &[u8] = b"0123456789abcdefABCDEF:::...%";
+    let mut rng = Lcg(42);
+    for _ in 0..100_000 {
+        let len = rng.below(24);
+        let input: String = (0..len)
+            .map(|_| char::from(ALPHABET[rng.below(ALPHABET.len())]))
+            .collect();
+        check(&input);
+    }
+}
+
+#[test]
+fn test_display_matches_std() {
+    let mut rng = Lcg(7);
+    for i in 0..20_000 {
+        let v4 = net::Ipv4Addr::from(rng.next() as u32);
+        let ours = Ipv4Addr::new(
+            v4.octets()[0],
+            v4.octets()[1],
+            v4.octets()[2],
+            v4.octets()[3],
+        );
+        assert_eq!(ours.to_string(), v4.to_string());
+
+        let mut segments = [0_u16; 8];
+        for segment in &mut segments {
+            // 0 の並びの長さや位置がいろいろになるように、半分以上を 0 にする
+            *segment = match rng.below(4) {
+                0 | 1 => 0,
+                2 => rng.below(16) as u16,
+                _ => rng.next() as u16,
+            };
+        }
+        // IPv4 射影・IPv4 互換のアドレスも混ぜる
+        match i % 10 {
+            0 => segments[..6].copy_from_slice(&[0, 0, 0, 0, 0, 0xffff]),
+            1 => segments[..6].fill(0),
+            _ => {}
+        }
+        let ours = Ipv6Addr::new(segments);
+        let expected = net::Ipv6Addr::from(segments);
+        assert_eq!(ours.to_string(), expected.to_string(), "{segments:x?}");
+        assert_eq!(format!("{ours:>42}"), format!("{expected:>42}"));
+        assert_eq!(format!("{ours:-^30}"), format!("{expected:-^30}"));
+        assert_eq!(ours.to_string().parse(), Ok(ours));
+    }
+}
+
+// =============================================================================
+// std::net・整数との変換
+// =============================================================================
+
+#[test]
+fn test_conversions_round_trip() {
+    let mut rng = Lcg(11);
+    for _ in 0..5_000 {
+        let bits = rng.next() as u32;
+        let v4 = Ipv4Addr::from(bits);
+        let std_v4 = net::Ipv4Addr::from(bits);
+        assert_eq!(net::Ipv4Addr::from(v4), std_v4);
+        assert_eq!(Ipv4Addr::from(std_v4), v4);
+        assert_eq!(Ipv4Addr::from(std_v4.octets()), v4);
+        assert_eq!(u32::from(v4), bits);
+        assert_eq!(<[u8; 4]>::from(v4), std_v4.octets());
+
+        let bits =
+            u128::from(rng.next()) << 80 ^ u128::from(rng.next()) << 32 ^ u128::from(rng.next());
+        let v6 = Ipv6Addr::from(bits);
+        let std_v6 = net::Ipv6Addr::from(bits);
+        assert_eq!(net::Ipv6Addr::try_from(v6), Ok(std_v6));
+        assert_eq!(Ipv6Addr::from(std_v6), v6);
+        assert_eq!(Ipv6Addr::from(std_v6.octets()), v6);
+        assert_eq!(v6.octets(), std_v6.octets());
+        assert_eq!(u128::try_from(v6), Ok(bits));
+
+        for std_addr in [net::IpAddr::V4(std_v4), net::IpAddr::V6(std_v6)] {
+            let ours = IpAddr::from(std_addr);
+            assert_eq!(ours.to_string(), std_addr.to_string());
+            assert_eq!(net::IpAddr::try_from(ours), Ok(std_addr));
+        }
+    }
+}
+
+// =============================================================================
+// エラーの表示テスト
+// =============================================================================
+
+#[test]
+fn test_error_messages() {
+    let cases = [
+        ("", "アドレスが空です"),
+        ("192.168.0.256", "位置 10: オクテットが 255 を超えています"),
+        ("1::2::3", "位置 4: `::` は1回しか使えません"),
+        ("1:2:3", "IPv6 のグループは8つ必要です（3 個あります）"),
+        ("fe80::1%", "位置 8: ゾーン ID が空です"),
+        (
+            "fe80::1%abcdefghijklmnop",
+            "位置 8: ゾーン ID が 15 バイトを超えています",
+        ),
+    ];
+    for (input, expected) in cases {
+        let error = input.parse::<IpAddr>().unwrap_err();
+        assert_eq!(error.to_string(), expected, "{input:?}");
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{ESCAPED_CHARS, Lcg};
+use enums::{Message, ParseMessageError};
+
+// =============================================================================
+// Display との往復
+// =============================================================================
+
+#[test]
+fn test_random_messages_round_trip() {
+    let mut rng = Lcg(37);
+    for _ in 0..20_000 {
+        let message = rng.message(ESCAPED_CHARS, 16);
+        let text = message.to_string();
+        assert_eq!(text.parse(), Ok(message), "{text:?}");
+        // 表示した文字列は1行に収まる（REPL で読める）
+        assert!(!text.contains(['\n', '\r']), "{text:?}");
+    }
+}
+
+#[test]
+fn test_parsed_messages_display_canonically() {
+    // 解析できた文字列は、表示し直すと同じメッセージに戻る
+    const ALPHABET: &[char] = &[
+        'M', 'o', 'v', 'e', 'W', 'r', 'i', 't', 'Q', 'u', '(', ')', ',', ' ', '\\', 'n', '-', '0',
+        '1', '9',
+    ];
+    let mut rng = Lcg(41);
+    let prefixes = ["Quit", "Move(", "Write(", "ChangeColor(", ""];
+    for _ in 0..100_000 {
+        let mut input = prefixes[rng.below(prefixes.len())].to_string();
+        for _ in 0..rng.below(12) {
+            input.push(ALPHABET[rng.below(ALPHABET.len())]);
+        }
+        if let Ok(message) = input.parse::<Message>() {
+            assert_eq!(
+                message.to_string().parse::<Message>(),
+                Ok(message),
+                "{input:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_mutated_displays_do_not_panic() {
+    // 正しい表示の1文字を消す・差し込むと、解析できないかエラーの位置が入力の中にある
+    let mut rng = Lcg(43);
+    for _ in 0..2_000 {
+        let text = rng.message(ESCAPED_CHARS, 16).to_string();
+        let chars: Vec<char> = text.chars().collect();
+        for i in 0..=chars.len() {
+            let mut variants = Vec::new();
+            if i < chars.len() {
+                let mut removed = chars.clone();
+                removed.remove(i);
+                variants.push(removed);
+            }
+            for ch in ['(', ')', ',', '\\', 'x'] {
+                let mut inserted = chars.clone();
+                inserted.insert(i, ch);
+                variants.push(inserted);
+            }
+            for variant in variants {
+                let input: String = variant.iter().collect();
+                let position = match input.parse::<Message>() {
+                    Err(
+                        ParseMessageError::Expected { position, .. }
+                        | ParseMessageError::InvalidNumber { position, .. }
+                        | ParseMessageError::UnescapedCharacter { position, .. }
+                        | ParseMessageError::InvalidEscape { position, .. }
+                        | ParseMessageError::TrailingCharacters { position },
+                    ) => position,
+                    _ => continue,
+                };
+                assert!(position <= input.len(), "{input:?}");
+                assert!(input.is_char_boundary(position), "{input:?}");
+            }
+        }
+    }
+}
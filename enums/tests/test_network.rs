@@ -0,0 +1,148 @@
+mod common;
+
+use common::Lcg;
+use enums::network::aggregate;
+use enums::{IpAddr, IpNetwork, Ipv4Addr, Ipv6Addr};
+
+fn v4_network(bits: u32, prefix: u8) -> IpNetwork {
+    IpNetwork::new(&IpAddr::V4(Ipv4Addr::from_bits(bits)), prefix).unwrap()
+}
+
+fn v6_network(bits: u128, prefix: u8) -> IpNetwork {
+    IpNetwork::new(&IpAddr::V6(Ipv6Addr::from_bits(bits)), prefix).unwrap()
+}
+
+// =============================================================================
+// ビット演算との比較
+// =============================================================================
+
+#[test]
+fn test_random_ipv4_networks() {
+    let mut rng = Lcg(3);
+    for _ in 0..5_000 {
+        let prefix = rng.below_u8(33);
+        let bits = rng.v4();
+        let network = v4_network(bits, prefix);
+        let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+
+        assert_eq!(
+            network.network(),
+            IpAddr::V4(Ipv4Addr::from_bits(bits & mask))
+        );
+        assert_eq!(
+            network.broadcast(),
+            IpAddr::V4(Ipv4Addr::from_bits(bits | !mask))
+        );
+        assert_eq!(network.netmask(), IpAddr::V4(Ipv4Addr::from_bits(mask)));
+        assert_eq!(network.to_string().parse(), Ok(network));
+
+        // 同じネットワークのアドレスと、ネットワーク部の1ビットを変えたアドレス
+        let inside = (bits & mask) | (rng.v4() & !mask);
+        assert!(network.contains(&IpAddr::V4(Ipv4Addr::from_bits(inside))));
+        if prefix > 0 {
+            let flipped = bits ^ (1 << (32 - rng.below(usize::from(prefix)) - 1));
+            assert!(!network.contains(&IpAddr::V4(Ipv4Addr::from_bits(flipped))));
+        }
+    }
+}
+
+#[test]
+fn test_random_ipv6_networks() {
+    let mut rng = Lcg(5);
+    for _ in 0..5_000 {
+        let prefix = rng.below_u8(129);
+        let bits = rng.v6();
+        let network = v6_network(bits, prefix);
+        let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+
+        assert_eq!(
+            network.network(),
+            IpAddr::V6(Ipv6Addr::from_bits(bits & mask))
+        );
+        assert_eq!(
+            network.broadcast(),
+            IpAddr::V6(Ipv6Addr::from_bits(bits | !mask))
+        );
+        assert_eq!(network.to_string().parse(), Ok(network));
+        assert!(network.contains(&IpAddr::V6(Ipv6Addr::from_bits(bits))));
+        #[allow(clippy::cast_possible_truncation)] // 下位32ビットだけの IPv4 アドレス
+        let low = bits as u32;
+        assert!(!network.contains(&IpAddr::V4(Ipv4Addr::from_bits(low))));
+    }
+}
+
+// =============================================================================
+// 分割と集約
+// =============================================================================
+
+#[test]
+fn test_subnets_partition_the_network() {
+    let mut rng = Lcg(11);
+    for _ in 0..500 {
+        let prefix = rng.below_u8(29) + 4;
+        let network = v4_network(rng.v4(), prefix);
+        let child = (prefix + rng.below_u8(5)).min(32);
+        let subnets: Vec<IpNetwork> = network.subnets(child).unwrap().collect();
+
+        assert_eq!(subnets.len(), 1 << (child - prefix));
+        // 隙間なく並び、全て元のネットワークに含まれ、集約すると元に戻る
+        for pair in subnets.windows(2) {
+            let IpAddr::V4(end) = pair[0].broadcast() else {
+                unreachable!()
+            };
+            let IpAddr::V4(start) = pair[1].network() else {
+                unreachable!()
+            };
+            assert_eq!(end.to_bits() + 1, start.to_bits());
+        }
+        assert!(
+            subnets
+                .iter()
+                .all(|subnet| network.contains_network(subnet))
+        );
+        assert_eq!(subnets[0].network(), network.network());
+        assert_eq!(subnets.last().unwrap().broadcast(), network.broadcast());
+        assert_eq!(aggregate(&subnets), [network]);
+    }
+}
+
+#[test]
+fn test_aggregate_covers_the_same_addresses() {
+    let mut rng = Lcg(17);
+    for _ in 0..200 {
+        // 10.0.0.0/24 の中の小さいネットワークをいくつか選び、アドレスごとに覆っているか比べる
+        let networks: Vec<IpNetwork> = (0..rng.below(12))
+            .map(|_| v4_network(0x0a00_0000 | (rng.v4() & 0xff), 24 + rng.below_u8(9)))
+            .collect();
+        let merged = aggregate(&networks);
+        for host in 0..=0xff {
+            let addr = IpAddr::V4(Ipv4Addr::from_bits(0x0a00_0000 | host));
+            let covered = networks.iter().any(|network| network.contains(&addr));
+            let count = merged
+                .iter()
+                .filter(|network| network.contains(&addr))
+                .count();
+            assert_eq!(count, usize::from(covered), "{addr} in {networks:?}");
+        }
+        // これ以上まとめられない
+        assert_eq!(aggregate(&merged), merged);
+    }
+}
+
+#[test]
+fn test_hosts_count_matches_host_count() {
+    for prefix in 20..=32 {
+        let network = v4_network(0xc0a8_0000, prefix);
+        assert_eq!(
+            network.hosts().count() as u128,
+            network.host_count().unwrap()
+        );
+    }
+    for prefix in 112..=128 {
+        let network = v6_network(0x2001_0db8 << 96, prefix);
+        assert_eq!(
+            network.hosts().count() as u128,
+            network.host_count().unwrap()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! `serde` 機能のテスト（`cargo test --features serde` で実行する）
+
+mod common;
+
+use common::Lcg;
+use enums::{IpAddr, Ipv4Addr, Ipv6Addr};
+use serde_test::{Configure, Token, assert_de_tokens_error, assert_tokens};
+use std::net;
+
+// =============================================================================
+// 人が読む形式（文字列）
+// =============================================================================
+
+#[test]
+fn test_json_round_trip() {
+    let cases = [
+        ("127.0.0.1", r#""127.0.0.1""#),
+        ("2001:DB8:0:0:0:0:0:1", r#""2001:db8::1""#),
+        ("::ffff:192.0.2.1", r#""::ffff:192.0.2.1""#),
+        ("fe80::1%eth0", r#""fe80::1%eth0""#),
+    ];
+    for (input, json) in cases {
+        let addr: IpAddr = input.parse().unwrap();
+        assert_eq!(serde_json::to_string(&addr).unwrap(), json);
+        assert_eq!(serde_json::from_str::<IpAddr>(json).unwrap(), addr);
+    }
+
+    let v4: Vec<Ipv4Addr> = serde_json::from_str(r#"["10.0.0.1", "10.0.0.2"]"#).unwrap();
+    assert_eq!(v4, [Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)]);
+    let v6: Ipv6Addr = serde_json::from_str(r#""::1""#).unwrap();
+    assert_eq!(v6, Ipv6Addr::new([0, 0, 0, 0, 0, 0, 0, 1]));
+}
+
+#[test]
+fn test_json_matches_std() {
+    let mut rng = Lcg(5);
+    for _ in 0..2_000 {
+        let v4 = net::IpAddr::from(net::Ipv4Addr::from(rng.next() as u32));
+        let v6 = net::IpAddr::from(net::Ipv6Addr::from(rng.v6()));
+        for std_addr in [v4, v6] {
+            let json = serde_json::to_string(&std_addr).unwrap();
+            assert_eq!(
+                serde_json::to_string(&IpAddr::from(std_addr)).unwrap(),
+                json
+            );
+            let ours: IpAddr = serde_json::from_str(&json).unwrap();
+            assert_eq!(net::IpAddr::try_from(ours), Ok(std_addr));
+        }
+    }
+}
+
+#[test]
+fn test_json_errors() {
+    let error = serde_json::from_str::<IpAddr>(r#""192.168.0.256""#).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "192.168.0.256: 位置 10: オクテットが 255 を超えています at line 1 column 15"
+    );
+    assert!(serde_json::from_str::<Ipv4Addr>(r#""::1""#).is_err());
+    assert!(serde_json::from_str::<IpAddr>("[127, 0, 0, 1]").is_err());
+}
+
+// =============================================================================
+// それ以外の形式（オクテットの並び）
+// =============================================================================
+
+#[test]
+fn test_compact_tokens() {
+    assert_tokens(
+        &Ipv4Addr::new(192, 0, 2, 1).compact(),
+        &[
+            Token::Tuple { len: 4 },
+            Token::U8(192),
+            Token::U8(0),
+            Token::U8(2),
+            Token::U8(1),
+            Token::TupleEnd,
+        ],
+    );
+
+    let scoped: Ipv6Addr = "fe80::1%eth0".parse().unwrap();
+    let mut tokens = vec![Token::Tuple { len: 2 }, Token::Tuple { len: 16 }];
+    tokens.extend(scoped.octets().map(Token::U8));
+    tokens.extend([
+        Token::TupleEnd,
+        Token::Some,
+        Token::Str("eth0"),
+        Token::TupleEnd,
+    ]);
+    assert_tokens(&scoped.compact(), &tokens);
+
+    tokens.insert(
+        0,
+        Token::NewtypeVariant {
+            name: "IpAddr",
+            variant: "V6",
+        },
+    );
+    assert_tokens(&IpAddr::V6(scoped).compact(), &tokens);
+}
+
+#[test]
+fn test_compact_errors() {
+    let mut tokens = vec![Token::Tuple { len: 2 }, Token::Tuple { len: 16 }];
+    tokens.extend([0; 16].map(Token::U8));
+    tokens.extend([
+        Token::TupleEnd,
+        Token::Some,
+        Token::Str("eth/0"),
+        Token::TupleEnd,
+    ]);
+    assert_de_tokens_error::<serde_test::Compact<Ipv6Addr>>(
+        &tokens,
+        "位置 3: '/' はアドレスに使えません",
+    );
+
+    let position = tokens.len() - 2;
+    tokens[position] = Token::Str("abcdefghijklmnop");
+    assert_de_tokens_error::<serde_test::Compact<Ipv6Addr>>(
+        &tokens,
+        "位置 0: ゾーン ID が 15 バイトを超えています",
+    );
+
+    assert_de_tokens_error::<serde_test::Compact<IpAddr>>(
+        &[Token::NewtypeVariant {
+            name: "IpAddr",
+            variant: "V5",
+        }],
+        "unknown variant `V5`, expected `V4` or `V6`",
+    );
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::Lcg;
+use enums::special::REGISTRY;
+use enums::{IpAddr, IpNetwork, Ipv4Addr, Ipv6Addr, Scope};
+use std::net;
+
+/// 登録表の各ブロックの中・マルチキャスト・ランダムなアドレスを混ぜて作る
+fn sample_addresses() -> Vec<IpAddr> {
+    let mut rng = Lcg(23);
+    let mut blocks: Vec<IpNetwork> = REGISTRY.iter().map(|entry| entry.network()).collect();
+    blocks.extend(
+        ["224.0.0.0/4", "ff00::/8", "0.0.0.0/0", "::/0"].map(|s| s.parse::<IpNetwork>().unwrap()),
+    );
+
+    let mut addresses = Vec::new();
+    for block in &blocks {
+        addresses.push(block.network());
+        addresses.push(block.broadcast());
+        for _ in 0..200 {
+            let random = rng.v6();
+            let addr = match block.network() {
+                IpAddr::V4(network) => {
+                    let IpAddr::V4(mask) = block.hostmask() else {
+                        unreachable!()
+                    };
+                    let host = random as u32 & mask.to_bits();
+                    IpAddr::V4(Ipv4Addr::from_bits(network.to_bits() | host))
+                }
+                IpAddr::V6(network) => {
+                    let IpAddr::V6(mask) = block.hostmask() else {
+                        unreachable!()
+                    };
+                    IpAddr::V6(Ipv6Addr::from_bits(
+                        network.to_bits() | (random & mask.to_bits()),
+                    ))
+                }
+            };
+            addresses.push(addr);
+        }
+    }
+    addresses
+}
+
+fn to_std(addr: &IpAddr) -> net::IpAddr {
+    match addr {
+        IpAddr::V4(addr) => net::IpAddr::from(addr.octets()),
+        IpAddr::V6(addr) => net::IpAddr::from(addr.segments),
+    }
+}
+
+// =============================================================================
+// std::net との比較
+// =============================================================================
+
+#[test]
+fn test_predicates_match_std() {
+    for addr in sample_addresses() {
+        let expected = to_std(&addr);
+        assert_eq!(addr.is_unspecified(), expected.is_unspecified(), "{addr}");
+        assert_eq!(addr.is_loopback(), expected.is_loopback(), "{addr}");
+        assert_eq!(addr.is_multicast(), expected.is_multicast(), "{addr}");
+        match expected {
+            net::IpAddr::V4(v4) => {
+                assert_eq!(addr.is_private(), v4.is_private(), "{addr}");
+                assert_eq!(addr.is_link_local(), v4.is_link_local(), "{addr}");
+                assert_eq!(addr.is_broadcast(), v4.is_broadcast(), "{addr}");
+                assert_eq!(addr.is_documentation(), v4.is_documentation(), "{addr}");
+            }
+            net::IpAddr::V6(v6) => {
+                assert_eq!(addr.is_private(), v6.is_unique_local(), "{addr}");
+                assert_eq!(addr.is_link_local(), v6.is_unicast_link_local(), "{addr}");
+            }
+        }
+    }
+}
+
+// =============================================================================
+// 登録表との整合性
+// =============================================================================
+
+#[test]
+fn test_scope_is_consistent_with_registry() {
+    for addr in sample_addresses() {
+        let scope = addr.scope();
+        assert_eq!(addr.is_global(), scope == Scope::Global, "{addr}");
+        if addr.is_loopback() || addr.is_unspecified() {
+            assert_eq!(scope, Scope::Host, "{addr}");
+        }
+        if addr.is_link_local() || addr.is_broadcast() {
+            assert_eq!(scope, Scope::Link, "{addr}");
+        }
+        if addr.is_private() {
+            assert_eq!(scope, Scope::Private, "{addr}");
+        }
+        if addr.is_documentation() || addr.is_benchmarking() {
+            assert_eq!(scope, Scope::Reserved, "{addr}");
+        }
+        // 表で「インターネット全体から届く」とされる行の中のアドレスはグローバル
+        if let Some(entry) = addr.special_purpose() {
+            match entry.globally_reachable {
+                Some(true) => assert_eq!(scope, Scope::Global, "{addr}"),
+                Some(false) => assert!(!addr.is_global(), "{addr}"),
+                None => {}
+            }
+        }
+    }
+}
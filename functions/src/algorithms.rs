@@ -0,0 +1,464 @@
+//! フィボナッチ数列の計算アルゴリズムの共通インターフェース
+//!
+//! 各アルゴリズムを `FibAlgorithm` として同じ形で呼び出し、
+//! 結果を `BigUint` で比べられるようにする。
+
+use crate::bigint::BigUint;
+use crate::binet::{self, fib_binet, fib_binet_dd};
+use crate::lookup;
+use crate::parallel;
+use crate::{fib, fib_big, BIG_MAX_N};
+use std::collections::HashMap;
+use std::fmt;
+
+/// u32 に収まる最大の n（F(47) = 2971215073）
+pub const MAX_U32_N: u64 = 47;
+
+/// メモ化再帰版で扱う最大の n（再帰の深さ = n のため）
+pub const MEMO_MAX_N: u64 = 1_000;
+
+/// 繰り返し版で扱う最大の n（多倍長の足し算を n 回繰り返すため、n = 10⁶ では十数秒かかる）
+pub const ITERATIVE_MAX_N: u64 = 100_000;
+
+/// アルゴリズムの時間計算量（演算回数のオーダー）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Complexity {
+    /// O(1)
+    Constant,
+    /// O(log n)
+    Logarithmic,
+    /// O(n)
+    Linear,
+    /// O(2^n)
+    Exponential,
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Complexity::Constant => "O(1)",
+            Complexity::Logarithmic => "O(log n)",
+            Complexity::Linear => "O(n)",
+            Complexity::Exponential => "O(2^n)",
+        };
+        f.pad(s)
+    }
+}
+
+/// フィボナッチ数列の計算アルゴリズム
+///
+/// ```
+/// use functions::{find_algorithm, FibAlgorithm};
+///
+/// let binet = find_algorithm("binet", 1).unwrap();
+/// assert_eq!(binet.fib(75).unwrap().to_string(), "2111485077978050");
+/// assert_eq!(binet.fib(76), None); // 精度の上限を超える
+/// ```
+pub trait FibAlgorithm {
+    /// アルゴリズムの名前（`fast-doubling` など）
+    fn name(&self) -> &'static str;
+
+    /// 時間計算量
+    fn complexity(&self) -> Complexity;
+
+    /// 正しい値を返せる最大の n
+    fn max_n(&self) -> u64;
+
+    /// F(n) を計算する（`n <= max_n()` であること）
+    fn compute(&self, n: u64) -> BigUint;
+
+    /// F(n) を計算する。`max_n()` を超える場合は `None` を返す
+    fn fib(&self, n: u64) -> Option<BigUint> {
+        (n <= self.max_n()).then(|| self.compute(n))
+    }
+}
+
+/// 素朴な再帰版（`fib` をそのまま使う）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Recursive;
+
+impl FibAlgorithm for Recursive {
+    fn name(&self) -> &'static str {
+        "recursive"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Exponential
+    }
+
+    fn max_n(&self) -> u64 {
+        MAX_U32_N
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // n <= MAX_U32_N
+    fn compute(&self, n: u64) -> BigUint {
+        BigUint::from(u64::from(fib(n as u32)))
+    }
+}
+
+/// 並列の再帰版（`parallel::fib_recursive`）
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelRecursive {
+    /// 使うスレッド数
+    pub threads: usize,
+}
+
+impl FibAlgorithm for ParallelRecursive {
+    fn name(&self) -> &'static str {
+        "parallel-recursive"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Exponential
+    }
+
+    fn max_n(&self) -> u64 {
+        MAX_U32_N
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // n <= MAX_U32_N
+    fn compute(&self, n: u64) -> BigUint {
+        BigUint::from(u64::from(parallel::fib_recursive(n as u32, self.threads)))
+    }
+}
+
+/// ビネの公式版（`fib_binet` をそのまま使う）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Binet;
+
+impl FibAlgorithm for Binet {
+    fn name(&self) -> &'static str {
+        "binet"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Constant
+    }
+
+    fn max_n(&self) -> u64 {
+        u64::from(binet::F64_MAX_N)
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // n <= F64_MAX_N
+    fn compute(&self, n: u64) -> BigUint {
+        BigUint::from(fib_binet(n as u32).unwrap_or_default())
+    }
+}
+
+/// double-double 版のビネの公式（`fib_binet_dd` をそのまま使う）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinetDoubleDouble;
+
+impl FibAlgorithm for BinetDoubleDouble {
+    fn name(&self) -> &'static str {
+        "binet-dd"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Logarithmic
+    }
+
+    fn max_n(&self) -> u64 {
+        u64::from(binet::DOUBLE_DOUBLE_MAX_N)
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // n <= DOUBLE_DOUBLE_MAX_N
+    fn compute(&self, n: u64) -> BigUint {
+        BigUint::from(fib_binet_dd(n as u32).unwrap_or_default())
+    }
+}
+
+/// 前の2項を足していく繰り返し版
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Iterative;
+
+impl FibAlgorithm for Iterative {
+    fn name(&self) -> &'static str {
+        "iterative"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Linear
+    }
+
+    fn max_n(&self) -> u64 {
+        ITERATIVE_MAX_N
+    }
+
+    fn compute(&self, n: u64) -> BigUint {
+        let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+        for _ in 0..n {
+            let next = &a + &b;
+            a = std::mem::replace(&mut b, next);
+        }
+        a
+    }
+}
+
+/// 計算済みの値を覚えておくメモ化再帰版
+///
+/// メモは呼び出しごとに作り直す（計測を公平にするため）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Memoized;
+
+impl Memoized {
+    fn fib_memo(n: u64, memo: &mut HashMap<u64, BigUint>) -> BigUint {
+        if n < 2 {
+            return BigUint::from(n);
+        }
+        if let Some(value) = memo.get(&n) {
+            return value.clone();
+        }
+        let value = &Self::fib_memo(n - 1, memo) + &Self::fib_memo(n - 2, memo);
+        memo.insert(n, value.clone());
+        value
+    }
+}
+
+impl FibAlgorithm for Memoized {
+    fn name(&self) -> &'static str {
+        "memoized"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Linear
+    }
+
+    fn max_n(&self) -> u64 {
+        MEMO_MAX_N
+    }
+
+    fn compute(&self, n: u64) -> BigUint {
+        Self::fib_memo(n, &mut HashMap::new())
+    }
+}
+
+/// 2×2 行列の累乗版
+///
+/// ```text
+/// [[1, 1], [1, 0]]^n = [[F(n+1), F(n)], [F(n), F(n-1)]]
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Matrix;
+
+/// 対称な 2×2 行列 [[a, b], [b, c]]（フィボナッチ行列の累乗は常に対称）
+struct Symmetric2 {
+    a: BigUint,
+    b: BigUint,
+    c: BigUint,
+}
+
+impl Symmetric2 {
+    fn mul(&self, other: &Self) -> Self {
+        Self {
+            a: &(&self.a * &other.a) + &(&self.b * &other.b),
+            b: &(&self.a * &other.b) + &(&self.b * &other.c),
+            c: &(&self.b * &other.b) + &(&self.c * &other.c),
+        }
+    }
+}
+
+impl FibAlgorithm for Matrix {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Logarithmic
+    }
+
+    fn max_n(&self) -> u64 {
+        BIG_MAX_N
+    }
+
+    fn compute(&self, n: u64) -> BigUint {
+        // 単位行列から始めて、二乗を繰り返しながら n のビットが立つところで掛ける
+        let mut result = Symmetric2 {
+            a: BigUint::one(),
+            b: BigUint::zero(),
+            c: BigUint::one(),
+        };
+        let mut base = Symmetric2 {
+            a: BigUint::one(),
+            b: BigUint::one(),
+            c: BigUint::zero(),
+        };
+        let mut k = n;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result.mul(&base);
+            }
+            k >>= 1;
+            if k > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result.b
+    }
+}
+
+/// 高速倍加法版（`fib_big` をそのまま使う）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastDoubling;
+
+impl FibAlgorithm for FastDoubling {
+    fn name(&self) -> &'static str {
+        "fast-doubling"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Logarithmic
+    }
+
+    fn max_n(&self) -> u64 {
+        BIG_MAX_N
+    }
+
+    fn compute(&self, n: u64) -> BigUint {
+        fib_big(n)
+    }
+}
+
+/// 並列の高速倍加法版（`parallel::fib_big_parallel`）
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelFastDoubling {
+    /// 使うスレッド数
+    pub threads: usize,
+}
+
+impl FibAlgorithm for ParallelFastDoubling {
+    fn name(&self) -> &'static str {
+        "parallel-doubling"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Logarithmic
+    }
+
+    fn max_n(&self) -> u64 {
+        BIG_MAX_N
+    }
+
+    fn compute(&self, n: u64) -> BigUint {
+        parallel::fib_big_parallel(n, self.threads)
+    }
+}
+
+/// コンパイル時に作った表を引く版（`lookup::get`）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lookup;
+
+impl FibAlgorithm for Lookup {
+    fn name(&self) -> &'static str {
+        "lookup"
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity::Constant
+    }
+
+    fn max_n(&self) -> u64 {
+        u64::from(lookup::MAX_U128_N)
+    }
+
+    fn compute(&self, n: u64) -> BigUint {
+        BigUint::from(lookup::get(n).unwrap_or_default())
+    }
+}
+
+/// すべてのアルゴリズム（遅いものから順）
+///
+/// 並列版は最大 `threads` 個のスレッドを使う
+#[must_use]
+pub fn all_algorithms(threads: usize) -> Vec<Box<dyn FibAlgorithm>> {
+    vec![
+        Box::new(Recursive),
+        Box::new(ParallelRecursive { threads }),
+        Box::new(Binet),
+        Box::new(BinetDoubleDouble),
+        Box::new(Iterative),
+        Box::new(Memoized),
+        Box::new(Matrix),
+        Box::new(FastDoubling),
+        Box::new(ParallelFastDoubling { threads }),
+        Box::new(Lookup),
+    ]
+}
+
+/// 名前からアルゴリズムを探す
+#[must_use]
+pub fn find_algorithm(name: &str, threads: usize) -> Option<Box<dyn FibAlgorithm>> {
+    all_algorithms(threads)
+        .into_iter()
+        .find(|algo| algo.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 相互検証で調べる n の上限（指数時間のものは時間がかかりすぎるため抑える）
+    fn check_limit(algo: &dyn FibAlgorithm) -> u64 {
+        let limit = match algo.complexity() {
+            Complexity::Exponential => 25,
+            Complexity::Linear => 500,
+            Complexity::Constant | Complexity::Logarithmic => 1_500,
+        };
+        algo.max_n().min(limit)
+    }
+
+    #[test]
+    fn test_all_algorithms_agree() {
+        // 高速倍加法の値を基準に比べる（基準自体も繰り返し版・行列版と比べるので、
+        // 両方が正しい範囲の n では全ての組が一致することになる）
+        let algorithms = all_algorithms(4);
+        let expected: Vec<BigUint> = (0..=1_500).map(|n| FastDoubling.compute(n)).collect();
+        for algo in &algorithms {
+            for n in 0..=check_limit(algo.as_ref()) {
+                assert_eq!(
+                    algo.fib(n).as_ref(),
+                    Some(&expected[n as usize]),
+                    "{}({n})",
+                    algo.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_range_boundary() {
+        for algo in all_algorithms(4) {
+            assert!(algo.max_n() <= BIG_MAX_N, "{}", algo.name());
+            assert_eq!(algo.fib(algo.max_n() + 1), None, "{}", algo.name());
+        }
+        assert_eq!(Binet.fib(75).unwrap().to_string(), "2111485077978050");
+        assert_eq!(
+            BinetDoubleDouble.fib(149).unwrap(),
+            FastDoubling.compute(149)
+        );
+        assert_eq!(
+            Memoized.fib(MEMO_MAX_N).unwrap(),
+            FastDoubling.compute(MEMO_MAX_N)
+        );
+    }
+
+    #[test]
+    fn test_matrix_large_n_matches_fast_doubling() {
+        for n in [10_000, 65_536, 100_001] {
+            assert_eq!(Matrix.compute(n), FastDoubling.compute(n), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_algorithm_names_are_unique() {
+        let names: Vec<_> = all_algorithms(1).iter().map(|a| a.name()).collect();
+        let mut unique = names.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len());
+        for name in names {
+            assert_eq!(find_algorithm(name, 1).map(|a| a.name()), Some(name));
+        }
+        assert!(find_algorithm("bogo", 1).is_none());
+    }
+}
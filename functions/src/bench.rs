@@ -0,0 +1,410 @@
+//! フィボナッチ数列の各アルゴリズムのベンチマーク
+//!
+//! criterion と同じ考え方の簡易ハーネス。ウォームアップで1回あたりの時間を見積もり、
+//! 1サンプルが目標時間になるよう繰り返し回数を決めてから、複数サンプルを計測して
+//! 統計量（平均・中央値・最小・最大・標準偏差）を求める。外部クレートは使わない。
+
+use crate::algorithms::{Complexity, FibAlgorithm};
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// 既定で計測する n
+pub const DEFAULT_NS: [u64; 10] = [10, 20, 30, 47, 75, 100, 1_000, 10_000, 100_000, 1_000_000];
+
+/// speedup で計測する並列版のアルゴリズムと n（逐次で数十 ms かかる大きさ）
+pub const SPEEDUP_CASES: [(&str, u64); 2] =
+    [("parallel-recursive", 35), ("parallel-doubling", 1_000_000)];
+
+/// ベンチマークの設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// ウォームアップ時間
+    pub warm_up: Duration,
+    /// 1サンプルの目標時間
+    pub sample_time: Duration,
+    /// サンプル数
+    pub samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warm_up: Duration::from_millis(100),
+            sample_time: Duration::from_millis(10),
+            samples: 20,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// 動作確認用の短い設定（ウォームアップなし、1回ずつ3サンプル）
+    #[must_use]
+    pub fn quick() -> Self {
+        Self {
+            warm_up: Duration::ZERO,
+            sample_time: Duration::ZERO,
+            samples: 3,
+        }
+    }
+}
+
+/// 1回あたりの時間（ナノ秒）の統計量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// 平均
+    pub mean: f64,
+    /// 中央値
+    pub median: f64,
+    /// 最小
+    pub min: f64,
+    /// 最大
+    pub max: f64,
+    /// 標準偏差（標本標準偏差）
+    pub stddev: f64,
+}
+
+impl Stats {
+    /// サンプル（1回あたりのナノ秒）から統計量を求める
+    ///
+    /// サンプルが空の場合は `None` を返す
+    #[must_use]
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        #[allow(clippy::cast_precision_loss)]
+        let count = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / count;
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            f64::midpoint(sorted[mid - 1], sorted[mid])
+        } else {
+            sorted[mid]
+        };
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (count - 1.0)
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            mean,
+            median,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            stddev: variance.sqrt(),
+        })
+    }
+}
+
+/// 1つのアルゴリズム・1つの n の計測結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    /// アルゴリズムの名前
+    pub algorithm: &'static str,
+    /// 計算した n
+    pub n: u64,
+    /// サンプル数
+    pub samples: usize,
+    /// 1サンプルあたりの繰り返し回数
+    pub iterations: u64,
+    /// 1回あたりの時間（ナノ秒）の統計量
+    pub stats: Stats,
+}
+
+/// `routine` の1回あたりの時間を計測する
+///
+/// 戻り値は（1サンプルあたりの繰り返し回数, 統計量）
+#[allow(clippy::missing_panics_doc)] // サンプル数は 1 以上に切り上げるので統計量は必ず求まる
+pub fn measure<T, F: FnMut() -> T>(config: &BenchConfig, mut routine: F) -> (u64, Stats) {
+    // ウォームアップしながら1回あたりの時間を見積もる
+    let start = Instant::now();
+    let mut warm_up_runs = 0_u32;
+    loop {
+        black_box(routine());
+        warm_up_runs += 1;
+        if start.elapsed() >= config.warm_up {
+            break;
+        }
+    }
+    let per_run = start.elapsed() / warm_up_runs;
+
+    let iterations = if per_run.is_zero() {
+        1
+    } else {
+        (config.sample_time.as_nanos() / per_run.as_nanos()).max(1)
+    };
+    let iterations = u64::try_from(iterations).unwrap_or(u64::MAX);
+
+    let samples: Vec<f64> = (0..config.samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(routine());
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let per_iteration = start.elapsed().as_nanos() as f64 / iterations as f64;
+            per_iteration
+        })
+        .collect();
+
+    let stats = Stats::from_samples(&samples).expect("サンプル数は 1 以上");
+    (iterations, stats)
+}
+
+/// ベンチマークで計測する n の上限（遅いアルゴリズムで時間がかかりすぎないよう抑える）
+#[must_use]
+pub fn bench_limit(algo: &dyn FibAlgorithm) -> u64 {
+    let limit = match algo.complexity() {
+        Complexity::Exponential => 30,
+        Complexity::Linear => 10_000,
+        Complexity::Constant | Complexity::Logarithmic => u64::MAX,
+    };
+    algo.max_n().min(limit)
+}
+
+/// 各アルゴリズムを、`ns` のうち計測できる n について計測する
+#[must_use]
+pub fn run_suite(
+    algorithms: &[Box<dyn FibAlgorithm>],
+    ns: &[u64],
+    config: &BenchConfig,
+) -> Vec<Measurement> {
+    let mut results = Vec::new();
+    for algo in algorithms {
+        let limit = bench_limit(algo.as_ref());
+        for &n in ns.iter().filter(|&&n| n <= limit) {
+            let (iterations, stats) = measure(config, || algo.compute(black_box(n)));
+            results.push(Measurement {
+                algorithm: algo.name(),
+                n,
+                samples: config.samples.max(1),
+                iterations,
+                stats,
+            });
+        }
+    }
+    results
+}
+
+/// speedup で計測するスレッド数（1 から `max` までの 2 の累乗と `max` 自身）
+#[must_use]
+pub fn thread_counts(max: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = std::iter::successors(Some(1_usize), |&k| k.checked_mul(2))
+        .take_while(|&k| k < max)
+        .collect();
+    counts.push(max.max(1));
+    counts
+}
+
+/// 計測結果を CSV に変換する
+#[must_use]
+pub fn to_csv(results: &[Measurement]) -> String {
+    let mut csv =
+        String::from("algorithm,n,samples,iterations,mean_ns,median_ns,min_ns,max_ns,stddev_ns\n");
+    for m in results {
+        let s = &m.stats;
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1}",
+            m.algorithm, m.n, m.samples, m.iterations, s.mean, s.median, s.min, s.max, s.stddev
+        );
+    }
+    csv
+}
+
+/// 計測結果を JSON の配列に変換する
+#[must_use]
+pub fn to_json(results: &[Measurement]) -> String {
+    let items: Vec<String> = results
+        .iter()
+        .map(|m| {
+            let s = &m.stats;
+            format!(
+                "  {{\"algorithm\": \"{}\", \"n\": {}, \"samples\": {}, \"iterations\": {}, \
+                 \"mean_ns\": {:.1}, \"median_ns\": {:.1}, \"min_ns\": {:.1}, \
+                 \"max_ns\": {:.1}, \"stddev_ns\": {:.1}}}",
+                m.algorithm, m.n, m.samples, m.iterations, s.mean, s.median, s.min, s.max, s.stddev
+            )
+        })
+        .collect();
+    if items.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", items.join(",\n"))
+    }
+}
+
+/// n ごとに、中央値の速い順にアルゴリズムを並べた比較表（Markdown）を作る
+#[must_use]
+pub fn report(results: &[Measurement]) -> String {
+    let mut ns: Vec<u64> = results.iter().map(|m| m.n).collect();
+    ns.sort_unstable();
+    ns.dedup();
+
+    let mut out = String::new();
+    for n in ns {
+        let mut rows: Vec<&Measurement> = results.iter().filter(|m| m.n == n).collect();
+        rows.sort_by(|a, b| a.stats.median.total_cmp(&b.stats.median));
+        let fastest = rows[0].stats.median.max(f64::MIN_POSITIVE);
+
+        let _ = writeln!(out, "### n = {n}\n");
+        out.push_str("| アルゴリズム | 中央値 | 標準偏差 | 最速との比 |\n");
+        out.push_str("| --- | ---: | ---: | ---: |\n");
+        for m in rows {
+            let _ = writeln!(
+                out,
+                "| {} | {} | ± {} | {:.2}x |",
+                m.algorithm,
+                format_ns(m.stats.median),
+                format_ns(m.stats.stddev),
+                m.stats.median / fastest
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// ナノ秒を読みやすい単位の文字列にする
+#[must_use]
+pub fn format_ns(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{ns:.0} ns")
+    } else if ns < 1e6 {
+        format!("{:.2} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.2} ms", ns / 1e6)
+    } else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::all_algorithms;
+
+    fn measurement(algorithm: &'static str, n: u64, median: f64) -> Measurement {
+        Measurement {
+            algorithm,
+            n,
+            samples: 3,
+            iterations: 10,
+            stats: Stats {
+                mean: median,
+                median,
+                min: median,
+                max: median,
+                stddev: 0.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let stats = Stats::from_samples(&[4.0, 1.0, 3.0, 2.0]).unwrap();
+        assert!((stats.mean - 2.5).abs() < 1e-12);
+        assert!((stats.median - 2.5).abs() < 1e-12);
+        assert!((stats.min - 1.0).abs() < 1e-12);
+        assert!((stats.max - 4.0).abs() < 1e-12);
+        // 標本分散 = (2.25 + 0.25 + 0.25 + 2.25) / 3
+        assert!((stats.stddev - (5.0_f64 / 3.0).sqrt()).abs() < 1e-12);
+
+        let single = Stats::from_samples(&[7.0]).unwrap();
+        assert!((single.median - 7.0).abs() < 1e-12);
+        assert!(single.stddev.abs() < 1e-12);
+
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_run_suite_respects_limits() {
+        let ns = [10, 40, 2_000, 20_000];
+        let results = run_suite(&all_algorithms(2), &ns, &BenchConfig::quick());
+
+        let has = |name: &str, n: u64| results.iter().any(|m| m.algorithm == name && m.n == n);
+        // 指数時間の再帰版は n ≤ 30 だけ
+        assert!(has("recursive", 10));
+        assert!(!has("recursive", 40));
+        // メモ化再帰版は max_n = 1000 まで
+        assert!(!has("memoized", 2_000));
+        // 線形時間のものは n ≤ 10000 まで
+        assert!(has("iterative", 2_000));
+        assert!(!has("iterative", 20_000));
+        // 対数時間のものは全て
+        assert!(has("fast-doubling", 20_000));
+        assert!(has("matrix", 20_000));
+
+        for m in &results {
+            assert_eq!(m.samples, 3);
+            assert!(m.iterations >= 1);
+            assert!(m.stats.min <= m.stats.median && m.stats.median <= m.stats.max);
+        }
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&[measurement("matrix", 100, 1234.56)]);
+        assert_eq!(
+            csv,
+            "algorithm,n,samples,iterations,mean_ns,median_ns,min_ns,max_ns,stddev_ns\n\
+             matrix,100,3,10,1234.6,1234.6,1234.6,1234.6,0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(to_json(&[]), "[]\n");
+        let json = to_json(&[
+            measurement("matrix", 100, 2.0),
+            measurement("binet", 10, 1.0),
+        ]);
+        assert!(json.starts_with("[\n  {\"algorithm\": \"matrix\", \"n\": 100,"));
+        assert!(json.contains("\"median_ns\": 2.0"));
+        assert!(json.contains("},\n  {\"algorithm\": \"binet\""));
+        assert!(json.ends_with("}\n]\n"));
+    }
+
+    #[test]
+    fn test_report_sorts_by_median() {
+        let results = [
+            measurement("matrix", 100, 3000.0),
+            measurement("fast-doubling", 100, 1000.0),
+            measurement("iterative", 10, 50.0),
+        ];
+        let report = report(&results);
+        // n の小さい順、同じ n では速い順
+        let n10 = report.find("### n = 10\n").unwrap();
+        let n100 = report.find("### n = 100\n").unwrap();
+        assert!(n10 < n100);
+        let fast = report
+            .find("| fast-doubling | 1.00 µs | ± 0 ns | 1.00x |")
+            .unwrap();
+        let slow = report
+            .find("| matrix | 3.00 µs | ± 0 ns | 3.00x |")
+            .unwrap();
+        assert!(fast < slow);
+    }
+
+    #[test]
+    fn test_thread_counts() {
+        assert_eq!(thread_counts(0), [1]);
+        assert_eq!(thread_counts(1), [1]);
+        assert_eq!(thread_counts(4), [1, 2, 4]);
+        assert_eq!(thread_counts(6), [1, 2, 4, 6]);
+        assert_eq!(thread_counts(16), [1, 2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn test_format_ns() {
+        assert_eq!(format_ns(12.3), "12 ns");
+        assert_eq!(format_ns(1_500.0), "1.50 µs");
+        assert_eq!(format_ns(2_500_000.0), "2.50 ms");
+        assert_eq!(format_ns(3_000_000_000.0), "3.00 s");
+    }
+}
@@ -0,0 +1,641 @@
+//! 任意精度の符号なし整数
+//!
+//! フィボナッチ数を正確に計算するための最小限の多倍長整数。
+//! 10^18 を基数とする u64 の配列（下位の桁から順）で値を保持するので、
+//! 10 進数への変換は各桁を並べるだけで済む。
+//! 負の係数を持つ線形漸化式のために、符号と絶対値からなる `BigInt` も用意する。
+
+use crate::parallel;
+use std::cmp::Ordering;
+use std::fmt::{self, Write};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// 1 リム（配列の1要素）あたりの基数
+const BASE: u64 = 1_000_000_000_000_000_000;
+
+/// 1 リムあたりの 10 進桁数
+const BASE_DIGITS: usize = 18;
+
+/// これより短いリム列の掛け算は筆算で行う（カラツバ法の閾値）
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// これより短いリム列の掛け算は並列にしない（約 9000 桁。スレッドを作る時間の方が長くなる）
+pub const PARALLEL_THRESHOLD: usize = 512;
+
+/// 任意精度の符号なし整数
+///
+/// 0 は空の配列で表し、最上位のリムは常に 0 以外
+///
+/// ```
+/// use functions::BigUint;
+///
+/// let a = BigUint::from(u128::MAX);
+/// let square = &a * &a;
+/// assert_eq!(square.digit_count(), 78);
+/// assert_eq!(&(&square + &a) - &square, a);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    /// 0
+    #[must_use]
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// 1
+    #[must_use]
+    pub fn one() -> Self {
+        Self::from(1_u64)
+    }
+
+    /// 10 進数での桁数（0 は 1 桁）
+    #[must_use]
+    pub fn digit_count(&self) -> usize {
+        match self.limbs.last() {
+            None => 1,
+            Some(top) => (self.limbs.len() - 1) * BASE_DIGITS + top.to_string().len(),
+        }
+    }
+
+    /// リム（10^18 進の桁）の個数
+    #[must_use]
+    pub fn limb_count(&self) -> usize {
+        self.limbs.len()
+    }
+
+    /// 最大 `threads` 個のスレッドを使って掛け算をする
+    ///
+    /// カラツバ法の3つの部分積を並列に計算する。結果は `*` と同じ
+    #[must_use]
+    pub fn mul_parallel(&self, rhs: &BigUint, threads: usize) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs, threads))
+    }
+
+    /// u128 に変換する（収まらなければ `None`）
+    #[must_use]
+    pub fn to_u128(&self) -> Option<u128> {
+        let base = u128::from(BASE);
+        self.limbs.iter().rev().try_fold(0_u128, |acc, &limb| {
+            acc.checked_mul(base)?.checked_add(u128::from(limb))
+        })
+    }
+
+    /// m で割った余り（m は 0 以外）
+    ///
+    /// # Panics
+    /// m が 0 の場合
+    #[must_use]
+    pub fn rem_u64(&self, m: u64) -> u64 {
+        assert!(m != 0, "0 で割ることはできません");
+        let (base, m) = (u128::from(BASE), u128::from(m));
+        let rem = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0, |rem, &limb| (rem * base + u128::from(limb)) % m);
+        // 余りは m (u64) 未満
+        u64::try_from(rem).unwrap_or_default()
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        trim(&mut limbs);
+        Self { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from_limbs(vec![value % BASE, value / BASE])
+    }
+}
+
+impl From<u128> for BigUint {
+    // 剰余と商はどちらも BASE 未満なので u64 への変換で切り捨ては起きない
+    #[allow(clippy::cast_possible_truncation)]
+    fn from(value: u128) -> Self {
+        let base = u128::from(BASE);
+        Self::from_limbs(vec![
+            (value % base) as u64,
+            (value / base % base) as u64,
+            (value / base / base) as u64,
+        ])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return f.pad_integral(true, "", "0");
+        };
+        let mut digits = String::with_capacity(self.digit_count());
+        digits.push_str(&top.to_string());
+        for limb in rest.iter().rev() {
+            write!(digits, "{limb:018}")?;
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(add_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    /// # Panics
+    /// 結果が負になる場合にパニックする
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        assert!(self >= rhs, "BigUint の引き算で結果が負になりました");
+        let mut limbs = self.limbs.clone();
+        sub_assign_limbs(&mut limbs, &rhs.limbs);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs, 1))
+    }
+}
+
+/// 任意精度の符号つき整数（符号と絶対値）
+///
+/// 0 の符号は常に正とする
+///
+/// ```
+/// use functions::bigint::BigInt;
+///
+/// let a = BigInt::from(-3_i64);
+/// let b = BigInt::from(5_i64);
+/// assert_eq!((&a * &b).to_string(), "-15");
+/// assert_eq!((&a + &b).to_string(), "2");
+/// assert_eq!((&a - &a), BigInt::zero());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    /// 0
+    #[must_use]
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// 1
+    #[must_use]
+    pub fn one() -> Self {
+        Self::from(BigUint::one())
+    }
+
+    /// 負なら真（0 は負ではない）
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// 絶対値
+    #[must_use]
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// i128 に変換する（収まらなければ `None`）
+    #[must_use]
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.negative {
+            0_i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// m で割った 0 以上 m 未満の余り（m は 0 以外）
+    ///
+    /// # Panics
+    /// m が 0 の場合
+    #[must_use]
+    pub fn rem_euclid_u64(&self, m: u64) -> u64 {
+        let rem = self.magnitude.rem_u64(m);
+        if self.negative && rem != 0 {
+            m - rem
+        } else {
+            rem
+        }
+    }
+
+    fn new(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && magnitude != BigUint::zero(),
+            magnitude,
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::new(false, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::new(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        Self::new(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+        // 符号が違えば、絶対値の大きい方から小さい方を引き、大きい方の符号にする
+        if self.magnitude >= rhs.magnitude {
+            BigInt::new(self.negative, &self.magnitude - &rhs.magnitude)
+        } else {
+            BigInt::new(rhs.negative, &rhs.magnitude - &self.magnitude)
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != rhs.negative,
+            &self.magnitude * &rhs.magnitude,
+        )
+    }
+}
+
+/// 上位の 0 のリムを取り除く
+fn trim(limbs: &mut Vec<u64>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+/// 上位の 0 を除いたリム列
+fn trimmed(limbs: &[u64]) -> &[u64] {
+    let len = limbs.iter().rposition(|&l| l != 0).map_or(0, |i| i + 1);
+    &limbs[..len]
+}
+
+fn cmp_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    let (a, b) = (trimmed(a), trimmed(b));
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = long.to_vec();
+    add_shifted(&mut result, short, 0);
+    result
+}
+
+/// `acc += x * BASE^shift`（`acc` は必要に応じて伸ばす）
+fn add_shifted(acc: &mut Vec<u64>, x: &[u64], shift: usize) {
+    if acc.len() < shift + x.len() {
+        acc.resize(shift + x.len(), 0);
+    }
+    let mut carry = 0;
+    for (i, &limb) in x.iter().enumerate() {
+        let sum = acc[shift + i] + limb + carry;
+        (acc[shift + i], carry) = if sum >= BASE {
+            (sum - BASE, 1)
+        } else {
+            (sum, 0)
+        };
+    }
+    let mut i = shift + x.len();
+    while carry > 0 {
+        if i == acc.len() {
+            acc.push(0);
+        }
+        let sum = acc[i] + carry;
+        (acc[i], carry) = if sum >= BASE {
+            (sum - BASE, 1)
+        } else {
+            (sum, 0)
+        };
+        i += 1;
+    }
+}
+
+/// `acc -= x`（`acc >= x` であること）
+fn sub_assign_limbs(acc: &mut [u64], x: &[u64]) {
+    let mut borrow = 0;
+    for (i, slot) in acc.iter_mut().enumerate() {
+        let sub = x.get(i).copied().unwrap_or(0) + borrow;
+        if sub == 0 && i >= x.len() {
+            break;
+        }
+        (*slot, borrow) = if *slot >= sub {
+            (*slot - sub, 0)
+        } else {
+            (*slot + BASE - sub, 1)
+        };
+    }
+    debug_assert_eq!(borrow, 0, "引かれる数の方が小さい");
+}
+
+/// 最大 `threads` 個のスレッドを使う掛け算
+fn mul_limbs(a: &[u64], b: &[u64], threads: usize) -> Vec<u64> {
+    let (a, b) = (trimmed(a), trimmed(b));
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if short.len() < KARATSUBA_THRESHOLD {
+        schoolbook(long, short)
+    } else if short.len() * 2 <= long.len() {
+        // 長さが大きく違うときは、長い方を短い方の長さで区切って掛ける
+        let mut result = Vec::with_capacity(long.len() + short.len());
+        for (i, chunk) in long.chunks(short.len()).enumerate() {
+            add_shifted(
+                &mut result,
+                &mul_limbs(chunk, short, threads),
+                i * short.len(),
+            );
+        }
+        result
+    } else {
+        karatsuba(long, short, threads)
+    }
+}
+
+/// 筆算による掛け算 O(n·m)
+// 剰余と商はどちらも BASE 未満なので u64 への変換で切り捨ては起きない
+#[allow(clippy::cast_possible_truncation)]
+fn schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0 {
+            continue;
+        }
+        let mut carry = 0_u128;
+        for (j, &y) in b.iter().enumerate() {
+            // (BASE-1) + (BASE-1)^2 + (BASE-1) = BASE^2 - 1 なので u128 に収まる
+            let t = u128::from(result[i + j]) + u128::from(x) * u128::from(y) + carry;
+            result[i + j] = (t % u128::from(BASE)) as u64;
+            carry = t / u128::from(BASE);
+        }
+        result[i + b.len()] = carry as u64;
+    }
+    result
+}
+
+/// カラツバ法による掛け算 O(n^1.585)
+///
+/// `a.len() >= b.len() > a.len() / 2` であること。
+/// `b` が `PARALLEL_THRESHOLD` リム以上なら3つの部分積を並列に計算する
+fn karatsuba(a: &[u64], b: &[u64], threads: usize) -> Vec<u64> {
+    let m = a.len() / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+    let (sum_a, sum_b) = (add_limbs(a0, a1), add_limbs(b0, b1));
+
+    let threads = if b.len() >= PARALLEL_THRESHOLD {
+        threads
+    } else {
+        1
+    };
+    let (z0, (z2, mut z1)) = parallel::join(
+        threads,
+        |t| mul_limbs(a0, b0, t),
+        |t| {
+            parallel::join(
+                t,
+                |t| mul_limbs(a1, b1, t),
+                |t| mul_limbs(&sum_a, &sum_b, t),
+            )
+        },
+    );
+    // z1 = (a0 + a1)(b0 + b1) - z0 - z2
+    sub_assign_limbs(&mut z1, &z0);
+    sub_assign_limbs(&mut z1, &z2);
+
+    let mut result = z0;
+    result.reserve(a.len() + b.len());
+    add_shifted(&mut result, trimmed(&z1), m);
+    add_shifted(&mut result, trimmed(&z2), 2 * m);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        // テスト用: 10 進文字列を 18 桁ずつ区切って作る
+        let limbs = s
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap().parse().unwrap())
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    #[test]
+    fn test_from_and_display() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::one().to_string(), "1");
+        assert_eq!(BigUint::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert_eq!(BigUint::from(BASE).to_string(), "1000000000000000000");
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(format!("{:>5}", BigUint::from(42_u64)), "   42");
+    }
+
+    #[test]
+    fn test_digit_count() {
+        assert_eq!(BigUint::zero().digit_count(), 1);
+        assert_eq!(BigUint::from(9_u64).digit_count(), 1);
+        assert_eq!(BigUint::from(BASE - 1).digit_count(), 18);
+        assert_eq!(BigUint::from(BASE).digit_count(), 19);
+        assert_eq!(BigUint::from(u64::MAX).digit_count(), 20);
+    }
+
+    #[test]
+    fn test_to_u128() {
+        for value in [0, 1, u128::from(BASE), u128::from(u64::MAX), u128::MAX] {
+            assert_eq!(BigUint::from(value).to_u128(), Some(value));
+        }
+        assert_eq!(
+            (&BigUint::from(u128::MAX) + &BigUint::one()).to_u128(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rem_u64() {
+        assert_eq!(BigUint::zero().rem_u64(7), 0);
+        assert_eq!(BigUint::from(u128::MAX).rem_u64(1), 0);
+        for m in [2, 7, 1_000_000_007, u64::MAX] {
+            assert_eq!(
+                u128::from(BigUint::from(u128::MAX).rem_u64(m)),
+                u128::MAX % u128::from(m)
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_sub_with_carry() {
+        let a = big("999999999999999999999999999999999999");
+        let one = BigUint::one();
+        let sum = &a + &one;
+        assert_eq!(sum.to_string(), format!("1{}", "0".repeat(36)));
+        assert_eq!(&sum - &one, a);
+        assert_eq!(&a - &a, BigUint::zero());
+        assert!(sum > a);
+    }
+
+    #[test]
+    #[should_panic(expected = "負")]
+    fn test_sub_underflow_panics() {
+        let _ = &BigUint::one() - &BigUint::from(2_u64);
+    }
+
+    #[test]
+    fn test_mul_matches_u128() {
+        let values = [0, 1, 7, BASE - 1, BASE, u64::MAX];
+        for &x in &values {
+            for &y in &values {
+                let product = &BigUint::from(x) * &BigUint::from(y);
+                assert_eq!(
+                    product.to_string(),
+                    (u128::from(x) * u128::from(y)).to_string()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_karatsuba_matches_schoolbook() {
+        // 閾値をまたぐ長さ・長さの違う組み合わせで、筆算の結果と比べる
+        let limbs = |len: usize, seed: u64| -> Vec<u64> {
+            (0..len as u64)
+                .map(|i| (i.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ seed) % BASE)
+                .collect()
+        };
+        for (la, lb) in [(32, 32), (33, 40), (100, 64), (150, 31), (257, 130)] {
+            let (a, b) = (limbs(la, 1), limbs(lb, 2));
+            let mut expected = schoolbook(&a, &b);
+            trim(&mut expected);
+            let mut actual = mul_limbs(&a, &b, 1);
+            trim(&mut actual);
+            assert_eq!(actual, expected, "{la} x {lb}");
+        }
+    }
+
+    #[test]
+    fn test_bigint_matches_i128() {
+        let values = [
+            0,
+            1,
+            -1,
+            7,
+            -7,
+            i128::from(BASE),
+            -i128::from(BASE),
+            i128::from(i64::MIN),
+        ];
+        for &a in &values {
+            assert_eq!(BigInt::from(a).to_string(), a.to_string());
+            assert_eq!(BigInt::from(a).to_i128(), Some(a));
+            assert_eq!(BigInt::from(a).rem_euclid_u64(7), a.rem_euclid(7) as u64);
+            for &b in &values {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!((&x + &y).to_i128(), Some(a + b), "{a} + {b}");
+                assert_eq!((&x - &y).to_i128(), Some(a - b), "{a} - {b}");
+                assert_eq!((&x * &y).to_i128(), Some(a * b), "{a} * {b}");
+            }
+        }
+        // 0 に符号はつかない
+        assert_eq!(&BigInt::from(-5_i64) + &BigInt::from(5_i64), BigInt::zero());
+        assert!(!(-&BigInt::zero()).is_negative());
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!((-&BigInt::from(i128::MIN)).to_i128(), None);
+        assert_eq!(format!("{:>4}", BigInt::from(-42_i64)), " -42");
+    }
+
+    #[test]
+    fn test_mul_parallel_matches_mul() {
+        // 並列にする閾値をまたぐ長さで、スレッド数を変えても結果が同じ
+        let number = |len: usize, seed: u64| {
+            BigUint::from_limbs(
+                (1..=len as u64)
+                    .map(|i| (i.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ seed) % BASE)
+                    .collect(),
+            )
+        };
+        for (la, lb) in [(511, 511), (512, 700), (2_000, 1_999), (3_000, 600)] {
+            let (a, b) = (number(la, 3), number(lb, 4));
+            let expected = &a * &b;
+            for threads in [1, 2, 3, 8] {
+                assert_eq!(
+                    a.mul_parallel(&b, threads),
+                    expected,
+                    "{la} x {lb}, {threads}"
+                );
+            }
+        }
+        assert_eq!(number(600, 5).limb_count(), 600);
+    }
+}
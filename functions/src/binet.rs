@@ -0,0 +1,309 @@
+//! ビネの公式と浮動小数点の精度
+//!
+//! ビネの公式は浮動小数点で計算するため、ある n から先は丸めた結果が
+//! 正しいフィボナッチ数と一致しなくなる。ここではその境界を調べ、
+//! 境界を超えた n をエラーにする。double-double 演算（f64 2つで約 106 ビットの仮数）
+//! 版は境界をさらに先へ延ばす。
+
+use crate::checked;
+use std::fmt;
+
+/// f64 のビネの公式が正確な最大の n（`first_divergence(binet_f64)` の1つ手前）
+pub const F64_MAX_N: u32 = 75;
+
+/// double-double のビネの公式が正確な最大の n
+pub const DOUBLE_DOUBLE_MAX_N: u32 = 149;
+
+/// 精度の範囲外の n が指定された
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecisionError {
+    /// 指定された n
+    pub n: u32,
+    /// 正確に計算できる最大の n
+    pub max_n: u32,
+}
+
+impl fmt::Display for PrecisionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "n = {} は浮動小数点の精度の範囲外です（正確なのは n ≤ {} まで）",
+            self.n, self.max_n
+        )
+    }
+}
+
+impl std::error::Error for PrecisionError {}
+
+/// フィボナッチ数列の n 番目の値を計算する（ビネの公式版）
+///
+/// ビネの公式: F(n) = (φ^n - ψ^n) / √5
+/// - φ (黄金比) = (1 + √5) / 2 ≈ 1.618
+/// - ψ (共役黄金比) = (1 - √5) / 2 ≈ -0.618
+///
+/// f64 で正確に計算できるのは n ≤ `F64_MAX_N` まで
+///
+/// # Errors
+/// n が `F64_MAX_N` を超える場合に `PrecisionError` を返す
+///
+/// ```
+/// use functions::fib_binet;
+///
+/// assert_eq!(fib_binet(75), Ok(2_111_485_077_978_050));
+/// assert!(fib_binet(76).is_err());
+/// ```
+pub fn fib_binet(n: u32) -> Result<u64, PrecisionError> {
+    if n > F64_MAX_N {
+        return Err(PrecisionError {
+            n,
+            max_n: F64_MAX_N,
+        });
+    }
+    // F(75) < 2^64 なので切り捨ては起きない
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(binet_f64(n) as u64)
+}
+
+/// フィボナッチ数列の n 番目の値を計算する（double-double 版のビネの公式）
+///
+/// 正確に計算できるのは n ≤ `DOUBLE_DOUBLE_MAX_N` まで
+///
+/// # Errors
+/// n が `DOUBLE_DOUBLE_MAX_N` を超える場合に `PrecisionError` を返す
+///
+/// ```
+/// use functions::binet::fib_binet_dd;
+///
+/// assert_eq!(fib_binet_dd(149), Ok(6_161_314_747_715_278_029_583_501_626_149));
+/// assert!(fib_binet_dd(150).is_err());
+/// ```
+pub fn fib_binet_dd(n: u32) -> Result<u128, PrecisionError> {
+    if n > DOUBLE_DOUBLE_MAX_N {
+        return Err(PrecisionError {
+            n,
+            max_n: DOUBLE_DOUBLE_MAX_N,
+        });
+    }
+    Ok(binet_double_double(n))
+}
+
+/// f64 でビネの公式を計算し、最も近い整数に丸める（精度の確認なし）
+#[allow(
+    clippy::cast_possible_wrap,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::manual_midpoint
+)]
+#[must_use]
+pub fn binet_f64(n: u32) -> u128 {
+    let sqrt5 = 5.0_f64.sqrt();
+    // 黄金比の計算: (1 + √5) / 2 は midpoint ではなく黄金比の定義式
+    let phi = (1.0 + sqrt5) / 2.0;
+    let psi = (1.0 - sqrt5) / 2.0; // 共役黄金比
+
+    // powi の指数は i32 なので、範囲外の n は i32::MAX として扱う
+    let exp = i32::try_from(n).unwrap_or(i32::MAX);
+    let result = (phi.powi(exp) - psi.powi(exp)) / sqrt5;
+    result.round() as u128
+}
+
+/// double-double でビネの公式を計算し、最も近い整数に丸める（精度の確認なし）
+#[must_use]
+pub fn binet_double_double(n: u32) -> u128 {
+    let sqrt5 = DoubleDouble::sqrt(5.0);
+    let phi = DoubleDouble::from(1.0).add(sqrt5).half();
+    let psi = DoubleDouble::from(1.0).sub(sqrt5).half();
+    phi.powi(n).sub(psi.powi(n)).div(sqrt5).round_to_u128()
+}
+
+/// ビネの公式の実装 `binet` が正確な値と最初に食い違う n を調べる
+///
+/// u128 に収まる範囲（n ≤ 186）で食い違いがなければ `None` を返す
+#[must_use]
+pub fn first_divergence(binet: fn(u32) -> u128) -> Option<u32> {
+    let max_n = u32::try_from(checked::max_n::<u128>()).unwrap_or(u32::MAX);
+    (0..=max_n).find(|&n| checked::fib::<u128>(u64::from(n)) != Some(binet(n)))
+}
+
+/// double-double 数: 値は `hi + lo`（|lo| ≤ ulp(hi) / 2）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+/// 誤差なしの足し算: a + b = s + e
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// |a| ≥ |b| のときの誤差なしの足し算
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// 誤差なしの掛け算: a × b = p + e（FMA を使う）
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(hi: f64) -> Self {
+        Self { hi, lo: 0.0 }
+    }
+}
+
+impl DoubleDouble {
+    fn new((hi, lo): (f64, f64)) -> Self {
+        Self { hi, lo }
+    }
+
+    /// f64 の平方根を double-double の精度で求める（ニュートン法で1回補正）
+    fn sqrt(x: f64) -> Self {
+        let hi = x.sqrt();
+        let (p, e) = two_prod(hi, hi);
+        let lo = ((x - p) - e) / (2.0 * hi);
+        Self::new(quick_two_sum(hi, lo))
+    }
+
+    fn add(self, other: Self) -> Self {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        Self::new(quick_two_sum(s, e + f))
+    }
+
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    /// 2 で割る（誤差なし）
+    fn half(self) -> Self {
+        Self {
+            hi: self.hi / 2.0,
+            lo: self.lo / 2.0,
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let (p, e) = two_prod(self.hi, other.hi);
+        let e = e + (self.hi * other.lo + self.lo * other.hi);
+        Self::new(quick_two_sum(p, e))
+    }
+
+    fn mul_f64(self, b: f64) -> Self {
+        let (p, e) = two_prod(self.hi, b);
+        Self::new(quick_two_sum(p, e + self.lo * b))
+    }
+
+    /// 長除法で商を3項まで求める
+    fn div(self, other: Self) -> Self {
+        let q1 = self.hi / other.hi;
+        let r = self.sub(other.mul_f64(q1));
+        let q2 = r.hi / other.hi;
+        let r = r.sub(other.mul_f64(q2));
+        let q3 = r.hi / other.hi;
+        Self::new(quick_two_sum(q1, q2)).add(Self::from(q3))
+    }
+
+    /// 二乗を繰り返す累乗
+    fn powi(self, n: u32) -> Self {
+        let mut result = Self::from(1.0);
+        let mut base = self;
+        let mut k = n;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result.mul(base);
+            }
+            base = base.mul(base);
+            k >>= 1;
+        }
+        result
+    }
+
+    /// 最も近い整数に丸める（0 以上の値であること）
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn round_to_u128(self) -> u128 {
+        // hi を整数に丸めた残り（hi - 丸めた値）は誤差なく求まるので、lo と合わせて丸め直す
+        let hi = self.hi.round();
+        let frac = (self.hi - hi) + self.lo;
+        (hi as u128).wrapping_add_signed(frac.round() as i128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib_big;
+
+    #[test]
+    fn test_f64_limit_matches_analysis() {
+        assert_eq!(first_divergence(binet_f64), Some(F64_MAX_N + 1));
+    }
+
+    #[test]
+    fn test_double_double_limit_matches_analysis() {
+        assert_eq!(
+            first_divergence(binet_double_double),
+            Some(DOUBLE_DOUBLE_MAX_N + 1)
+        );
+    }
+
+    #[test]
+    fn test_fib_binet_exact_up_to_limit() {
+        for n in 0..=F64_MAX_N {
+            let expected = fib_big(u64::from(n)).to_string();
+            assert_eq!(fib_binet(n).unwrap().to_string(), expected, "n = {n}");
+        }
+        assert_eq!(
+            fib_binet(F64_MAX_N + 1),
+            Err(PrecisionError {
+                n: F64_MAX_N + 1,
+                max_n: F64_MAX_N
+            })
+        );
+        assert!(fib_binet(1000).is_err());
+    }
+
+    #[test]
+    fn test_fib_binet_dd_exact_up_to_limit() {
+        for n in 0..=DOUBLE_DOUBLE_MAX_N {
+            let expected = fib_big(u64::from(n)).to_string();
+            assert_eq!(fib_binet_dd(n).unwrap().to_string(), expected, "n = {n}");
+        }
+        assert!(fib_binet_dd(DOUBLE_DOUBLE_MAX_N + 1).is_err());
+    }
+
+    #[test]
+    fn test_double_double_arithmetic() {
+        // √5 の2乗が double-double の精度で 5 に戻る
+        let sqrt5 = DoubleDouble::sqrt(5.0);
+        let square = sqrt5.mul(sqrt5);
+        assert!((square.hi - 5.0).abs() + square.lo.abs() < 1e-30);
+
+        // 1/3 × 3 = 1
+        let third = DoubleDouble::from(1.0).div(DoubleDouble::from(3.0));
+        let one = third.mul_f64(3.0).sub(DoubleDouble::from(1.0));
+        assert!(one.hi.abs() < 1e-31);
+    }
+
+    #[test]
+    fn test_precision_error_display() {
+        let error = fib_binet(76).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "n = 76 は浮動小数点の精度の範囲外です（正確なのは n ≤ 75 まで）"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! 整数の幅ごとのオーバーフローを検出するフィボナッチ数列
+//!
+//! `fib::<u64>(n)` のように結果の型を選び、その型に収まらない場合は
+//! パニックや飽和ではなく `None` を返す。
+
+/// フィボナッチ数の計算に使える符号なし整数型
+pub trait FibInt: Copy + Eq + std::fmt::Debug {
+    /// 型の名前（`u64` など）
+    const NAME: &'static str;
+    /// ビット幅
+    const BITS: u32;
+    /// 0
+    const ZERO: Self;
+    /// 1
+    const ONE: Self;
+
+    /// オーバーフローしたら `None` を返す足し算
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// u128 から変換する（収まらなければ `None`）
+    fn from_u128(value: u128) -> Option<Self>;
+}
+
+macro_rules! impl_fib_int {
+    ($($t:ty),*) => {
+        $(
+            impl FibInt for $t {
+                const NAME: &'static str = stringify!($t);
+                const BITS: u32 = <$t>::BITS;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn from_u128(value: u128) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_fib_int!(u8, u16, u32, u64, u128);
+
+/// フィボナッチ数列の n 番目の値を型 `T` で計算する
+///
+/// `T` に収まらない場合は `None` を返す。途中の値が溢れた時点で打ち切るので、
+/// n がどれだけ大きくても計算は `max_n::<T>()` 回程度で終わる。
+///
+/// ```
+/// use functions::checked;
+///
+/// assert_eq!(checked::fib::<u8>(13), Some(233));
+/// assert_eq!(checked::fib::<u8>(14), None);
+/// assert_eq!(checked::fib::<u64>(u64::MAX), None);
+/// ```
+#[must_use]
+pub fn fib<T: FibInt>(n: u64) -> Option<T> {
+    // (a, b) = (F(k-1), F(k)) として F(n) まで進める（F(n+1) は求めない）
+    let (mut a, mut b) = (T::ONE, T::ZERO);
+    for _ in 0..n {
+        (a, b) = (b, a.checked_add(b)?);
+    }
+    Some(b)
+}
+
+/// 型 `T` で表せる最大の n
+#[must_use]
+pub fn max_n<T: FibInt>() -> u64 {
+    let (mut a, mut b) = (T::ONE, T::ZERO);
+    let mut n = 0;
+    while let Some(next) = a.checked_add(b) {
+        (a, b) = (b, next);
+        n += 1;
+    }
+    n
+}
+
+/// 型ごとの表せる範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WidthLimit {
+    /// 型の名前
+    pub name: &'static str,
+    /// ビット幅
+    pub bits: u32,
+    /// 表せる最大の n
+    pub max_n: u64,
+}
+
+fn width_limit<T: FibInt>() -> WidthLimit {
+    WidthLimit {
+        name: T::NAME,
+        bits: T::BITS,
+        max_n: max_n::<T>(),
+    }
+}
+
+/// u8〜u128 それぞれで表せる最大の n
+#[must_use]
+pub fn width_limits() -> Vec<WidthLimit> {
+    vec![
+        width_limit::<u8>(),
+        width_limit::<u16>(),
+        width_limit::<u32>(),
+        width_limit::<u64>(),
+        width_limit::<u128>(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::MAX_U32_N;
+    use crate::fib_big;
+
+    /// 境界の n では値が正確で、その次は `None` になることを確かめる
+    fn check_boundary<T: FibInt + std::fmt::Display>(expected_max_n: u64) {
+        let max = max_n::<T>();
+        assert_eq!(max, expected_max_n, "{}", T::NAME);
+        for n in 0..=max {
+            let value = fib::<T>(n).unwrap_or_else(|| panic!("{}: fib({n})", T::NAME));
+            assert_eq!(value.to_string(), fib_big(n).to_string(), "{}", T::NAME);
+        }
+        assert_eq!(fib::<T>(max + 1), None, "{}", T::NAME);
+        assert_eq!(fib::<T>(u64::MAX), None, "{}", T::NAME);
+    }
+
+    #[test]
+    fn test_boundary_u8() {
+        check_boundary::<u8>(13); // F(13) = 233
+    }
+
+    #[test]
+    fn test_boundary_u16() {
+        check_boundary::<u16>(24); // F(24) = 46368
+    }
+
+    #[test]
+    fn test_boundary_u32() {
+        check_boundary::<u32>(47); // F(47) = 2971215073
+        assert_eq!(max_n::<u32>(), MAX_U32_N);
+    }
+
+    #[test]
+    fn test_boundary_u64() {
+        check_boundary::<u64>(93); // F(93) = 12200160415121876738
+    }
+
+    #[test]
+    fn test_boundary_u128() {
+        check_boundary::<u128>(186); // F(186) ≈ 3.3 × 10^38
+    }
+
+    #[test]
+    fn test_width_limits() {
+        let limits = width_limits();
+        let summary: Vec<_> = limits.iter().map(|l| (l.name, l.bits, l.max_n)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("u8", 8, 13),
+                ("u16", 16, 24),
+                ("u32", 32, 47),
+                ("u64", 64, 93),
+                ("u128", 128, 186)
+            ]
+        );
+    }
+}
@@ -0,0 +1,722 @@
+//! コマンドライン引数の解析
+
+use crate::algorithms::{all_algorithms, find_algorithm};
+use crate::parallel;
+use crate::recurrence::LinearRecurrence;
+use crate::trace;
+use crate::BIG_MAX_N;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// 既定のアルゴリズム
+pub const DEFAULT_ALGORITHM: &str = "fast-doubling";
+
+/// compare で n を省略したときの n
+pub const DEFAULT_COMPARE_N: u64 = 30;
+
+/// seq で範囲を省略したときの範囲の終わり（0〜19）
+pub const DEFAULT_SEQUENCE_END: u64 = 19;
+
+/// --threads で指定できる最大のスレッド数
+pub const MAX_THREADS: usize = 256;
+
+/// range と seq で一度に出力できる項の数
+pub const MAX_RANGE_TERMS: u64 = 100_000;
+
+/// compare で一度に比べられる n の数（n ごとに全アルゴリズムの時間を計る）
+pub const MAX_COMPARE_TERMS: u64 = 1_000;
+
+/// 実行するコマンド
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Command {
+    /// デモを表示する（コマンド省略時）
+    #[default]
+    Demo,
+    /// F(n) を計算する
+    Nth(u64),
+    /// 範囲内の全ての n について F(n) を計算する
+    Range(RangeInclusive<u64>),
+    /// 範囲内の n について複数のアルゴリズムの結果と時間を比べる
+    Compare(RangeInclusive<u64>),
+    /// F(n) mod m を計算する（n は桁数の制限のない 10 進数）
+    Mod {
+        /// n（10 進数の文字列）
+        n: String,
+        /// 法 m
+        m: u64,
+    },
+    /// ピサノ周期 π(m) を計算する
+    Pisano(u64),
+    /// 線形漸化式の数列を、行列の累乗と一般項で計算して比べる
+    Sequence(LinearRecurrence, RangeInclusive<u64>),
+    /// 再帰版の呼び出し回数と深さを計測する
+    Trace(u32),
+    /// 全アルゴリズムのベンチマーク
+    Bench,
+    /// 並列版のスレッド数ごとの速度向上を計測する
+    Speedup,
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Demo => "demo",
+            Command::Nth(_) => "nth",
+            Command::Range(_) => "range",
+            Command::Compare(_) => "compare",
+            Command::Mod { .. } => "mod",
+            Command::Pisano(_) => "pisano",
+            Command::Sequence(..) => "seq",
+            Command::Trace(_) => "trace",
+            Command::Speedup => "speedup",
+            Command::Bench => "bench",
+        }
+    }
+}
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// 桁をそろえた表（既定）
+    #[default]
+    Table,
+    /// CSV
+    Csv,
+    /// JSON
+    Json,
+}
+
+/// 呼び出しの木の出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    /// 罫線で描いた木
+    Text,
+    /// Graphviz の DOT 形式
+    Dot,
+}
+
+/// 値の表示方法（巨大な F(n) を全桁表示しないための選択肢）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueFormat {
+    /// 全桁を表示する（既定）
+    #[default]
+    Full,
+    /// 10 進数の桁数だけを表示する
+    Digits,
+    /// F(n) mod m を表示する
+    Modulo(u64),
+}
+
+/// コマンドラインオプション
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// 実行するコマンド
+    pub command: Command,
+    /// nth / range で使うアルゴリズム
+    pub algorithm: String,
+    /// compare で比べるアルゴリズム
+    pub algorithms: Vec<String>,
+    /// 出力形式
+    pub format: Format,
+    /// 値の表示方法
+    pub value: ValueFormat,
+    /// ベンチマークを短く実行する
+    pub quick: bool,
+    /// 並列版で使うスレッド数（speedup では最大のスレッド数）
+    pub threads: usize,
+    /// trace でメモ化した再帰の木を出力する
+    pub memo: bool,
+    /// trace で計測結果の代わりに呼び出しの木を出力する
+    pub tree: Option<TreeFormat>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            command: Command::default(),
+            algorithm: DEFAULT_ALGORITHM.to_string(),
+            algorithms: all_algorithms(1)
+                .iter()
+                .map(|algo| algo.name().to_string())
+                .collect(),
+            format: Format::default(),
+            value: ValueFormat::default(),
+            quick: false,
+            threads: parallel::available_threads(),
+            memo: false,
+            tree: None,
+        }
+    }
+}
+
+/// 引数解析のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// 値が必要なオプションに値がない
+    MissingValue(&'static str),
+    /// コマンドに必要な引数がない
+    MissingArgument(&'static str),
+    /// オプションや引数の値が不正
+    InvalidValue(&'static str, String),
+    /// 値が上限を超えている（引数、値、上限）
+    TooLarge(&'static str, u64, u64),
+    /// 不明なアルゴリズム名
+    UnknownAlgorithm(String),
+    /// 同時に指定できないオプション
+    Conflict(&'static str, &'static str),
+    /// そのコマンドでは使えないオプション
+    NotApplicable(&'static str, &'static str),
+    /// 不明なオプションまたは余分な引数
+    UnknownOption(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::MissingValue(option) => write!(f, "{option} には値が必要です"),
+            ArgError::MissingArgument(argument) => write!(f, "{argument} を指定してください"),
+            ArgError::InvalidValue(option, value) => {
+                write!(f, "{option} の値が不正です: {value}")
+            }
+            ArgError::TooLarge(argument, value, max) => {
+                write!(f, "{argument} が大きすぎます: {value}（{max} まで）")
+            }
+            ArgError::UnknownAlgorithm(name) => write!(f, "不明なアルゴリズムです: {name}"),
+            ArgError::Conflict(a, b) => write!(f, "{a} と {b} は同時に指定できません"),
+            ArgError::NotApplicable(option, command) => {
+                write!(f, "{option} は {command} コマンドでは使えません")
+            }
+            ArgError::UnknownOption(option) => write!(f, "不明なオプションです: {option}"),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// 使い方の説明
+pub const USAGE: &str = "\
+使い方: fib [コマンド] [オプション]
+
+コマンド:
+  nth <n>                     F(n) を計算する
+  range <a>..<b>              F(a)〜F(b-1) を計算する（a..=b で b を含む）
+  compare [<n> | <a>..<b>]    アルゴリズムごとの結果と時間を比べる（既定: n = 30）
+  mod <n> <m>                 F(n) mod m を計算する（n は何桁でもよい）
+  pisano <m>                  ピサノ周期 π(m) を計算する
+  seq <数列> [<a>..<b>]       線形漸化式の数列を一般項と比べる（既定: 0..20）
+                              数列: fibonacci, lucas, tribonacci, pell
+                              または「係数:初項」（例: 1,1,1:0,0,1）
+  trace <n>                   再帰版の呼び出し回数・最大の深さを計測する
+  bench                       全アルゴリズムのベンチマーク
+  speedup                     並列版のスレッド数ごとの速度向上を計測する
+  （省略時）                  デモを表示する
+
+  n は 10000000 まで。範囲は range / seq で 100000 項、compare で 1000 項まで
+
+オプション:
+  --algo <name>               使うアルゴリズム（nth / range、既定: fast-doubling）
+  --algos <a,b,...>           比べるアルゴリズム（compare、既定: 全て）
+  --format <table|csv|json>   出力形式（既定: table、trace --tree / --dot では使えない）
+  --digits                    値の代わりに 10 進数の桁数を表示する
+  --mod <m>                   値の代わりに F(n) mod m を表示する
+  --quick                     ベンチマークを短く実行する（bench / speedup）
+  --threads <k>               並列版のスレッド数（既定: CPU の論理コア数）
+  --tree                      呼び出しの木を罫線で出力する（trace、小さな n のみ）
+  --dot                       呼び出しの木を Graphviz の DOT 形式で出力する（trace）
+  --memo                      メモ化した再帰の木を出力する（trace --tree / --dot）
+  -h, --help                  このヘルプを表示
+
+アルゴリズム: recursive, parallel-recursive, binet, binet-dd, iterative, memoized, matrix,
+            fast-doubling, parallel-doubling, lookup";
+
+/// コマンドライン引数（プログラム名を除く）を解析する
+///
+/// `-h` / `--help` が指定された場合は `Ok(None)` を返す
+///
+/// # Errors
+/// 不明なコマンド・オプションや値の不足がある場合に `ArgError` を返す
+pub fn parse_args<I>(args: I) -> Result<Option<Options>, ArgError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options::default();
+    let mut args = args.into_iter().peekable();
+    // コマンドに合わないオプションを後で報告するため、指定されたものを覚えておく
+    let mut used: Vec<&'static str> = Vec::new();
+
+    if let Some(first) = args.next_if(|arg| !arg.starts_with('-')) {
+        options.command = parse_command(first, &mut args)?;
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--algo" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--algo"))?;
+                options.algorithm = known_algorithm(value)?;
+                used.push("--algo");
+            }
+            "--algos" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--algos"))?;
+                options.algorithms = value
+                    .split(',')
+                    .map(|name| known_algorithm(name.trim().to_string()))
+                    .collect::<Result<_, _>>()?;
+                used.push("--algos");
+            }
+            "--format" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--format"))?;
+                options.format = match value.as_str() {
+                    "table" => Format::Table,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err(ArgError::InvalidValue("--format", value)),
+                };
+                used.push("--format");
+            }
+            "--digits" => {
+                if matches!(options.value, ValueFormat::Modulo(_)) {
+                    return Err(ArgError::Conflict("--digits", "--mod"));
+                }
+                options.value = ValueFormat::Digits;
+                used.push("--digits");
+            }
+            "--mod" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--mod"))?;
+                if options.value == ValueFormat::Digits {
+                    return Err(ArgError::Conflict("--digits", "--mod"));
+                }
+                options.value = ValueFormat::Modulo(parse_modulus("--mod", value)?);
+                used.push("--mod");
+            }
+            "--tree" | "--dot" => {
+                let (option, format) = if arg == "--tree" {
+                    ("--tree", TreeFormat::Text)
+                } else {
+                    ("--dot", TreeFormat::Dot)
+                };
+                if options.tree.is_some_and(|tree| tree != format) {
+                    return Err(ArgError::Conflict("--tree", "--dot"));
+                }
+                options.tree = Some(format);
+                used.push(option);
+            }
+            "--memo" => {
+                options.memo = true;
+                used.push("--memo");
+            }
+            "--quick" => {
+                options.quick = true;
+                used.push("--quick");
+            }
+            "--threads" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--threads"))?;
+                options.threads = match value.parse() {
+                    Ok(k) if (1..=MAX_THREADS).contains(&k) => k,
+                    _ => return Err(ArgError::InvalidValue("--threads", value)),
+                };
+                used.push("--threads");
+            }
+            _ => return Err(ArgError::UnknownOption(arg)),
+        }
+    }
+
+    for option in used {
+        if !applicable(option, &options) {
+            // trace は木を出力するかどうかで使えるオプションが変わる
+            let command = match (&options.command, options.tree) {
+                (Command::Trace(_), Some(TreeFormat::Text)) => "trace --tree",
+                (Command::Trace(_), Some(TreeFormat::Dot)) => "trace --dot",
+                (command, _) => command.name(),
+            };
+            return Err(ArgError::NotApplicable(option, command));
+        }
+    }
+
+    Ok(Some(options))
+}
+
+/// コマンド名とそれに続く引数を解析する
+fn parse_command<I>(first: String, args: &mut std::iter::Peekable<I>) -> Result<Command, ArgError>
+where
+    I: Iterator<Item = String>,
+{
+    let command = match first.as_str() {
+        "nth" => {
+            let n = args.next().ok_or(ArgError::MissingArgument("<n>"))?;
+            Command::Nth(parse_n("<n>", &n)?)
+        }
+        "range" => {
+            let range = args.next().ok_or(ArgError::MissingArgument("<a>..<b>"))?;
+            Command::Range(parse_range(&range, MAX_RANGE_TERMS)?)
+        }
+        "compare" => match args.next_if(|arg| !arg.starts_with('-')) {
+            Some(arg) if arg.contains("..") => {
+                Command::Compare(parse_range(&arg, MAX_COMPARE_TERMS)?)
+            }
+            Some(arg) => {
+                let n = parse_n("<n>", &arg)?;
+                Command::Compare(n..=n)
+            }
+            None => Command::Compare(DEFAULT_COMPARE_N..=DEFAULT_COMPARE_N),
+        },
+        "mod" => {
+            let n = args.next().ok_or(ArgError::MissingArgument("<n>"))?;
+            if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ArgError::InvalidValue("<n>", n));
+            }
+            let m = args.next().ok_or(ArgError::MissingArgument("<m>"))?;
+            Command::Mod {
+                n,
+                m: parse_modulus("<m>", m)?,
+            }
+        }
+        "pisano" => {
+            let m = args.next().ok_or(ArgError::MissingArgument("<m>"))?;
+            Command::Pisano(parse_modulus("<m>", m)?)
+        }
+        "seq" => {
+            let spec = args.next().ok_or(ArgError::MissingArgument("<数列>"))?;
+            let recurrence = spec
+                .parse()
+                .map_err(|_| ArgError::InvalidValue("<数列>", spec))?;
+            let range = match args.next_if(|arg| !arg.starts_with('-')) {
+                Some(arg) => parse_range(&arg, MAX_RANGE_TERMS)?,
+                None => 0..=DEFAULT_SEQUENCE_END,
+            };
+            Command::Sequence(recurrence, range)
+        }
+        "trace" => {
+            let n = args.next().ok_or(ArgError::MissingArgument("<n>"))?;
+            match n.parse() {
+                Ok(n) if n <= trace::MAX_N => Command::Trace(n),
+                _ => return Err(ArgError::InvalidValue("<n>", n)),
+            }
+        }
+        "bench" => Command::Bench,
+        "speedup" => Command::Speedup,
+        _ => return Err(ArgError::UnknownOption(first)),
+    };
+    Ok(command)
+}
+
+/// オプションがコマンド（と他のオプション）に対して使えるかどうか
+fn applicable(option: &str, options: &Options) -> bool {
+    let command = &options.command;
+    match option {
+        "--algo" => matches!(command, Command::Nth(_) | Command::Range(_)),
+        "--algos" => matches!(command, Command::Compare(_)),
+        "--quick" => matches!(command, Command::Bench | Command::Speedup),
+        "--threads" => matches!(
+            command,
+            Command::Nth(_)
+                | Command::Range(_)
+                | Command::Compare(_)
+                | Command::Bench
+                | Command::Speedup
+        ),
+        "--tree" | "--dot" => matches!(command, Command::Trace(_)),
+        // メモ化は木の形だけを変える。木を出力しないときの計測結果は常に両方を表示する
+        "--memo" => matches!(command, Command::Trace(_)) && options.tree.is_some(),
+        // 木はテキストか DOT でしか出力しない
+        "--format" => !matches!(command, Command::Demo) && options.tree.is_none(),
+        "--digits" | "--mod" => {
+            matches!(
+                command,
+                Command::Nth(_) | Command::Range(_) | Command::Compare(_) | Command::Sequence(..)
+            )
+        }
+        _ => true,
+    }
+}
+
+fn known_algorithm(name: String) -> Result<String, ArgError> {
+    if find_algorithm(&name, 1).is_some() {
+        Ok(name)
+    } else {
+        Err(ArgError::UnknownAlgorithm(name))
+    }
+}
+
+/// n（`BIG_MAX_N` 以下）を解析する
+fn parse_n(argument: &'static str, value: &str) -> Result<u64, ArgError> {
+    let n = value
+        .parse()
+        .map_err(|_| ArgError::InvalidValue(argument, value.to_string()))?;
+    check_n(argument, n)
+}
+
+fn check_n(argument: &'static str, n: u64) -> Result<u64, ArgError> {
+    if n > BIG_MAX_N {
+        return Err(ArgError::TooLarge(argument, n, BIG_MAX_N));
+    }
+    Ok(n)
+}
+
+/// 法 m（1 以上）を解析する
+fn parse_modulus(option: &'static str, value: String) -> Result<u64, ArgError> {
+    match value.parse() {
+        Ok(m) if m > 0 => Ok(m),
+        _ => Err(ArgError::InvalidValue(option, value)),
+    }
+}
+
+/// `a..b`（b を含まない）または `a..=b`（b を含む）を解析する
+///
+/// b は `BIG_MAX_N` 以下、項の数は `max_terms` 以下に限る
+fn parse_range(value: &str, max_terms: u64) -> Result<RangeInclusive<u64>, ArgError> {
+    let invalid = || ArgError::InvalidValue("<a>..<b>", value.to_string());
+    let (start, end) = value.split_once("..").ok_or_else(invalid)?;
+    let start: u64 = start.parse().map_err(|_| invalid())?;
+    let end = match end.strip_prefix('=') {
+        Some(end) => end.parse().map_err(|_| invalid())?,
+        None => end
+            .parse::<u64>()
+            .map_err(|_| invalid())?
+            .checked_sub(1)
+            .ok_or_else(invalid)?,
+    };
+    if start > end {
+        return Err(invalid());
+    }
+    let end = check_n("<a>..<b>", end)?;
+    let terms = end - start + 1;
+    if terms > max_terms {
+        return Err(ArgError::TooLarge("<a>..<b> の項数", terms, max_terms));
+    }
+    Ok(start..=end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(list: &[&str]) -> Result<Option<Options>, ArgError> {
+        parse_args(list.iter().map(ToString::to_string))
+    }
+
+    fn command(list: &[&str]) -> Command {
+        parse(list).unwrap().unwrap().command
+    }
+
+    #[test]
+    fn test_parse_args_default_is_demo() {
+        assert_eq!(parse(&[]), Ok(Some(Options::default())));
+        assert_eq!(parse(&["--help"]), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_args_commands() {
+        assert_eq!(command(&["nth", "100"]), Command::Nth(100));
+        assert_eq!(command(&["range", "10..20"]), Command::Range(10..=19));
+        assert_eq!(command(&["range", "10..=20"]), Command::Range(10..=20));
+        assert_eq!(command(&["compare"]), Command::Compare(30..=30));
+        assert_eq!(command(&["compare", "40"]), Command::Compare(40..=40));
+        assert_eq!(command(&["compare", "0..5"]), Command::Compare(0..=4));
+        assert_eq!(
+            command(&["mod", "123456789012345678901234567890", "1000"]),
+            Command::Mod {
+                n: "123456789012345678901234567890".to_string(),
+                m: 1000
+            }
+        );
+        assert_eq!(command(&["pisano", "10"]), Command::Pisano(10));
+        assert_eq!(
+            command(&["seq", "lucas"]),
+            Command::Sequence(LinearRecurrence::lucas(), 0..=19)
+        );
+        assert_eq!(
+            command(&["seq", "2,1:0,1", "5..=8"]),
+            Command::Sequence("2,1:0,1".parse().unwrap(), 5..=8)
+        );
+        assert_eq!(command(&["trace", "10"]), Command::Trace(10));
+        assert_eq!(command(&["bench"]), Command::Bench);
+        assert_eq!(command(&["speedup"]), Command::Speedup);
+    }
+
+    #[test]
+    fn test_parse_args_options() {
+        let options = parse(&[
+            "nth", "1000", "--algo", "matrix", "--format", "json", "--digits",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.algorithm, "matrix");
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.value, ValueFormat::Digits);
+
+        let options = parse(&["compare", "--algos", "binet, matrix", "--mod", "1000"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.algorithms, ["binet", "matrix"]);
+        assert_eq!(options.value, ValueFormat::Modulo(1000));
+
+        let options = parse(&["trace", "5", "--dot", "--memo"]).unwrap().unwrap();
+        assert_eq!(options.tree, Some(TreeFormat::Dot));
+        assert!(options.memo);
+
+        let options = parse(&[
+            "nth",
+            "40",
+            "--algo",
+            "parallel-recursive",
+            "--threads",
+            "4",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.algorithm, "parallel-recursive");
+        assert_eq!(options.threads, 4);
+        assert!(Options::default().threads >= 1);
+
+        assert!(
+            parse(&["bench", "--quick", "--format", "csv"])
+                .unwrap()
+                .unwrap()
+                .quick
+        );
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert_eq!(parse(&["nth"]), Err(ArgError::MissingArgument("<n>")));
+        assert_eq!(
+            parse(&["nth", "-1"]),
+            Err(ArgError::InvalidValue("<n>", "-1".to_string()))
+        );
+        assert_eq!(
+            parse(&["range", "20..10"]),
+            Err(ArgError::InvalidValue("<a>..<b>", "20..10".to_string()))
+        );
+        assert_eq!(
+            parse(&["range", "5..5"]),
+            Err(ArgError::InvalidValue("<a>..<b>", "5..5".to_string()))
+        );
+        assert_eq!(
+            parse(&["nth", "10", "--algo", "bogo"]),
+            Err(ArgError::UnknownAlgorithm("bogo".to_string()))
+        );
+        assert_eq!(
+            parse(&["nth", "10", "--format", "xml"]),
+            Err(ArgError::InvalidValue("--format", "xml".to_string()))
+        );
+        assert_eq!(
+            parse(&["nth", "10", "--mod", "0"]),
+            Err(ArgError::InvalidValue("--mod", "0".to_string()))
+        );
+        assert_eq!(
+            parse(&["nth", "10", "--digits", "--mod", "7"]),
+            Err(ArgError::Conflict("--digits", "--mod"))
+        );
+        assert_eq!(
+            parse(&["nth", "10", "--algos", "binet"]),
+            Err(ArgError::NotApplicable("--algos", "nth"))
+        );
+        assert_eq!(
+            parse(&["nth", "18446744073709551615"]),
+            Err(ArgError::TooLarge("<n>", u64::MAX, BIG_MAX_N))
+        );
+        assert!(parse(&["nth", &BIG_MAX_N.to_string()]).is_ok());
+        assert_eq!(
+            parse(&["compare", &(BIG_MAX_N + 1).to_string()]),
+            Err(ArgError::TooLarge("<n>", BIG_MAX_N + 1, BIG_MAX_N))
+        );
+        assert_eq!(
+            parse(&["range", "0..=10000001"]),
+            Err(ArgError::TooLarge("<a>..<b>", BIG_MAX_N + 1, BIG_MAX_N))
+        );
+        assert_eq!(
+            parse(&["seq", "pell", "10000000..10000002"]),
+            Err(ArgError::TooLarge("<a>..<b>", BIG_MAX_N + 1, BIG_MAX_N))
+        );
+        assert_eq!(
+            parse(&["range", "0..10000000"]),
+            Err(ArgError::TooLarge(
+                "<a>..<b> の項数",
+                BIG_MAX_N,
+                MAX_RANGE_TERMS
+            ))
+        );
+        assert!(parse(&["range", "9900001..=10000000"]).is_ok());
+        assert_eq!(
+            parse(&["seq", "pell", "0..=100000"]),
+            Err(ArgError::TooLarge(
+                "<a>..<b> の項数",
+                100_001,
+                MAX_RANGE_TERMS
+            ))
+        );
+        assert_eq!(
+            parse(&["compare", "0..1001"]),
+            Err(ArgError::TooLarge(
+                "<a>..<b> の項数",
+                1001,
+                MAX_COMPARE_TERMS
+            ))
+        );
+        assert_eq!(
+            parse(&["mod", "1e9", "7"]),
+            Err(ArgError::InvalidValue("<n>", "1e9".to_string()))
+        );
+        assert_eq!(parse(&["mod", "10"]), Err(ArgError::MissingArgument("<m>")));
+        assert_eq!(
+            parse(&["pisano", "0"]),
+            Err(ArgError::InvalidValue("<m>", "0".to_string()))
+        );
+        assert_eq!(
+            parse(&["pisano", "10", "--digits"]),
+            Err(ArgError::NotApplicable("--digits", "pisano"))
+        );
+        assert_eq!(
+            parse(&["seq", "1,1:0"]),
+            Err(ArgError::InvalidValue("<数列>", "1,1:0".to_string()))
+        );
+        assert_eq!(
+            parse(&["trace", "94"]),
+            Err(ArgError::InvalidValue("<n>", "94".to_string()))
+        );
+        assert_eq!(
+            parse(&["trace", "5", "--tree", "--dot"]),
+            Err(ArgError::Conflict("--tree", "--dot"))
+        );
+        assert_eq!(
+            parse(&["nth", "5", "--memo"]),
+            Err(ArgError::NotApplicable("--memo", "nth"))
+        );
+        assert_eq!(
+            parse(&["trace", "5", "--memo"]),
+            Err(ArgError::NotApplicable("--memo", "trace"))
+        );
+        assert_eq!(
+            parse(&["trace", "5", "--tree", "--format", "json"]),
+            Err(ArgError::NotApplicable("--format", "trace --tree"))
+        );
+        assert_eq!(
+            parse(&["trace", "5", "--format", "csv", "--dot", "--memo"]),
+            Err(ArgError::NotApplicable("--format", "trace --dot"))
+        );
+        assert!(parse(&["trace", "5", "--format", "csv"]).is_ok());
+        for threads in ["0", "257", "four"] {
+            assert_eq!(
+                parse(&["speedup", "--threads", threads]),
+                Err(ArgError::InvalidValue("--threads", threads.to_string()))
+            );
+        }
+        assert_eq!(
+            parse(&["trace", "5", "--threads", "2"]),
+            Err(ArgError::NotApplicable("--threads", "trace"))
+        );
+        assert_eq!(
+            parse(&["bench", "--digits"]),
+            Err(ArgError::NotApplicable("--digits", "bench"))
+        );
+        assert_eq!(
+            parse(&["fizz"]),
+            Err(ArgError::UnknownOption("fizz".to_string()))
+        );
+        assert_eq!(
+            parse(&["nth", "10", "20"]),
+            Err(ArgError::UnknownOption("20".to_string()))
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! フィボナッチ数列の計算
+//!
+//! 再帰版・ビネの公式版・多倍長整数による高速倍加法版など、F(n) を求めるいろいろな方法と、
+//! 剰余・ピサノ周期・一般の線形漸化式・イテレータなどの関連する計算をまとめたライブラリ。
+//! `fib` コマンド（`src/main.rs`）はこのライブラリを使って作られている。
+//!
+//! ```
+//! use functions::{fib, fib_big, fib_binet};
+//!
+//! assert_eq!(fib(10), 55);
+//! assert_eq!(fib_binet(75), Ok(2_111_485_077_978_050));
+//! assert_eq!(fib_big(100).to_string(), "354224848179261915075");
+//! ```
+//!
+//! アルゴリズムは `FibAlgorithm` トレイトで同じ形で呼び出せる。
+//!
+//! ```
+//! use functions::{all_algorithms, fib_big};
+//!
+//! for algo in all_algorithms(1) {
+//!     if let Some(value) = algo.fib(40) {
+//!         assert_eq!(value, fib_big(40), "{}", algo.name());
+//!     }
+//! }
+//! ```
+
+pub mod algorithms;
+pub mod bench;
+pub mod bigint;
+pub mod binet;
+pub mod checked;
+pub mod cli;
+pub mod lookup;
+pub mod modular;
+pub mod output;
+pub mod parallel;
+pub mod recurrence;
+pub mod sequence;
+pub mod trace;
+
+pub use algorithms::{all_algorithms, find_algorithm, FibAlgorithm};
+pub use bigint::BigUint;
+pub use binet::{fib_binet, PrecisionError};
+pub use sequence::{BigFibonacci, Fibonacci};
+
+/// フィボナッチ数列の n 番目の値を計算する（再帰版）
+///
+/// 呼び出し回数は 2F(n+1) − 1 回で、n が 40 を超えると目に見えて遅くなる。
+/// 結果が u32 に収まるのは n ≤ 47 まで。
+///
+/// ```
+/// assert_eq!(functions::fib(20), 6765);
+/// ```
+#[must_use]
+pub fn fib(n: u32) -> u32 {
+    match n {
+        0 => 0,
+        1 => 1,
+        _ => fib(n - 1) + fib(n - 2),
+    }
+}
+
+/// 多倍長版で計算する最大の n
+///
+/// F(10⁷) は約 209 万桁で、計算に数秒かかる。これより大きい n は計算も表示も現実的でない
+pub const BIG_MAX_N: u64 = 10_000_000;
+
+/// フィボナッチ数列の n 番目の値を正確に計算する（多倍長版）
+///
+/// 高速倍加法による O(log n) 回の多倍長乗算で計算する。
+/// - F(2k)   = F(k) × (2F(k+1) − F(k))
+/// - F(2k+1) = F(k)² + F(k+1)²
+///
+/// n ≤ 186 ではコンパイル時に作った表 `lookup::FIB_TABLE_U128` を引く
+///
+/// ```
+/// let f = functions::fib_big(1_000);
+/// assert_eq!(f.digit_count(), 209);
+/// assert!(f.to_string().ends_with("849228875"));
+/// ```
+#[must_use]
+pub fn fib_big(n: u64) -> BigUint {
+    if let Some(value) = lookup::get(n) {
+        return BigUint::from(value);
+    }
+
+    // (a, b) = (F(k), F(k+1)) を n の上位ビットから倍加していく
+    let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        // (F(2k), F(2k+1))
+        let even = &a * &(&(&b + &b) - &a);
+        let odd = &(&a * &a) + &(&b * &b);
+        (a, b) = if (n >> bit) & 1 == 1 {
+            let next = &even + &odd;
+            (odd, next)
+        } else {
+            (even, odd)
+        };
+    }
+    a
+}
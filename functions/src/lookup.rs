@@ -0,0 +1,167 @@
+//! コンパイル時に作るフィボナッチ数の表
+//!
+//! `const fn` の繰り返しで u64 / u128 に収まる全ての F(n) をコンパイル時に計算しておき、
+//! 実行時は配列を引くだけ（O(1)）にする。`const` や配列の長さなど、定数が必要な場所でも使える。
+
+/// u64 に収まる最大の n（F(93) = 12200160415121876738）
+pub const MAX_U64_N: u32 = 93;
+
+/// u128 に収まる最大の n（F(186) ≈ 3.3 × 10^38）
+pub const MAX_U128_N: u32 = 186;
+
+/// F(0)〜F(93)
+pub const FIB_TABLE: [u64; MAX_U64_N as usize + 1] = table_u64();
+
+/// F(0)〜F(186)
+pub const FIB_TABLE_U128: [u128; MAX_U128_N as usize + 1] = table_u128();
+
+/// F(n) を繰り返しで計算する（`const` の中でも呼べる）
+///
+/// # Panics
+/// n が `MAX_U64_N` を超える場合（`const` の中ではコンパイルエラーになる）
+///
+/// ```
+/// const F_90: u64 = functions::lookup::fib_u64(90);
+/// assert_eq!(F_90, functions::lookup::FIB_TABLE[90]);
+/// ```
+#[must_use]
+pub const fn fib_u64(n: u32) -> u64 {
+    assert!(n <= MAX_U64_N, "F(n) は u64 に収まりません（n ≤ 93）");
+    let (mut a, mut b) = (0_u64, 1_u64);
+    let mut i = 0;
+    while i < n {
+        // F(n+1) は u64 に収まらないことがあるので、最後の1回は足さない
+        if i + 1 < n {
+            (a, b) = (b, a + b);
+        } else {
+            a = b;
+        }
+        i += 1;
+    }
+    a
+}
+
+/// F(n) を u128 で繰り返しで計算する（`const` の中でも呼べる）
+///
+/// # Panics
+/// n が `MAX_U128_N` を超える場合
+#[must_use]
+pub const fn fib_u128(n: u32) -> u128 {
+    assert!(n <= MAX_U128_N, "F(n) は u128 に収まりません（n ≤ 186）");
+    let (mut a, mut b) = (0_u128, 1_u128);
+    let mut i = 0;
+    while i < n {
+        if i + 1 < n {
+            (a, b) = (b, a + b);
+        } else {
+            a = b;
+        }
+        i += 1;
+    }
+    a
+}
+
+/// 表から F(n) を引く（u128 に収まらなければ `None`）
+#[must_use]
+pub const fn get(n: u64) -> Option<u128> {
+    if n > MAX_U128_N as u64 {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation)] // n <= MAX_U128_N
+    Some(FIB_TABLE_U128[n as usize])
+}
+
+const fn table_u64() -> [u64; MAX_U64_N as usize + 1] {
+    let mut table = [0; MAX_U64_N as usize + 1];
+    table[1] = 1;
+    let mut i = 2;
+    while i < table.len() {
+        table[i] = table[i - 1] + table[i - 2];
+        i += 1;
+    }
+    table
+}
+
+const fn table_u128() -> [u128; MAX_U128_N as usize + 1] {
+    let mut table = [0; MAX_U128_N as usize + 1];
+    table[1] = 1;
+    let mut i = 2;
+    while i < table.len() {
+        table[i] = table[i - 1] + table[i - 2];
+        i += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{all_algorithms, Complexity};
+    use crate::bigint::BigUint;
+    use crate::binet::{fib_binet, fib_binet_dd};
+    use crate::sequence::Fibonacci;
+    use crate::{checked, fib, fib_big, parallel};
+
+    // コンパイル時に評価されることを確かめる
+    const F_50: u64 = fib_u64(50);
+    const F_186: u128 = FIB_TABLE_U128[MAX_U128_N as usize];
+    const LEN: usize = FIB_TABLE[10] as usize;
+
+    #[test]
+    fn test_const_evaluation() {
+        assert_eq!(F_50, 12_586_269_025);
+        assert_eq!(F_186, 332_825_110_087_067_562_321_196_029_789_634_457_848);
+        assert_eq!([0_u8; LEN].len(), 55);
+        assert_eq!(FIB_TABLE[93], 12_200_160_415_121_876_738);
+        assert_eq!(get(186), Some(F_186));
+        assert_eq!(get(187), None);
+    }
+
+    #[test]
+    fn test_tables_match_const_fns() {
+        for n in 0..=MAX_U64_N {
+            assert_eq!(FIB_TABLE[n as usize], fib_u64(n));
+            assert_eq!(FIB_TABLE_U128[n as usize], u128::from(fib_u64(n)));
+        }
+        for n in 0..=MAX_U128_N {
+            assert_eq!(FIB_TABLE_U128[n as usize], fib_u128(n));
+        }
+    }
+
+    #[test]
+    fn test_table_matches_every_runtime_algorithm() {
+        let iterated: Vec<u128> = Fibonacci::<u128>::new().collect();
+        assert_eq!(iterated, FIB_TABLE_U128);
+        for (n, &expected) in (0_u32..).zip(&FIB_TABLE_U128) {
+            let big = BigUint::from(expected);
+            assert_eq!(fib_big(u64::from(n)), big, "fib_big({n})");
+            assert_eq!(checked::fib::<u128>(u64::from(n)), Some(expected));
+            assert_eq!(parallel::fib_big_parallel(u64::from(n), 2), big);
+            if n <= 30 {
+                assert_eq!(u128::from(fib(n)), expected, "fib({n})");
+                assert_eq!(u128::from(parallel::fib_recursive(n, 2)), expected);
+            }
+            if let Ok(value) = fib_binet(n) {
+                assert_eq!(u128::from(value), expected, "fib_binet({n})");
+            }
+            if let Ok(value) = fib_binet_dd(n) {
+                assert_eq!(value, expected, "fib_binet_dd({n})");
+            }
+            for algo in all_algorithms(2) {
+                // 指数時間のものは時間がかかりすぎるので n ≤ 30 に限る
+                if algo.complexity() == Complexity::Exponential && n > 30 {
+                    continue;
+                }
+                if let Some(value) = algo.fib(u64::from(n)) {
+                    assert_eq!(value, big, "{}({n})", algo.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "u64 に収まりません")]
+    fn test_fib_u64_overflow_panics() {
+        let _ = fib_u64(94);
+    }
+}
@@ -1,149 +1,555 @@
+use functions::algorithms::{all_algorithms, find_algorithm, FibAlgorithm};
+use functions::bigint::BigInt;
+use functions::cli::{self, Command, Options, TreeFormat, ValueFormat};
+use functions::output::{Cell, Table, TableWriter};
+use functions::recurrence::LinearRecurrence;
+use functions::sequence::{BigFibonacci, Fibonacci};
+use functions::{
+    bench, binet, checked, fib_big, fib_binet, lookup, modular, parallel, sequence, trace, BigUint,
+};
+use std::hint::black_box;
+use std::io::{self, BufWriter, StdoutLock};
+use std::ops::RangeInclusive;
+use std::time::Instant;
+
 fn main() {
-    println!("再帰版 vs ビネの公式版:");
-    for n in 0..20 {
-        let recursive = fib(n);
-        let binet = fib_binet(n);
-        let status = if recursive == binet { "✓" } else { "✗" };
-        println!("fib({n:2}) = {recursive:5} | fib_binet({n:2}) = {binet:5} {status}");
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            eprintln!("{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let result = match &options.command {
+        Command::Demo => {
+            demo();
+            Ok(())
+        }
+        Command::Nth(n) => run_range(&options, *n..=*n),
+        Command::Range(range) => run_range(&options, range.clone()),
+        Command::Compare(range) => output_result(run_compare(&options, range.clone())),
+        Command::Mod { n, m } => run_mod(&options, n, *m),
+        Command::Pisano(m) => {
+            run_pisano(&options, *m);
+            Ok(())
+        }
+        Command::Sequence(recurrence, range) => {
+            output_result(run_sequence(&options, recurrence, range.clone()))
+        }
+        Command::Trace(n) => run_trace(&options, *n),
+        Command::Speedup => {
+            run_speedup(&options);
+            Ok(())
+        }
+        Command::Bench => {
+            run_bench(&options);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
     }
 }
 
-/// フィボナッチ数列の n 番目の値を計算する（再帰版）
-fn fib(n: u32) -> u32 {
-    match n {
-        0 => 0,
-        1 => 1,
-        _ => fib(n - 1) + fib(n - 2),
+/// 行を受け取るたびに標準出力へ書き出す表
+fn stdout_table(
+    options: &Options,
+    headers: Vec<&'static str>,
+) -> io::Result<TableWriter<BufWriter<StdoutLock<'static>>>> {
+    TableWriter::new(BufWriter::new(io::stdout().lock()), headers, options.format)
+}
+
+/// 書き出しの結果をコマンドの結果にする（パイプの先が閉じた場合は成功とみなす）
+fn output_result(result: io::Result<()>) -> Result<(), String> {
+    match result {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(format!("出力に失敗しました: {e}")),
+        _ => Ok(()),
     }
 }
 
-/// フィボナッチ数列の n 番目の値を計算する（ビネの公式版）
+/// 値の表示方法に合わせた列の見出し
+fn value_header(value: ValueFormat) -> &'static str {
+    match value {
+        ValueFormat::Full => "value",
+        ValueFormat::Digits => "digits",
+        ValueFormat::Modulo(_) => "mod",
+    }
+}
+
+/// F(n) を表示方法に合わせたマスにする
+fn value_cell(value: &BigUint, format: ValueFormat) -> Cell {
+    match format {
+        ValueFormat::Full => Cell::Big(value.to_string()),
+        ValueFormat::Digits => Cell::Integer(value.digit_count() as u64),
+        ValueFormat::Modulo(m) => Cell::Integer(value.rem_u64(m)),
+    }
+}
+
+/// 符号つきの値を表示方法に合わせたマスにする（桁数は絶対値の桁数、剰余は 0 以上）
+fn signed_value_cell(value: &BigInt, format: ValueFormat) -> Cell {
+    match format {
+        ValueFormat::Full => Cell::Big(value.to_string()),
+        ValueFormat::Digits => Cell::Integer(value.magnitude().digit_count() as u64),
+        ValueFormat::Modulo(m) => Cell::Integer(value.rem_euclid_u64(m)),
+    }
+}
+
+/// nth / range: 1つのアルゴリズムで範囲内の F(n) を出力する
+fn run_range(options: &Options, range: RangeInclusive<u64>) -> Result<(), String> {
+    let algo = find_algorithm(&options.algorithm, options.threads)
+        .ok_or_else(|| format!("不明なアルゴリズムです: {}", options.algorithm))?;
+    if *range.end() > algo.max_n() {
+        return Err(format!(
+            "n = {} は {} の上限を超えています（n ≤ {} まで）",
+            range.end(),
+            algo.name(),
+            algo.max_n()
+        ));
+    }
+
+    output_result(write_range(options, algo.as_ref(), range))
+}
+
+/// 最初の2項だけをアルゴリズムで計算し、以降は直前の2項の和を出力する
+fn write_range(
+    options: &Options,
+    algo: &dyn FibAlgorithm,
+    range: RangeInclusive<u64>,
+) -> io::Result<()> {
+    let mut writer = stdout_table(options, vec!["n", value_header(options.value)])?;
+    let (start, end) = range.into_inner();
+    let mut current = algo.compute(start);
+    let mut next = (start < end).then(|| algo.compute(start + 1));
+    for n in start..=end {
+        writer.push(vec![Cell::Integer(n), value_cell(&current, options.value)])?;
+        if let Some(following) = next.take() {
+            next = (n + 2 <= end).then(|| &current + &following);
+            current = following;
+        }
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// compare: 範囲内の n について、アルゴリズムごとの値・時間・正誤を出力する
+///
+/// 上限を超えたアルゴリズムは値と時間を空欄にする。正誤は `BigFibonacci` の値と比べる
+fn run_compare(options: &Options, range: RangeInclusive<u64>) -> io::Result<()> {
+    let algorithms: Vec<Box<dyn FibAlgorithm>> = options
+        .algorithms
+        .iter()
+        .filter_map(|name| find_algorithm(name, options.threads))
+        .collect();
+
+    let mut writer = stdout_table(
+        options,
+        vec![
+            "algorithm",
+            "n",
+            value_header(options.value),
+            "time",
+            "correct",
+        ],
+    )?;
+    let skip = usize::try_from(*range.start()).unwrap_or(usize::MAX);
+    for (n, expected) in range.zip(BigFibonacci::new().skip(skip)) {
+        for algo in &algorithms {
+            let start = Instant::now();
+            let result = algo.fib(n);
+            let elapsed = start.elapsed();
+            let row = match result {
+                Some(value) => vec![
+                    value_cell(&value, options.value),
+                    Cell::Duration(elapsed.as_secs_f64() * 1e9),
+                    Cell::Bool(value == expected),
+                ],
+                None => vec![Cell::Missing, Cell::Missing, Cell::Missing],
+            };
+            let mut cells = vec![Cell::Text(algo.name().to_string()), Cell::Integer(n)];
+            cells.extend(row);
+            writer.push(cells)?;
+        }
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// mod: F(n) mod m を出力する
+fn run_mod(options: &Options, n: &str, m: u64) -> Result<(), String> {
+    let value = modular::fib_mod_str(n, m).map_err(|e| e.to_string())?;
+    let mut table = Table::new(vec!["n", "m", "value"]);
+    table.push(vec![
+        Cell::Big(n.to_string()),
+        Cell::Integer(m),
+        Cell::Integer(value),
+    ]);
+    print!("{}", table.render(options.format));
+    Ok(())
+}
+
+/// pisano: ピサノ周期 π(m) と m の素因数分解を出力する
+fn run_pisano(options: &Options, m: u64) {
+    let factors: Vec<String> = modular::factorize(m)
+        .into_iter()
+        .map(|(p, k)| match k {
+            1 => p.to_string(),
+            _ => format!("{p}^{k}"),
+        })
+        .collect();
+    let mut table = Table::new(vec!["m", "factors", "period"]);
+    table.push(vec![
+        Cell::Integer(m),
+        Cell::Text(factors.join(" * ")),
+        Cell::Big(modular::pisano_period(m).to_string()),
+    ]);
+    print!("{}", table.render(options.format));
+}
+
+/// seq: 行列の累乗による正確な値と一般項（特性根）による値を並べる
 ///
-/// ビネの公式: F(n) = (φ^n - ψ^n) / √5
-/// - φ (黄金比) = (1 + √5) / 2 ≈ 1.618
-/// - ψ (共役黄金比) = (1 - √5) / 2 ≈ -0.618
+/// 根に重複があって一般項を求められない数列や、一般項の精度を超えた n は空欄にする
+fn run_sequence(
+    options: &Options,
+    recurrence: &LinearRecurrence,
+    range: RangeInclusive<u64>,
+) -> io::Result<()> {
+    let form = recurrence.closed_form();
+    let mut writer = stdout_table(
+        options,
+        vec!["n", value_header(options.value), "closed_form", "correct"],
+    )?;
+    let terms = recurrence.iter_from(*range.start());
+    for (n, exact) in range.zip(terms) {
+        let closed = form
+            .as_ref()
+            .and_then(|form| form.term(u32::try_from(n).ok()?).ok())
+            .map(BigInt::from);
+        let (closed_cell, correct) = match closed {
+            Some(closed) => (
+                signed_value_cell(&closed, options.value),
+                Cell::Bool(closed == exact),
+            ),
+            None => (Cell::Missing, Cell::Missing),
+        };
+        writer.push(vec![
+            Cell::Integer(n),
+            signed_value_cell(&exact, options.value),
+            closed_cell,
+            correct,
+        ])?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// trace: メモ化なし・ありの再帰の呼び出し回数と深さ、または呼び出しの木を出力する
 ///
-/// 注意: 浮動小数点の精度により、大きな n では誤差が生じる可能性あり
-#[allow(
-    clippy::cast_possible_wrap,
-    clippy::cast_possible_truncation,
-    clippy::cast_sign_loss,
-    clippy::manual_midpoint
-)]
-fn fib_binet(n: u32) -> u32 {
-    let sqrt5 = 5.0_f64.sqrt();
-    // 黄金比の計算: (1 + √5) / 2 は midpoint ではなく黄金比の定義式
-    let phi = (1.0 + sqrt5) / 2.0;
-    let psi = (1.0 - sqrt5) / 2.0; // 共役黄金比
-
-    // n は u32 なので i32 への変換は n <= i32::MAX の範囲で安全
-    // 結果は常に正の整数なので u32 への変換も安全
-    let result = (phi.powi(n as i32) - psi.powi(n as i32)) / sqrt5;
-    result.round() as u32
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    // fib() のテスト
-    #[test]
-    fn test_fib_base_cases() {
-        assert_eq!(fib(0), 0);
-        assert_eq!(fib(1), 1);
-    }
-
-    #[test]
-    fn test_fib_known_values() {
-        assert_eq!(fib(2), 1);
-        assert_eq!(fib(3), 2);
-        assert_eq!(fib(4), 3);
-        assert_eq!(fib(5), 5);
-        assert_eq!(fib(10), 55);
-        assert_eq!(fib(20), 6765);
-    }
-
-    // fib_binet() のテスト
-    #[test]
-    fn test_fib_binet_base_cases() {
-        assert_eq!(fib_binet(0), 0);
-        assert_eq!(fib_binet(1), 1);
-    }
-
-    #[test]
-    fn test_fib_binet_known_values() {
-        assert_eq!(fib_binet(2), 1);
-        assert_eq!(fib_binet(3), 2);
-        assert_eq!(fib_binet(4), 3);
-        assert_eq!(fib_binet(5), 5);
-        assert_eq!(fib_binet(10), 55);
-        assert_eq!(fib_binet(20), 6765);
-    }
-
-    // 両関数の一致テスト
-    #[test]
-    fn test_fib_and_fib_binet_match() {
-        for n in 0..25 {
-            assert_eq!(
-                fib(n),
-                fib_binet(n),
-                "fib({n}) と fib_binet({n}) が一致しない"
-            );
+/// メモ化なしは呼び出し回数が指数的に増えるので、`PLAIN_MAX_N` を超える n では空欄にする
+fn run_trace(options: &Options, n: u32) -> Result<(), String> {
+    use trace::Strategy;
+
+    if let Some(format) = options.tree {
+        let strategy = if options.memo {
+            Strategy::Memoized
+        } else {
+            Strategy::Plain
+        };
+        let tree = trace::call_tree(n, strategy).ok_or_else(|| {
+            format!(
+                "fib({n}) の呼び出しの木は {} ノードを超えるため出力できません",
+                trace::MAX_TREE_NODES
+            )
+        })?;
+        match format {
+            TreeFormat::Text => print!("{}", tree.to_text()),
+            TreeFormat::Dot => print!("{}", tree.to_dot()),
+        }
+        return Ok(());
+    }
+
+    let mut table = Table::new(vec![
+        "strategy",
+        "n",
+        "value",
+        "calls",
+        "max_depth",
+        "cache_hits",
+    ]);
+    for strategy in [Strategy::Plain, Strategy::Memoized] {
+        let mut row = vec![
+            Cell::Text(strategy.name().to_string()),
+            Cell::Integer(u64::from(n)),
+        ];
+        if strategy == Strategy::Plain && n > trace::PLAIN_MAX_N {
+            row.extend([Cell::Missing, Cell::Missing, Cell::Missing, Cell::Missing]);
+        } else {
+            let t = trace::trace(n, strategy);
+            row.extend([
+                Cell::Integer(t.value),
+                Cell::Integer(t.calls),
+                Cell::Integer(u64::from(t.max_depth)),
+                Cell::Integer(t.cache_hits),
+            ]);
+        }
+        table.push(row);
+    }
+    print!("{}", table.render(options.format));
+    Ok(())
+}
+
+/// 全アルゴリズムのベンチマークを実行し、指定の形式で出力する
+fn run_bench(options: &Options) {
+    let config = if options.quick {
+        bench::BenchConfig::quick()
+    } else {
+        bench::BenchConfig::default()
+    };
+    let results = bench::run_suite(
+        &all_algorithms(options.threads),
+        &bench::DEFAULT_NS,
+        &config,
+    );
+    match options.format {
+        cli::Format::Csv => print!("{}", bench::to_csv(&results)),
+        cli::Format::Json => print!("{}", bench::to_json(&results)),
+        cli::Format::Table => print!("{}", bench::report(&results)),
+    }
+}
+
+/// speedup: 並列版をスレッド数を変えて計測し、1 スレッドに対する速度向上を出力する
+fn run_speedup(options: &Options) {
+    let config = if options.quick {
+        bench::BenchConfig::quick()
+    } else {
+        bench::BenchConfig::default()
+    };
+    let mut table = Table::new(vec!["algorithm", "n", "threads", "median", "speedup"]);
+    for (name, n) in bench::SPEEDUP_CASES {
+        let mut baseline = None;
+        for threads in bench::thread_counts(options.threads) {
+            let algo =
+                find_algorithm(name, threads).expect("SPEEDUP_CASES のアルゴリズムは存在する");
+            let (_, stats) = bench::measure(&config, || algo.compute(black_box(n)));
+            let baseline = *baseline.get_or_insert(stats.median);
+            table.push(vec![
+                Cell::Text(name.to_string()),
+                Cell::Integer(n),
+                Cell::Integer(threads as u64),
+                Cell::Duration(stats.median),
+                Cell::Ratio(baseline / stats.median),
+            ]);
         }
     }
+    print!("{}", table.render(options.format));
+}
 
-    // 計算速度の比較テスト
-    #[test]
-    fn test_performance_comparison() {
-        use std::time::Instant;
+/// コマンドを省略したときのデモ
+fn demo() {
+    let sections: [fn(); 10] = [
+        demo_binet,
+        demo_iterator,
+        demo_binet_precision,
+        demo_big,
+        demo_modular,
+        demo_recurrence,
+        demo_widths,
+        demo_lookup,
+        demo_algorithms,
+        demo_trace,
+    ];
+    for (i, section) in sections.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        section();
+    }
+}
 
-        let n = 30; // 再帰版が遅くなる値
+/// デモ: ビネの公式との比較
+fn demo_binet() {
+    println!("イテレータ vs ビネの公式版:");
+    for (n, exact) in (0..).zip(Fibonacci::<u64>::new().take(20)) {
+        let binet = fib_binet(n);
+        let status = if binet == Ok(exact) { "✓" } else { "✗" };
+        let binet = binet.map_or_else(|e| e.to_string(), |v| v.to_string());
+        println!("F({n:2}) = {exact:5} | fib_binet({n:2}) = {binet:>5} {status}");
+    }
+}
 
-        // 再帰版の計測
-        let start = Instant::now();
-        let result_recursive = fib(n);
-        let duration_recursive = start.elapsed();
+/// デモ: イテレータとゼッケンドルフ表現など
+fn demo_iterator() {
+    println!("イテレータと関連する関数:");
+    let first: Vec<String> = Fibonacci::<u8>::new().map(|f| f.to_string()).collect();
+    println!("u8 に収まる項: {}", first.join(", "));
+    println!(
+        "Fibonacci::<u64>::new().nth(90) = {:?}",
+        Fibonacci::<u64>::new().nth(90)
+    );
+    println!(
+        "BigFibonacci::new().nth(10000) は {} 桁",
+        BigFibonacci::new()
+            .nth(10_000)
+            .map_or(0, |f| f.digit_count())
+    );
+    for x in [100, 2023, 1_000_000] {
+        let terms: Vec<String> = sequence::zeckendorf(x)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        println!("ゼッケンドルフ表現: {x} = {}", terms.join(" + "));
+    }
+    for x in [144, 145, 12_200_160_415_121_876_738] {
+        match sequence::index_of(x) {
+            Some(n) => println!("{x} は F({n})"),
+            None => println!("{x} はフィボナッチ数ではない"),
+        }
+    }
+    println!("is_fibonacci(4) = {}", sequence::is_fibonacci(4));
+}
 
-        // ビネの公式版の計測
-        let start = Instant::now();
-        let result_binet = fib_binet(n);
-        let duration_binet = start.elapsed();
+/// デモ: ビネの公式の精度の限界
+fn demo_binet_precision() {
+    println!("ビネの公式の精度:");
+    for (name, binet) in [
+        ("f64", binet::binet_f64 as fn(u32) -> u128),
+        ("double-double", binet::binet_double_double),
+    ] {
+        match binet::first_divergence(binet) {
+            Some(n) => println!(
+                "{name:<13}: n = {n} で初めて食い違う（正確: {}, ビネ: {}）",
+                fib_big(u64::from(n)),
+                binet(n)
+            ),
+            None => println!("{name:<13}: u128 の範囲で食い違いなし"),
+        }
+    }
+    if let Err(e) = fib_binet(binet::F64_MAX_N + 1) {
+        println!("fib_binet({}) → {e}", binet::F64_MAX_N + 1);
+    }
+    if let Err(e) = binet::fib_binet_dd(binet::DOUBLE_DOUBLE_MAX_N + 1) {
+        println!("fib_binet_dd({}) → {e}", binet::DOUBLE_DOUBLE_MAX_N + 1);
+    }
+}
 
-        println!("\n=== 計算速度比較 (n = {n}) ===");
-        println!(
-            "fib({n})       = {result_recursive:10} | 時間: {:?}",
-            duration_recursive
-        );
+/// デモ: 多倍長版
+fn demo_big() {
+    println!("多倍長版:");
+    for n in [50, 100, 200] {
+        println!("fib_big({n:3}) = {}", fib_big(n));
+    }
+    for n in [1_000, 10_000, 100_000, 1_000_000] {
+        println!("fib_big({n:7}) は {} 桁", fib_big(n).digit_count());
+    }
+}
+
+/// デモ: 剰余とピサノ周期
+fn demo_modular() {
+    println!("剰余とピサノ周期:");
+    for (n, m) in [(10_u128.pow(18), 1_000_000_007), (u128::MAX, 1_000_000_007)] {
+        println!("F({n}) mod {m} = {}", modular::fib_mod(n, m));
+    }
+    for m in [10, 1_000, 1_000_000_007] {
+        println!("π({m}) = {}", modular::pisano_period(m));
+    }
+}
+
+/// デモ: 一般の線形漸化式
+fn demo_recurrence() {
+    println!("線形漸化式:");
+    for recurrence in LinearRecurrence::presets() {
+        let terms: Vec<String> = recurrence
+            .terms(10)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let exact = recurrence
+            .closed_form()
+            .map_or_else(|| "-".to_string(), |form| form.max_n().to_string());
         println!(
-            "fib_binet({n}) = {result_binet:10} | 時間: {:?}",
-            duration_binet
+            "{:<10} {recurrence}: {}, … | 一般項は n = {exact} まで正確",
+            recurrence.name(),
+            terms.join(", ")
         );
+    }
+}
+
+/// デモ: 型ごとに表せる範囲
+fn demo_widths() {
+    println!("型ごとに表せる最大の n:");
+    for limit in checked::width_limits() {
+        let value = checked::fib::<u128>(limit.max_n).unwrap_or_default();
         println!(
-            "ビネの公式は再帰版の約 {:.0} 倍高速",
-            duration_recursive.as_nanos() as f64 / duration_binet.as_nanos().max(1) as f64
+            "{:>4} ({:3} ビット): n = {:3} まで | F({}) = {value}",
+            limit.name, limit.bits, limit.max_n, limit.max_n
         );
+    }
+    println!("checked::fib::<u32>(48) = {:?}", checked::fib::<u32>(48));
+}
+
+/// デモ: コンパイル時に作った表
+fn demo_lookup() {
+    const F_90: u64 = lookup::fib_u64(90);
+    const F_186: u128 = lookup::fib_u128(lookup::MAX_U128_N);
 
-        // 結果が一致することを確認
-        assert_eq!(result_recursive, result_binet);
+    println!("コンパイル時の表:");
+    println!(
+        "FIB_TABLE は {} 項（F(0)〜F({})）、FIB_TABLE_U128 は {} 項",
+        lookup::FIB_TABLE.len(),
+        lookup::MAX_U64_N,
+        lookup::FIB_TABLE_U128.len()
+    );
+    println!("const F_90: u64 = fib_u64(90) = {F_90}");
+    println!("const F_186: u128 = fib_u128(186) = {F_186}");
+    println!("FIB_TABLE[50] = {}", lookup::FIB_TABLE[50]);
+    println!("lookup::get(187) = {:?}", lookup::get(187));
+}
 
-        // ビネの公式が再帰版より高速であることを確認
-        assert!(
-            duration_binet < duration_recursive,
-            "ビネの公式版が再帰版より遅い（予想外）"
+/// デモ: アルゴリズムごとの値と時間
+fn demo_algorithms() {
+    let n = 30;
+    println!("アルゴリズム比較 (n = {n}):");
+    for algo in all_algorithms(parallel::available_threads()) {
+        let start = Instant::now();
+        let result = algo.fib(n);
+        let elapsed = start.elapsed();
+        let value = result.map_or_else(|| "-".to_string(), |v| v.to_string());
+        println!(
+            "{:<13} {:<8} 上限 n = {:<8} | {value:>6} | 時間: {elapsed:?}",
+            algo.name(),
+            algo.complexity(),
+            algo.max_n(),
         );
     }
+}
 
-    // 大きな n での速度テスト（ビネの公式のみ）
-    #[test]
-    fn test_binet_large_n_performance() {
-        use std::time::Instant;
+/// デモ: 再帰の呼び出し回数とメモ化の効果
+fn demo_trace() {
+    use trace::Strategy;
 
-        println!("\n=== ビネの公式の大きな n での性能 ===");
-        for n in [50, 100, 500, 1000] {
-            let start = Instant::now();
-            let result = fib_binet(n);
-            let duration = start.elapsed();
-            println!("fib_binet({n:4}) = {:20} | 時間: {:?}", result, duration);
+    println!("再帰の呼び出し回数:");
+    for n in [10, 20, 30] {
+        let plain = trace::trace(n, Strategy::Plain);
+        let memo = trace::trace(n, Strategy::Memoized);
+        println!(
+            "fib({n:2}): メモ化なし {:>9} 回 (深さ {:2}) | メモ化あり {:2} 回 (深さ {:2}, メモ参照 {:2})",
+            plain.calls, plain.max_depth, memo.calls, memo.max_depth, memo.cache_hits
+        );
+    }
+    for strategy in [Strategy::Plain, Strategy::Memoized] {
+        if let Some(tree) = trace::call_tree(4, strategy) {
+            println!(
+                "fib(4) の呼び出しの木（{}, {} ノード）:",
+                strategy.name(),
+                tree.nodes().len()
+            );
+            print!("{}", tree.to_text());
         }
     }
 }
@@ -0,0 +1,447 @@
+//! フィボナッチ数の剰余とピサノ周期
+//!
+//! F(n) mod m は高速倍加法をすべて mod m で行えば、n がどれほど大きくても
+//! O(log n) 回の掛け算で求まる。n は u128 のほか、10 進数の文字列でも指定できる。
+//! ピサノ周期 π(m)（F(n) mod m の周期）は m を素因数分解し、素数ごとの周期から求める。
+
+use std::fmt;
+
+/// 10 進数の整数として解釈できない文字列が指定された
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNumberError {
+    /// 指定された文字列
+    pub input: String,
+}
+
+impl fmt::Display for ParseNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "10 進数の整数ではありません: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for ParseNumberError {}
+
+/// (F(k) mod m, F(k+1) mod m)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FibPair {
+    f: u64,
+    g: u64,
+}
+
+/// 法 m での演算と (F(k), F(k+1)) の組の操作
+#[derive(Debug, Clone, Copy)]
+struct Modulus(u64);
+
+impl Modulus {
+    fn mul(self, a: u64, b: u64) -> u64 {
+        reduce(u128::from(a) * u128::from(b), self.0)
+    }
+
+    fn add(self, a: u64, b: u64) -> u64 {
+        reduce(u128::from(a) + u128::from(b), self.0)
+    }
+
+    fn sub(self, a: u64, b: u64) -> u64 {
+        reduce(u128::from(a) + u128::from(self.0) - u128::from(b), self.0)
+    }
+
+    /// (F(0), F(1))
+    fn start(self) -> FibPair {
+        FibPair {
+            f: 0,
+            g: 1 % self.0,
+        }
+    }
+
+    /// k → k + 1
+    fn step(self, p: FibPair) -> FibPair {
+        FibPair {
+            f: p.g,
+            g: self.add(p.f, p.g),
+        }
+    }
+
+    /// k → 2k
+    /// - F(2k)   = F(k) × (2F(k+1) − F(k))
+    /// - F(2k+1) = F(k)² + F(k+1)²
+    fn double(self, p: FibPair) -> FibPair {
+        FibPair {
+            f: self.mul(p.f, self.sub(self.add(p.g, p.g), p.f)),
+            g: self.add(self.mul(p.f, p.f), self.mul(p.g, p.g)),
+        }
+    }
+
+    /// a, b → a + b
+    /// - F(a+b)   = F(a) F(b+1) + (F(a+1) − F(a)) F(b)
+    /// - F(a+b+1) = F(a+1) F(b+1) + F(a) F(b)
+    fn sum(self, a: FibPair, b: FibPair) -> FibPair {
+        FibPair {
+            f: self.add(self.mul(a.f, b.g), self.mul(self.sub(a.g, a.f), b.f)),
+            g: self.add(self.mul(a.g, b.g), self.mul(a.f, b.f)),
+        }
+    }
+
+    /// (F(n) mod m, F(n+1) mod m)
+    fn pair(self, n: u128) -> FibPair {
+        let mut p = self.start();
+        for bit in (0..u128::BITS - n.leading_zeros()).rev() {
+            p = self.double(p);
+            if (n >> bit) & 1 == 1 {
+                p = self.step(p);
+            }
+        }
+        p
+    }
+}
+
+/// 剰余（m より小さいので u64 に収まる）
+#[allow(clippy::cast_possible_truncation)]
+fn reduce(x: u128, m: u64) -> u64 {
+    (x % u128::from(m)) as u64
+}
+
+/// F(n) mod m を計算する
+///
+/// # Panics
+/// m が 0 の場合
+///
+/// ```
+/// assert_eq!(functions::modular::fib_mod(1_000_000_000_000_000_000, 1_000_000_007), 209_783_453);
+/// ```
+#[must_use]
+pub fn fib_mod(n: u128, m: u64) -> u64 {
+    assert!(m != 0, "0 で割ることはできません");
+    Modulus(m).pair(n).f
+}
+
+/// F(n) mod m を計算する（n は 10 進数の文字列、桁数の制限なし）
+///
+/// 上の桁から1桁ずつ k → 10k + d と進める
+///
+/// # Errors
+/// n が空、または数字以外を含む場合に `ParseNumberError` を返す
+///
+/// # Panics
+/// m が 0 の場合
+///
+/// ```
+/// use functions::modular::fib_mod_str;
+///
+/// assert_eq!(fib_mod_str("100", 1_000), Ok(75)); // F(100) = …075
+/// assert!(fib_mod_str("1e9", 1_000).is_err());
+/// ```
+pub fn fib_mod_str(n: &str, m: u64) -> Result<u64, ParseNumberError> {
+    assert!(m != 0, "0 で割ることはできません");
+    if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseNumberError {
+            input: n.to_string(),
+        });
+    }
+
+    let modulus = Modulus(m);
+    let mut p = modulus.start();
+    for digit in n.bytes().map(|b| b - b'0') {
+        let twice = modulus.double(p);
+        let eight = modulus.double(modulus.double(twice));
+        p = modulus.sum(eight, twice);
+        for _ in 0..digit {
+            p = modulus.step(p);
+        }
+    }
+    Ok(p.f)
+}
+
+/// ピサノ周期 π(m): F(n) mod m が繰り返す周期
+///
+/// m = Π pᵏ と素因数分解し、π(m) = lcm(π(pᵏ)), π(pᵏ) = pᵏ⁻¹ π(p) で求める。
+/// π(p) は p ≡ ±1 (mod 10) なら p − 1 の、p ≡ ±3 (mod 10) なら 2(p + 1) の約数なので、
+/// その数を周期であるかぎり素因数で割って小さくしていく
+///
+/// π(pᵏ) = pᵏ⁻¹ π(p) は未解決の予想（ウォールの予想）だが、反例は知られていない
+///
+/// # Panics
+/// m が 0 の場合
+///
+/// ```
+/// use functions::modular::pisano_period;
+///
+/// assert_eq!(pisano_period(10), 60);
+/// assert_eq!(pisano_period(1_000_000_007), 2_000_000_016);
+/// ```
+#[must_use]
+pub fn pisano_period(m: u64) -> u128 {
+    assert!(m != 0, "0 で割ることはできません");
+    factorize(m)
+        .into_iter()
+        .map(|(p, k)| u128::from(p).pow(k - 1) * pisano_prime(p))
+        .fold(1, lcm)
+}
+
+/// 素数 p のピサノ周期
+fn pisano_prime(p: u64) -> u128 {
+    match p {
+        2 => return 3,
+        5 => return 20,
+        _ => {}
+    }
+    // 周期の倍数とその素因数分解
+    let (mut period, factors) = if matches!(p % 10, 1 | 9) {
+        (u128::from(p - 1), factorize(p - 1))
+    } else {
+        let mut factors = factorize(p + 1);
+        match factors.first_mut() {
+            Some((2, k)) => *k += 1,
+            _ => factors.insert(0, (2, 1)),
+        }
+        (2 * u128::from(p + 1), factors)
+    };
+
+    let modulus = Modulus(p);
+    let is_period = |k: u128| modulus.pair(k) == modulus.start();
+    for (q, k) in factors {
+        let q = u128::from(q);
+        for _ in 0..k {
+            if !is_period(period / q) {
+                break;
+            }
+            period /= q;
+        }
+    }
+    period
+}
+
+fn gcd<T>(mut a: T, mut b: T) -> T
+where
+    T: Copy + PartialEq + Default + std::ops::Rem<Output = T>,
+{
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn lcm(a: u128, b: u128) -> u128 {
+    a / gcd(a, b) * b
+}
+
+/// 素因数分解（素因数の小さい順に (p, 指数)）
+///
+/// 0 と 1 は素因数を持たないので空の `Vec` を返す
+#[must_use]
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut primes = Vec::new();
+    if n > 1 {
+        collect_prime_factors(n, &mut primes);
+    }
+    primes.sort_unstable();
+
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, k)) if *q == p => *k += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+fn collect_prime_factors(mut n: u64, primes: &mut Vec<u64>) {
+    // 小さい素因数は割り算で取り除く
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        primes.push(n);
+        return;
+    }
+    let d = pollard_rho(n);
+    collect_prime_factors(d, primes);
+    collect_prime_factors(n / d, primes);
+}
+
+/// 素数判定（u64 の範囲で決定的なミラー・ラビン法）
+#[must_use]
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+
+    let modulus = Modulus(n);
+    let pow = |mut base: u64, mut exp: u64| {
+        let mut result = 1;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = modulus.mul(result, base);
+            }
+            base = modulus.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    };
+
+    // n − 1 = d × 2ˢ
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x = pow(a, d);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = modulus.mul(x, x);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// ポラードのロー法で合成数 n の自明でない約数を1つ見つける
+fn pollard_rho(n: u64) -> u64 {
+    let modulus = Modulus(n);
+    for c in 1.. {
+        let next = |x: u64| modulus.add(modulus.mul(x, x), c);
+        let (mut slow, mut fast, mut divisor) = (2, 2, 1);
+        while divisor == 1 {
+            slow = next(slow);
+            fast = next(next(fast));
+            divisor = gcd(slow.abs_diff(fast), n);
+        }
+        if divisor != n {
+            return divisor;
+        }
+    }
+    unreachable!("合成数には必ず約数が見つかる")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib_big;
+
+    /// 定義どおり (0, 1) に戻るまで数える
+    fn naive_pisano(m: u64) -> u128 {
+        let modulus = Modulus(m);
+        let start = modulus.start();
+        let mut p = modulus.step(start);
+        let mut period = 1;
+        while p != start {
+            p = modulus.step(p);
+            period += 1;
+        }
+        period
+    }
+
+    #[test]
+    fn test_fib_mod_matches_fib_big() {
+        for m in [1, 2, 7, 10, 1_000, 1_000_000_007, u64::MAX] {
+            for n in 0..300_u64 {
+                assert_eq!(
+                    fib_mod(u128::from(n), m),
+                    fib_big(n).rem_u64(m),
+                    "F({n}) mod {m}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fib_mod_huge_n() {
+        assert_eq!(fib_mod(10_u128.pow(18), 1_000_000_007), 209_783_453);
+        // 周期で割った余りの n と同じ値になる
+        for m in [10, 1_000, 1_000_000_007] {
+            let period = pisano_period(m);
+            for n in [u128::from(u64::MAX), 10_u128.pow(30) + 7, u128::MAX] {
+                assert_eq!(fib_mod(n, m), fib_mod(n % period, m), "F({n}) mod {m}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fib_mod_str() {
+        for n in [0, 1, 9, 10, 12_345, u128::MAX] {
+            assert_eq!(
+                fib_mod_str(&n.to_string(), 1_000_000_007),
+                Ok(fib_mod(n, 1_000_000_007))
+            );
+        }
+        // 100 桁の n: 1 の後に 0 が 99 個
+        let n = format!("1{}", "0".repeat(99));
+        let period = pisano_period(1_000_000);
+        let reduced = (0..99).fold(1 % period, |acc, _| acc * 10 % period);
+        assert_eq!(fib_mod_str(&n, 1_000_000), Ok(fib_mod(reduced, 1_000_000)));
+
+        for input in ["", "-1", "1_000", "12a"] {
+            assert_eq!(
+                fib_mod_str(input, 7),
+                Err(ParseNumberError {
+                    input: input.to_string()
+                })
+            );
+        }
+        assert_eq!(
+            fib_mod_str("x", 7).unwrap_err().to_string(),
+            "10 進数の整数ではありません: \"x\""
+        );
+    }
+
+    #[test]
+    fn test_pisano_period_matches_naive() {
+        let known = [1, 3, 8, 6, 20, 24, 16, 12, 24, 60, 10, 24];
+        for (m, period) in (1..).zip(known) {
+            assert_eq!(pisano_period(m), period, "π({m})");
+        }
+        for m in 1..=2_000 {
+            assert_eq!(pisano_period(m), naive_pisano(m), "π({m})");
+        }
+    }
+
+    #[test]
+    fn test_pisano_period_large() {
+        assert_eq!(pisano_period(1_000), 1_500);
+        assert_eq!(pisano_period(1_000_000_000), 1_500_000_000);
+        assert_eq!(pisano_period(1_000_000_007), 2_000_000_016);
+        for m in [1_000_000_007, 998_244_353, u64::MAX, (1 << 61) - 1] {
+            let period = pisano_period(m);
+            assert_eq!((fib_mod(period, m), fib_mod(period + 1, m)), (0, 1 % m));
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(0), []);
+        assert_eq!(factorize(1), []);
+        assert_eq!(factorize(360), [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(
+            factorize(u64::MAX),
+            [
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65_537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        // 大きな素数どうしの積
+        assert_eq!(
+            factorize(1_000_000_007 * 998_244_353),
+            [(998_244_353, 1), (1_000_000_007, 1)]
+        );
+        assert!(is_prime((1 << 61) - 1));
+        assert!(!is_prime(3_215_031_751)); // 2, 3, 5, 7 を底とする強擬素数
+        assert!(!is_prime(0) && !is_prime(1) && is_prime(2));
+    }
+}
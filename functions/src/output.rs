@@ -0,0 +1,486 @@
+//! 計算結果の表・CSV・JSON への出力
+//!
+//! `Table` は全ての行をためてから文字列にする。行が多くなるコマンドでは `TableWriter` を使い、
+//! 受け取った行から順に書き出す。
+
+use crate::bench::format_ns;
+use crate::cli::Format;
+use std::fmt::Write;
+use std::io;
+
+/// 表の1マス
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    /// 文字列
+    Text(String),
+    /// 整数（JSON では数値）
+    Integer(u64),
+    /// 巨大になりうる整数（JSON では文字列）
+    Big(String),
+    /// 経過時間（ナノ秒）
+    Duration(f64),
+    /// 比（速度向上など、表では `2.50x`）
+    Ratio(f64),
+    /// 真偽値
+    Bool(bool),
+    /// 値なし（アルゴリズムの上限を超えた場合など）
+    Missing,
+}
+
+impl Cell {
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Cell::Integer(_) | Cell::Big(_) | Cell::Duration(_) | Cell::Ratio(_)
+        )
+    }
+
+    fn to_table(&self) -> String {
+        match self {
+            Cell::Text(s) | Cell::Big(s) => s.clone(),
+            Cell::Integer(v) => v.to_string(),
+            Cell::Duration(ns) => format_ns(*ns),
+            Cell::Ratio(r) => format!("{r:.2}x"),
+            Cell::Bool(true) => "✓".to_string(),
+            Cell::Bool(false) => "✗".to_string(),
+            Cell::Missing => "-".to_string(),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Text(s) if s.contains([',', '"', '\n']) => {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            }
+            Cell::Text(s) | Cell::Big(s) => s.clone(),
+            Cell::Integer(v) => v.to_string(),
+            Cell::Duration(ns) => format!("{ns:.0}"),
+            Cell::Ratio(r) => format!("{r:.3}"),
+            Cell::Bool(b) => b.to_string(),
+            Cell::Missing => String::new(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        match self {
+            Cell::Text(s) | Cell::Big(s) => json_string(s),
+            Cell::Integer(v) => v.to_string(),
+            Cell::Duration(ns) => format!("{ns:.0}"),
+            Cell::Ratio(r) => format!("{r:.3}"),
+            Cell::Bool(b) => b.to_string(),
+            Cell::Missing => "null".to_string(),
+        }
+    }
+}
+
+/// 見出しと行からなる表
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    /// 列の見出し（JSON ではキー）
+    pub headers: Vec<&'static str>,
+    /// 行（各行の長さは見出しと同じ）
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// 見出しだけの空の表
+    #[must_use]
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    /// 行を追加する
+    pub fn push(&mut self, row: Vec<Cell>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+        self.rows.push(row);
+    }
+
+    /// 指定の形式の文字列にする
+    #[must_use]
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Table => self.to_table(),
+            Format::Csv => self.to_csv(),
+            Format::Json => self.to_json(),
+        }
+    }
+
+    /// 列の幅をそろえた表（数値は右寄せ）
+    fn to_table(&self) -> String {
+        let cells: Vec<Vec<String>> = self.rows.iter().map(|row| table_cells(row)).collect();
+        let layout = Layout::new(&self.headers, &self.rows, &cells);
+        let mut out = layout.header(&self.headers);
+        for values in &cells {
+            out.push_str(&layout.row(values));
+        }
+        out
+    }
+
+    fn to_csv(&self) -> String {
+        let mut out = csv_line(&self.headers);
+        for row in &self.rows {
+            out.push_str(&csv_row(row));
+        }
+        out
+    }
+
+    /// 行ごとのオブジェクトの配列
+    fn to_json(&self) -> String {
+        let items: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| json_row(&self.headers, row))
+            .collect();
+        if items.is_empty() {
+            "[]\n".to_string()
+        } else {
+            format!("[\n{}\n]\n", items.join(",\n"))
+        }
+    }
+}
+
+/// 表の各列の幅と、右寄せにするかどうか
+#[derive(Debug)]
+struct Layout {
+    widths: Vec<usize>,
+    /// 数値のマスを含む列は右寄せにする
+    numeric: Vec<bool>,
+}
+
+impl Layout {
+    /// 見出しと行（`cells` は各マスの表示）が収まる幅
+    fn new(headers: &[&str], rows: &[Vec<Cell>], cells: &[Vec<String>]) -> Self {
+        let widths = headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let numeric = (0..headers.len())
+            .map(|i| rows.iter().any(|row| row[i].is_numeric()))
+            .collect();
+        Self { widths, numeric }
+    }
+
+    /// 見出しと罫線の2行
+    fn header(&self, headers: &[&str]) -> String {
+        let rule: Vec<String> = self.widths.iter().map(|&w| "-".repeat(w)).collect();
+        let rule: Vec<&str> = rule.iter().map(String::as_str).collect();
+        self.line(headers, &self.numeric) + &self.line(&rule, &vec![false; rule.len()])
+    }
+
+    fn row(&self, values: &[String]) -> String {
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        self.line(&values, &self.numeric)
+    }
+
+    /// 幅をそろえた1行（幅より長いマスはそのまま書く）
+    fn line(&self, values: &[&str], right: &[bool]) -> String {
+        let fields: Vec<String> = values
+            .iter()
+            .zip(right)
+            .zip(&self.widths)
+            .map(|((&value, &right), &width)| {
+                // 文字数で幅をそろえる（`{:>width$}` は幅が u16 を超えると panic する）
+                let padding = " ".repeat(width.saturating_sub(value.chars().count()));
+                if right {
+                    padding + value
+                } else {
+                    value.to_string() + &padding
+                }
+            })
+            .collect();
+        format!("{}\n", fields.join("  ").trim_end())
+    }
+}
+
+/// 表の形式で、列の幅を決めるまでためておく行の数
+pub const ALIGN_ROWS: usize = 1_000;
+
+/// 表の形式で、列の幅を決めるまでためておく文字数
+pub const ALIGN_BYTES: usize = 1 << 20;
+
+/// 行を受け取るたびに出力する表
+///
+/// CSV・JSON は行を受け取るたびに書き出すので、行が多くても全体をためない。
+/// 表の形式は最初の `ALIGN_ROWS` 行（または `ALIGN_BYTES` 文字）で列の幅を決めて書き出し、
+/// それ以降の行はその幅でそろえる（幅より長いマスははみ出す）。
+/// 行がそれより少なければ、出力は `Table::render` と同じ。
+///
+/// ```
+/// use functions::cli::Format;
+/// use functions::output::{Cell, TableWriter};
+///
+/// let mut writer = TableWriter::new(Vec::new(), vec!["n", "value"], Format::Csv).unwrap();
+/// writer.push(vec![Cell::Integer(10), Cell::Integer(55)]).unwrap();
+/// let out = writer.finish().unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "n,value\n10,55\n");
+/// ```
+#[derive(Debug)]
+pub struct TableWriter<W: io::Write> {
+    out: W,
+    format: Format,
+    headers: Vec<&'static str>,
+    /// 表の形式で、列の幅を決める前の行
+    pending: Vec<Vec<Cell>>,
+    /// `pending` の表示の文字数
+    pending_bytes: usize,
+    /// 表の形式で決めた列の幅
+    layout: Option<Layout>,
+    /// 書き出した行の数
+    written: usize,
+}
+
+impl<W: io::Write> TableWriter<W> {
+    /// 見出しを決めて書き始める（CSV はここで見出しの行を書く）
+    ///
+    /// # Errors
+    /// 書き込みに失敗した場合に `io::Error` を返す
+    pub fn new(mut out: W, headers: Vec<&'static str>, format: Format) -> io::Result<Self> {
+        if format == Format::Csv {
+            out.write_all(csv_line(&headers).as_bytes())?;
+        }
+        Ok(Self {
+            out,
+            format,
+            headers,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            layout: None,
+            written: 0,
+        })
+    }
+
+    /// 行を追加する
+    ///
+    /// # Errors
+    /// 書き込みに失敗した場合に `io::Error` を返す
+    pub fn push(&mut self, row: Vec<Cell>) -> io::Result<()> {
+        debug_assert_eq!(row.len(), self.headers.len());
+        match self.format {
+            Format::Table => {
+                if let Some(layout) = &self.layout {
+                    let line = layout.row(&table_cells(&row));
+                    return self.out.write_all(line.as_bytes());
+                }
+                self.pending_bytes += table_cells(&row).iter().map(String::len).sum::<usize>();
+                self.pending.push(row);
+                if self.pending.len() >= ALIGN_ROWS || self.pending_bytes >= ALIGN_BYTES {
+                    self.write_pending()?;
+                }
+                Ok(())
+            }
+            Format::Csv => self.out.write_all(csv_row(&row).as_bytes()),
+            Format::Json => {
+                let separator = if self.written == 0 { "[\n" } else { ",\n" };
+                self.written += 1;
+                write!(self.out, "{separator}{}", json_row(&self.headers, &row))
+            }
+        }
+    }
+
+    /// ためた行で列の幅を決め、見出しとともに書き出す
+    fn write_pending(&mut self) -> io::Result<()> {
+        let rows = std::mem::take(&mut self.pending);
+        let cells: Vec<Vec<String>> = rows.iter().map(|row| table_cells(row)).collect();
+        let layout = Layout::new(&self.headers, &rows, &cells);
+        let mut text = layout.header(&self.headers);
+        for values in &cells {
+            text.push_str(&layout.row(values));
+        }
+        self.layout = Some(layout);
+        self.out.write_all(text.as_bytes())
+    }
+
+    /// 残りを書き出して、書き込み先を返す
+    ///
+    /// # Errors
+    /// 書き込みに失敗した場合に `io::Error` を返す
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.format {
+            Format::Table if self.layout.is_none() => self.write_pending()?,
+            Format::Table | Format::Csv => {}
+            Format::Json if self.written == 0 => self.out.write_all(b"[]\n")?,
+            Format::Json => self.out.write_all(b"\n]\n")?,
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// 表の形式での1行の各マス
+fn table_cells(row: &[Cell]) -> Vec<String> {
+    row.iter().map(Cell::to_table).collect()
+}
+
+/// CSV の1行（見出し）
+fn csv_line(fields: &[&str]) -> String {
+    format!("{}\n", fields.join(","))
+}
+
+/// CSV の1行（値）
+fn csv_row(row: &[Cell]) -> String {
+    let fields: Vec<String> = row.iter().map(Cell::to_csv).collect();
+    format!("{}\n", fields.join(","))
+}
+
+/// JSON の1つのオブジェクト（先頭の字下げを含み、区切りと改行は含まない）
+fn json_row(headers: &[&str], row: &[Cell]) -> String {
+    let fields: Vec<String> = headers
+        .iter()
+        .zip(row)
+        .map(|(header, cell)| format!("{}: {}", json_string(header), cell.to_json()))
+        .collect();
+    format!("  {{{}}}", fields.join(", "))
+}
+
+/// JSON の文字列リテラルにする
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Table {
+        let mut table = Table::new(vec!["algorithm", "n", "value", "time", "ok"]);
+        table.push(vec![
+            Cell::Text("binet".to_string()),
+            Cell::Integer(100),
+            Cell::Missing,
+            Cell::Duration(1500.0),
+            Cell::Bool(false),
+        ]);
+        table.push(vec![
+            Cell::Text("matrix".to_string()),
+            Cell::Integer(100),
+            Cell::Big("354224848179261915075".to_string()),
+            Cell::Duration(20.0),
+            Cell::Bool(true),
+        ]);
+        table
+    }
+
+    #[test]
+    fn test_render_table() {
+        assert_eq!(
+            sample().render(Format::Table),
+            "\
+algorithm    n                  value     time  ok
+---------  ---  ---------------------  -------  --
+binet      100                      -  1.50 µs  ✗
+matrix     100  354224848179261915075    20 ns  ✓
+"
+        );
+
+        // 20 万桁を超える F(10⁶) のような巨大な値でもそろえられる
+        let wide = "9".repeat(100_000);
+        let mut table = Table::new(vec!["n", "value"]);
+        table.push(vec![Cell::Integer(1), Cell::Big(wide.clone())]);
+        table.push(vec![Cell::Integer(2), Cell::Big("1".to_string())]);
+        let rendered = table.render(Format::Table);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[2], format!("1  {wide}"));
+        assert_eq!(lines[3], format!("2  {}1", " ".repeat(wide.len() - 1)));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(
+            sample().render(Format::Csv),
+            "algorithm,n,value,time,ok\nbinet,100,,1500,false\nmatrix,100,354224848179261915075,20,true\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        assert_eq!(
+            sample().render(Format::Json),
+            "[\n  {\"algorithm\": \"binet\", \"n\": 100, \"value\": null, \"time\": 1500, \"ok\": false},\n  \
+             {\"algorithm\": \"matrix\", \"n\": 100, \"value\": \"354224848179261915075\", \"time\": 20, \"ok\": true}\n]\n"
+        );
+        assert_eq!(Table::new(vec!["n"]).render(Format::Json), "[]\n");
+
+        let mut table = Table::new(vec!["threads", "speedup"]);
+        table.push(vec![Cell::Integer(4), Cell::Ratio(3.456_7)]);
+        assert_eq!(
+            table.render(Format::Json),
+            "[\n  {\"threads\": 4, \"speedup\": 3.457}\n]\n"
+        );
+        assert_eq!(
+            table.render(Format::Table),
+            "threads  speedup\n-------  -------\n      4    3.46x\n"
+        );
+    }
+
+    #[test]
+    fn test_writer_matches_render() {
+        let empty = Table::new(vec!["n", "value"]);
+        for table in [sample(), empty] {
+            for format in [Format::Table, Format::Csv, Format::Json] {
+                let mut writer =
+                    TableWriter::new(Vec::new(), table.headers.clone(), format).unwrap();
+                for row in &table.rows {
+                    writer.push(row.clone()).unwrap();
+                }
+                let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+                assert_eq!(out, table.render(format), "{format:?}");
+            }
+        }
+
+        // CSV・JSON は finish の前に書き出している
+        let mut writer = TableWriter::new(Vec::new(), vec!["n"], Format::Json).unwrap();
+        writer.push(vec![Cell::Integer(1)]).unwrap();
+        writer.push(vec![Cell::Integer(2)]).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&writer.out),
+            "[\n  {\"n\": 1},\n  {\"n\": 2}"
+        );
+    }
+
+    #[test]
+    fn test_writer_streams_long_table() {
+        let mut writer = TableWriter::new(Vec::new(), vec!["n"], Format::Table).unwrap();
+        for n in 0..ALIGN_ROWS as u64 {
+            assert!(writer.out.is_empty());
+            writer.push(vec![Cell::Integer(n)]).unwrap();
+        }
+        // 最初の ALIGN_ROWS 行で幅を決めて書き出し、以降の行は1行ずつ書く
+        let aligned = writer.out.len();
+        assert!(String::from_utf8_lossy(&writer.out).starts_with("  n\n---\n  0\n"));
+        writer.push(vec![Cell::Integer(123_456)]).unwrap();
+        assert_eq!(&writer.out[aligned..], b"123456\n");
+        writer.push(vec![Cell::Integer(7)]).unwrap();
+        assert_eq!(&writer.out[aligned + 7..], b"  7\n");
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(Cell::Text("a,b".to_string()).to_csv(), "\"a,b\"");
+    }
+}
@@ -0,0 +1,171 @@
+//! スレッドを使った並列計算
+//!
+//! `join` は2つの処理を fork-join で並列に実行する（rayon の `join` と同じ形）。
+//! 使ってよいスレッド数を引数で受け取り、分岐のたびに2つの処理で分け合うので、
+//! 同時に動くスレッドは指定した数を超えない。スレッドは `std::thread::scope` で作る。
+
+use crate::bigint::{self, BigUint};
+use crate::{fib, fib_big};
+use std::num::NonZeroUsize;
+use std::panic;
+use std::thread;
+
+/// 並列再帰版で、これより小さい n は逐次の `fib` で計算する
+///
+/// F(20) の計算は約 2 万回の呼び出しで、スレッドを作る時間（数十 µs）と同じくらいになる
+pub const SEQUENTIAL_CUTOFF: u32 = 20;
+
+/// 使えるスレッド数（CPU の論理コア数、分からなければ 1）
+#[must_use]
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// `left` と `right` を並列に実行し、両方の結果を返す
+///
+/// 各処理には使ってよいスレッド数が渡される。`threads` が 1 以下なら現在のスレッドで順に実行する。
+/// どちらかがパニックした場合は、そのパニックを呼び出し元に伝える。
+///
+/// ```
+/// use functions::parallel::join;
+///
+/// let (sum, product) = join(2, |_| (1..=10).sum::<u32>(), |_| (1..=10).product::<u32>());
+/// assert_eq!((sum, product), (55, 3_628_800));
+/// ```
+pub fn join<A, B, L, R>(threads: usize, left: L, right: R) -> (A, B)
+where
+    A: Send,
+    L: FnOnce(usize) -> A + Send,
+    R: FnOnce(usize) -> B,
+{
+    if threads < 2 {
+        return (left(1), right(1));
+    }
+    let right_threads = threads / 2;
+    let left_threads = threads - right_threads;
+    thread::scope(|scope| {
+        let handle = scope.spawn(move || left(left_threads));
+        let b = right(right_threads);
+        let a = handle.join().unwrap_or_else(|e| panic::resume_unwind(e));
+        (a, b)
+    })
+}
+
+/// F(n) を並列の再帰で計算する（u32 に収まる n ≤ 47）
+///
+/// F(n − 1) と F(n − 2) を別のスレッドで計算し、n が `SEQUENTIAL_CUTOFF` 未満になるか
+/// スレッドを使い切ったら逐次の `fib` に切り替える
+#[must_use]
+pub fn fib_recursive(n: u32, threads: usize) -> u32 {
+    if n < SEQUENTIAL_CUTOFF || threads < 2 {
+        return fib(n);
+    }
+    // 計算量の多い F(n − 1) の方に多くのスレッドを割り当てる
+    let (a, b) = join(
+        threads,
+        |t| fib_recursive(n - 1, t),
+        |t| fib_recursive(n - 2, t),
+    );
+    a + b
+}
+
+/// F(n) を高速倍加法で計算し、各段の3つの掛け算とその中の掛け算を並列にする
+///
+/// 値が `bigint::PARALLEL_THRESHOLD` リムより短い間は `fib_big` と同じく逐次で計算する
+#[must_use]
+pub fn fib_big_parallel(n: u64, threads: usize) -> BigUint {
+    if threads < 2 {
+        return fib_big(n);
+    }
+    let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let threads = if a.limb_count() >= bigint::PARALLEL_THRESHOLD {
+            threads
+        } else {
+            1
+        };
+        let twice_b_minus_a = &(&b + &b) - &a;
+        // F(2k) = F(k)(2F(k+1) − F(k)), F(2k+1) = F(k)² + F(k+1)²
+        let (even, (a2, b2)) = join(
+            threads,
+            |t| a.mul_parallel(&twice_b_minus_a, t),
+            |t| join(t, |t| a.mul_parallel(&a, t), |t| b.mul_parallel(&b, t)),
+        );
+        let odd = &a2 + &b2;
+        (a, b) = if (n >> bit) & 1 == 1 {
+            let next = &even + &odd;
+            (odd, next)
+        } else {
+            (even, odd)
+        };
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_join_splits_threads() {
+        assert_eq!(join(1, |t| t, |t| t), (1, 1));
+        assert_eq!(join(2, |t| t, |t| t), (1, 1));
+        assert_eq!(join(5, |t| t, |t| t), (3, 2));
+        assert_eq!(
+            join(8, |t| t * 10, |t| format!("{t}")),
+            (40, "4".to_string())
+        );
+
+        // 入れ子にしても同時に動くスレッドは指定した数を超えない
+        let spawned = AtomicUsize::new(0);
+        fn count(depth: u32, threads: usize, spawned: &AtomicUsize) -> usize {
+            if depth == 0 {
+                return 1;
+            }
+            if threads >= 2 {
+                spawned.fetch_add(1, Ordering::Relaxed);
+            }
+            let (a, b) = join(
+                threads,
+                |t| count(depth - 1, t, spawned),
+                |t| count(depth - 1, t, spawned),
+            );
+            a + b
+        }
+        assert_eq!(count(6, 4, &spawned), 64);
+        assert_eq!(spawned.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "left")]
+    fn test_join_propagates_panic() {
+        join(2, |_| panic!("left"), |_| ());
+    }
+
+    #[test]
+    fn test_fib_recursive_matches_sequential() {
+        for threads in [1, 2, 3, 8] {
+            for n in 0..=30 {
+                assert_eq!(
+                    fib_recursive(n, threads),
+                    fib(n),
+                    "n = {n}, threads = {threads}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fib_big_parallel_matches_sequential() {
+        for threads in [1, 2, 3, 4] {
+            for n in [0, 1, 2, 93, 94, 1_000, 100_000, 300_001] {
+                assert_eq!(
+                    fib_big_parallel(n, threads),
+                    fib_big(n),
+                    "n = {n}, threads = {threads}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,782 @@
+//! 一般の線形漸化式
+//!
+//! a(n) = c₁ a(n−1) + c₂ a(n−2) + … + cₖ a(n−k) の形の数列を、
+//! 係数 c と初項 a(0)〜a(k−1) で表す。フィボナッチ数列はその特別な場合。
+//!
+//! - 正確な値: コンパニオン行列の累乗（O(k³ log n) 回の多倍長演算）
+//! - ビネの公式に相当する一般項: 特性方程式の根 rᵢ を数値的に求め、
+//!   a(n) = Σ wᵢ rᵢⁿ の重み wᵢ を初項から解く（根に重複がない場合のみ）
+//!
+//! 係数と初項は i64 の整数で、負の値も使える（値は符号つきの多倍長整数 `BigInt` で計算する）。
+
+use crate::bigint::BigInt;
+use crate::binet::PrecisionError;
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+
+/// 一般項の精度を調べる最大の項数（値が増えない数列でも調べ終わるように）
+const CLOSED_FORM_CHECK_TERMS: usize = 1_000;
+
+/// 漸化式の指定の誤り
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecurrenceError {
+    /// 係数が1つもない
+    Empty,
+    /// 係数と初項の個数が違う
+    LengthMismatch {
+        /// 係数の個数
+        coefficients: usize,
+        /// 初項の個数
+        initial: usize,
+    },
+    /// 数列の名前でも `係数:初項` の形でもない
+    Parse(String),
+}
+
+impl fmt::Display for RecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecurrenceError::Empty => write!(f, "係数を1つ以上指定してください"),
+            RecurrenceError::LengthMismatch {
+                coefficients,
+                initial,
+            } => write!(
+                f,
+                "係数の個数 ({coefficients}) と初項の個数 ({initial}) が違います"
+            ),
+            RecurrenceError::Parse(input) => write!(
+                f,
+                "数列の名前か「係数:初項」（例: 1,1:0,1）を指定してください: {input}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecurrenceError {}
+
+/// 定数係数の線形漸化式で定まる数列
+///
+/// ```
+/// use functions::recurrence::LinearRecurrence;
+///
+/// let pell: LinearRecurrence = "2,1:0,1".parse().unwrap();
+/// assert_eq!(pell.to_string(), "a(n) = 2a(n-1) + a(n-2)（初項 0, 1）");
+/// assert_eq!(pell.term(10).to_string(), "2378");
+///
+/// // 負の係数: a(n) = 2a(n-1) - a(n-2) は等差数列
+/// let linear: LinearRecurrence = "2,-1:5,3".parse().unwrap();
+/// assert_eq!(linear.to_string(), "a(n) = 2a(n-1) - a(n-2)（初項 5, 3）");
+/// assert_eq!(linear.term(100).to_string(), "-195");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    name: String,
+    coefficients: Vec<i64>,
+    initial: Vec<i64>,
+}
+
+impl LinearRecurrence {
+    /// 係数 [c₁, …, cₖ] と初項 [a(0), …, a(k−1)] から作る
+    ///
+    /// # Errors
+    /// 係数が空の場合や、係数と初項の個数が違う場合に `RecurrenceError` を返す
+    pub fn new(
+        name: impl Into<String>,
+        coefficients: Vec<i64>,
+        initial: Vec<i64>,
+    ) -> Result<Self, RecurrenceError> {
+        if coefficients.is_empty() {
+            return Err(RecurrenceError::Empty);
+        }
+        if coefficients.len() != initial.len() {
+            return Err(RecurrenceError::LengthMismatch {
+                coefficients: coefficients.len(),
+                initial: initial.len(),
+            });
+        }
+        Ok(Self {
+            name: name.into(),
+            coefficients,
+            initial,
+        })
+    }
+
+    fn preset(name: &str, coefficients: &[i64], initial: &[i64]) -> Self {
+        Self {
+            name: name.to_string(),
+            coefficients: coefficients.to_vec(),
+            initial: initial.to_vec(),
+        }
+    }
+
+    /// フィボナッチ数: F(n) = F(n−1) + F(n−2), 0, 1
+    #[must_use]
+    pub fn fibonacci() -> Self {
+        Self::preset("fibonacci", &[1, 1], &[0, 1])
+    }
+
+    /// リュカ数: L(n) = L(n−1) + L(n−2), 2, 1
+    #[must_use]
+    pub fn lucas() -> Self {
+        Self::preset("lucas", &[1, 1], &[2, 1])
+    }
+
+    /// トリボナッチ数: T(n) = T(n−1) + T(n−2) + T(n−3), 0, 0, 1
+    #[must_use]
+    pub fn tribonacci() -> Self {
+        Self::preset("tribonacci", &[1, 1, 1], &[0, 0, 1])
+    }
+
+    /// ペル数: P(n) = 2P(n−1) + P(n−2), 0, 1
+    #[must_use]
+    pub fn pell() -> Self {
+        Self::preset("pell", &[2, 1], &[0, 1])
+    }
+
+    /// 名前のついた数列の一覧
+    #[must_use]
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self::fibonacci(),
+            Self::lucas(),
+            Self::tribonacci(),
+            Self::pell(),
+        ]
+    }
+
+    /// 数列の名前
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 階数 k
+    #[must_use]
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// a(n) を計算する（コンパニオン行列の累乗）
+    ///
+    /// 状態 [a(n+k−1), …, a(n)] に行列 C を掛けると1つ進むので、
+    /// a(n) = (Cⁿ⁻ᵏ⁺¹ × [a(k−1), …, a(0)]) の先頭
+    #[must_use]
+    pub fn term(&self, n: u64) -> BigInt {
+        let k = self.order() as u64;
+        if let Some(&value) = usize::try_from(n).ok().and_then(|i| self.initial.get(i)) {
+            return BigInt::from(value);
+        }
+
+        let power = SquareMatrix::companion(&self.coefficients).pow(n - (k - 1));
+        power.rows[0]
+            .iter()
+            .zip(self.initial.iter().rev())
+            .fold(BigInt::zero(), |acc, (x, &a)| {
+                &acc + &(x * &BigInt::from(a))
+            })
+    }
+
+    /// a(0) から a(count−1) までを順に足し合わせて計算する
+    #[must_use]
+    pub fn terms(&self, count: usize) -> Vec<BigInt> {
+        let mut terms: Vec<BigInt> = self.initial.iter().map(|&a| BigInt::from(a)).collect();
+        while terms.len() < count {
+            let next = self
+                .coefficients
+                .iter()
+                .zip(terms.iter().rev())
+                .fold(BigInt::zero(), |acc, (&c, a)| {
+                    &acc + &(&BigInt::from(c) * a)
+                });
+            terms.push(next);
+        }
+        terms.truncate(count);
+        terms
+    }
+
+    /// a(start) から順に返す終わりのないイテレータ
+    ///
+    /// 最初の k 項だけを `term` で求め、以降は漸化式で1項ずつ足し合わせる
+    pub fn iter_from(&self, start: u64) -> impl Iterator<Item = BigInt> + '_ {
+        let mut window: VecDeque<BigInt> = (0..self.order() as u64)
+            .map(|i| self.term(start.saturating_add(i)))
+            .collect();
+        std::iter::from_fn(move || {
+            // window = [a(n), …, a(n+k−1)] から a(n+k) を求めて a(n) を返す
+            let next = self
+                .coefficients
+                .iter()
+                .zip(window.iter().rev())
+                .fold(BigInt::zero(), |acc, (&c, a)| {
+                    &acc + &(&BigInt::from(c) * a)
+                });
+            window.push_back(next);
+            window.pop_front()
+        })
+    }
+
+    /// ビネの公式に相当する一般項を求める
+    ///
+    /// 特性方程式の根に重複がある場合や、数値誤差で初項すら再現できない場合は `None`
+    #[must_use]
+    pub fn closed_form(&self) -> Option<ClosedForm> {
+        let roots = characteristic_roots(&self.coefficients);
+        let distinct = roots.iter().enumerate().all(|(i, &r)| {
+            roots[i + 1..]
+                .iter()
+                .all(|&s| (r - s).abs() > 1e-6 * (1.0 + r.abs()))
+        });
+        if !distinct {
+            return None;
+        }
+
+        // Vandermonde 行列 V[n][i] = rᵢⁿ で V w = a(0..k) を解く
+        let k = self.order();
+        #[allow(clippy::cast_precision_loss)]
+        let matrix: Vec<Vec<Complex>> = (0..k)
+            .map(|n| {
+                let n_u32 = u32::try_from(n).unwrap_or(u32::MAX);
+                let mut row: Vec<Complex> = roots.iter().map(|&r| r.powi(n_u32)).collect();
+                row.push(Complex::from(self.initial[n] as f64));
+                row
+            })
+            .collect();
+        let weights = solve(matrix)?;
+
+        let mut form = ClosedForm {
+            roots,
+            weights,
+            max_n: 0,
+        };
+        // i128 に収まる範囲で、正確な値と最初に食い違う n の手前までを正確とする
+        let exact = self.exact_i128_terms();
+        let matched = (0_u32..)
+            .zip(&exact)
+            .take_while(|&(n, &a)| form.round(n) == Some(a))
+            .count();
+        if matched < k {
+            return None;
+        }
+        form.max_n = u32::try_from(matched - 1).unwrap_or(u32::MAX);
+        Some(form)
+    }
+
+    /// i128 に収まる限りの項（最大 `CLOSED_FORM_CHECK_TERMS` 項）
+    fn exact_i128_terms(&self) -> Vec<i128> {
+        let mut terms: Vec<i128> = self.initial.iter().map(|&a| i128::from(a)).collect();
+        while terms.len() < CLOSED_FORM_CHECK_TERMS {
+            let next = self
+                .coefficients
+                .iter()
+                .zip(terms.iter().rev())
+                .try_fold(0_i128, |acc, (&c, &a)| {
+                    i128::from(c).checked_mul(a)?.checked_add(acc)
+                });
+            match next {
+                Some(next) => terms.push(next),
+                None => break,
+            }
+        }
+        terms
+    }
+}
+
+impl fmt::Display for LinearRecurrence {
+    /// `a(n) = 2a(n-1) - a(n-2)（初項 0, 1）` の形
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rhs = String::new();
+        for (i, &c) in self
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c != 0)
+        {
+            let sign = match (rhs.is_empty(), c < 0) {
+                (true, false) => "",
+                (true, true) => "-",
+                (false, false) => " + ",
+                (false, true) => " - ",
+            };
+            let factor = match c.unsigned_abs() {
+                1 => String::new(),
+                abs => abs.to_string(),
+            };
+            let _ = write!(rhs, "{sign}{factor}a(n-{})", i + 1);
+        }
+        if rhs.is_empty() {
+            rhs.push('0');
+        }
+        let initial: Vec<String> = self.initial.iter().map(ToString::to_string).collect();
+        write!(f, "a(n) = {rhs}（初項 {}）", initial.join(", "))
+    }
+}
+
+impl FromStr for LinearRecurrence {
+    type Err = RecurrenceError;
+
+    /// 数列の名前（`lucas` など）か、`係数:初項`（例: `1,1,1:0,0,1`）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(preset) = Self::presets().into_iter().find(|r| r.name == s) {
+            return Ok(preset);
+        }
+        let parse_list = |list: &str| -> Result<Vec<i64>, RecurrenceError> {
+            list.split(',')
+                .map(|x| {
+                    x.trim()
+                        .parse()
+                        .map_err(|_| RecurrenceError::Parse(s.to_string()))
+                })
+                .collect()
+        };
+        let (coefficients, initial) = s
+            .split_once(':')
+            .ok_or_else(|| RecurrenceError::Parse(s.to_string()))?;
+        Self::new(s, parse_list(coefficients)?, parse_list(initial)?)
+    }
+}
+
+/// 特性根による一般項 a(n) = Σ wᵢ rᵢⁿ（浮動小数点で計算する）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedForm {
+    roots: Vec<Complex>,
+    weights: Vec<Complex>,
+    max_n: u32,
+}
+
+impl ClosedForm {
+    /// 正確な値と一致する最大の n（i128 に収まる範囲で調べたもの）
+    #[must_use]
+    pub fn max_n(&self) -> u32 {
+        self.max_n
+    }
+
+    /// a(n) を一般項から計算する
+    ///
+    /// # Errors
+    /// n が `max_n()` を超える場合に `PrecisionError` を返す
+    pub fn term(&self, n: u32) -> Result<i128, PrecisionError> {
+        if n > self.max_n {
+            return Err(PrecisionError {
+                n,
+                max_n: self.max_n,
+            });
+        }
+        Ok(self.round(n).unwrap_or_default())
+    }
+
+    /// Σ wᵢ rᵢⁿ の実部を最も近い整数に丸める（i128 に収まらなければ `None`）
+    #[allow(clippy::cast_possible_truncation)]
+    fn round(&self, n: u32) -> Option<i128> {
+        let value = self
+            .roots
+            .iter()
+            .zip(&self.weights)
+            .fold(Complex::from(0.0), |acc, (&r, &w)| acc + w * r.powi(n))
+            .re
+            .round();
+        // -2^127 は i128 に収まるが、2^127 ちょうどは収まらない
+        let limit = 2.0_f64.powi(127);
+        (value.is_finite() && value >= -limit && value < limit).then_some(value as i128)
+    }
+}
+
+/// 多倍長整数の正方行列
+#[derive(Clone)]
+struct SquareMatrix {
+    rows: Vec<Vec<BigInt>>,
+}
+
+impl SquareMatrix {
+    fn identity(k: usize) -> Self {
+        let rows = (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| {
+                        if i == j {
+                            BigInt::one()
+                        } else {
+                            BigInt::zero()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// 1行目が係数、その下が 1 つずらした単位行列
+    fn companion(coefficients: &[i64]) -> Self {
+        let k = coefficients.len();
+        let mut matrix = Self::identity(k);
+        matrix.rows.rotate_right(1);
+        matrix.rows[0] = coefficients.iter().map(|&c| BigInt::from(c)).collect();
+        matrix
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let k = self.rows.len();
+        let rows = (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| {
+                        (0..k).fold(BigInt::zero(), |acc, l| {
+                            &acc + &(&self.rows[i][l] * &other.rows[l][j])
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { rows }
+    }
+
+    fn pow(&self, mut exp: u64) -> Self {
+        let mut result = Self::identity(self.rows.len());
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+}
+
+/// 複素数（特性根の計算用）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Self { re, im: 0.0 }
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let norm = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self {
+            re: (self.re * rhs.re + self.im * rhs.im) / norm,
+            im: (self.im * rhs.re - self.re * rhs.im) / norm,
+        }
+    }
+}
+
+impl Complex {
+    fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    fn powi(self, n: u32) -> Self {
+        let mut result = Self::from(1.0);
+        let mut base = self;
+        let mut k = n;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            k >>= 1;
+        }
+        result
+    }
+}
+
+/// 特性方程式 xᵏ − c₁xᵏ⁻¹ − … − cₖ = 0 の根
+///
+/// デュラン・ケルナー法ですべての根を同時に求め、ニュートン法で仕上げる
+#[allow(clippy::cast_precision_loss)]
+fn characteristic_roots(coefficients: &[i64]) -> Vec<Complex> {
+    // 多項式の値と微分値（ホーナー法）
+    let eval = |z: Complex| {
+        let (mut p, mut dp) = (Complex::from(1.0), Complex::from(0.0));
+        for &c in coefficients {
+            dp = dp * z + p;
+            p = p * z - Complex::from(c as f64);
+        }
+        (p, dp)
+    };
+
+    // 根の大きさは 1 + max|cᵢ| 以下
+    let radius = 1.0
+        + coefficients
+            .iter()
+            .map(|c| c.unsigned_abs())
+            .max()
+            .unwrap_or_default() as f64;
+    let seed = Complex { re: 0.4, im: 0.9 };
+    let mut roots: Vec<Complex> = (0..coefficients.len())
+        .map(|i| seed.powi(u32::try_from(i).unwrap_or_default()) * Complex::from(radius))
+        .collect();
+
+    for _ in 0..1_000 {
+        let mut change: f64 = 0.0;
+        for i in 0..roots.len() {
+            let denominator = (0..roots.len())
+                .filter(|&j| j != i)
+                .fold(Complex::from(1.0), |acc, j| acc * (roots[i] - roots[j]));
+            let delta = eval(roots[i]).0 / denominator;
+            if delta.re.is_finite() && delta.im.is_finite() {
+                roots[i] = roots[i] - delta;
+                change = change.max(delta.abs() / (1.0 + roots[i].abs()));
+            }
+        }
+        if change < 1e-16 {
+            break;
+        }
+    }
+
+    for root in &mut roots {
+        for _ in 0..3 {
+            let (p, dp) = eval(*root);
+            let delta = p / dp;
+            if delta.re.is_finite() && delta.im.is_finite() {
+                *root = *root - delta;
+            }
+        }
+    }
+    roots
+}
+
+/// 拡大係数行列 [A | b] の連立一次方程式を解く（部分ピボット選択つきガウスの消去法）
+///
+/// 正則でなければ `None`
+fn solve(mut matrix: Vec<Vec<Complex>>) -> Option<Vec<Complex>> {
+    let k = matrix.len();
+    for col in 0..k {
+        let pivot =
+            (col..k).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower {
+            let factor = row[col] / pivot_row[col];
+            for (x, &p) in row.iter_mut().zip(pivot_row).skip(col) {
+                *x = *x - factor * p;
+            }
+        }
+    }
+
+    let mut solution = vec![Complex::from(0.0); k];
+    for row in (0..k).rev() {
+        let sum = (row + 1..k).fold(matrix[row][k], |acc, j| acc - matrix[row][j] * solution[j]);
+        solution[row] = sum / matrix[row][row];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib_big;
+
+    fn strings(values: &[BigInt]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_presets_first_terms() {
+        let expected = [
+            ("fibonacci", "0 1 1 2 3 5 8 13 21 34"),
+            ("lucas", "2 1 3 4 7 11 18 29 47 76"),
+            ("tribonacci", "0 0 1 1 2 4 7 13 24 44"),
+            ("pell", "0 1 2 5 12 29 70 169 408 985"),
+        ];
+        for (recurrence, (name, terms)) in LinearRecurrence::presets().iter().zip(expected) {
+            assert_eq!(recurrence.name(), name);
+            assert_eq!(strings(&recurrence.terms(10)).join(" "), terms, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_matrix_term_matches_iteration() {
+        let custom = LinearRecurrence::new("custom", vec![0, 3, 0, 2], vec![1, 0, 4, 1]).unwrap();
+        let signed = LinearRecurrence::new("signed", vec![-3, 1, 2], vec![4, -1, 0]).unwrap();
+        for recurrence in LinearRecurrence::presets()
+            .into_iter()
+            .chain([custom, signed])
+        {
+            let terms = recurrence.terms(300);
+            for (n, expected) in (0..).zip(&terms) {
+                assert_eq!(
+                    &recurrence.term(n),
+                    expected,
+                    "{} a({n})",
+                    recurrence.name()
+                );
+            }
+            for start in [0, 1, 3, 150] {
+                let from: Vec<BigInt> = recurrence.iter_from(start).take(100).collect();
+                assert_eq!(
+                    from,
+                    terms[start as usize..start as usize + 100],
+                    "{} iter_from({start})",
+                    recurrence.name()
+                );
+            }
+        }
+        let fibonacci = LinearRecurrence::fibonacci();
+        assert_eq!(fibonacci.term(10_000), BigInt::from(fib_big(10_000)));
+    }
+
+    #[test]
+    fn test_closed_form_matches_exact_values() {
+        for recurrence in LinearRecurrence::presets() {
+            let form = recurrence.closed_form().expect("根に重複はない");
+            // 値が f64 の仮数（53 ビット）に収まる程度の範囲は正確
+            assert!(
+                form.max_n() >= 35,
+                "{}: {}",
+                recurrence.name(),
+                form.max_n()
+            );
+            for n in 0..=form.max_n() {
+                assert_eq!(
+                    form.term(n).unwrap().to_string(),
+                    recurrence.term(u64::from(n)).to_string(),
+                    "{} a({n})",
+                    recurrence.name()
+                );
+            }
+            let n = form.max_n() + 1;
+            assert_eq!(
+                form.term(n),
+                Err(PrecisionError {
+                    n,
+                    max_n: form.max_n()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_characteristic_roots() {
+        // フィボナッチ数列の根は黄金比 φ と ψ
+        let form = LinearRecurrence::fibonacci().closed_form().unwrap();
+        let mut roots: Vec<f64> = form.roots.iter().map(|r| r.re).collect();
+        roots.sort_by(f64::total_cmp);
+        let sqrt5 = 5.0_f64.sqrt();
+        assert!((roots[0] - (1.0 - sqrt5) / 2.0).abs() < 1e-15);
+        assert!((roots[1] - (1.0 + sqrt5) / 2.0).abs() < 1e-15);
+
+        // トリボナッチ数列は実根1つと共役な複素根2つ
+        let form = LinearRecurrence::tribonacci().closed_form().unwrap();
+        let complex = form.roots.iter().filter(|r| r.im.abs() > 1e-9).count();
+        assert_eq!(complex, 2);
+    }
+
+    #[test]
+    fn test_closed_form_needs_distinct_roots() {
+        // a(n) = a(n-1): 値が増えなくても調べ終わる
+        let constant = LinearRecurrence::new("constant", vec![1], vec![7]).unwrap();
+        assert_eq!(constant.closed_form().unwrap().max_n(), 999);
+        // a(n) = 0: 根 0 が重複する
+        let zero = LinearRecurrence::new("zero", vec![0, 0], vec![1, 1]).unwrap();
+        assert!(zero.closed_form().is_none());
+        // a(n) = 2a(n-1): 根は 2 だけ
+        let powers = LinearRecurrence::new("powers", vec![2], vec![1]).unwrap();
+        assert_eq!(powers.closed_form().unwrap().term(100), Ok(1 << 100));
+    }
+
+    #[test]
+    fn test_negative_coefficients() {
+        // a(n) = 2a(n-1) - a(n-2): 初項の差ずつ増える等差数列（特性根 1 が重複する）
+        let linear: LinearRecurrence = "2,-1:5,3".parse().unwrap();
+        assert_eq!(strings(&linear.terms(5)).join(" "), "5 3 1 -1 -3");
+        assert_eq!(linear.term(1_000_000).to_string(), "-1999995");
+        assert!(linear.closed_form().is_none());
+
+        // a(n) = a(n-1) - a(n-2): 周期 6 で符号が入れ替わる
+        let periodic = LinearRecurrence::new("periodic", vec![1, -1], vec![0, 1]).unwrap();
+        assert_eq!(strings(&periodic.terms(8)).join(" "), "0 1 1 0 -1 -1 0 1");
+        assert_eq!(periodic.term(6 * 1_000_000_007 + 4), BigInt::from(-1_i64));
+
+        // a(n) = -2a(n-1): 負の実根は一般項でも求められる
+        let alternating = LinearRecurrence::new("alternating", vec![-2], vec![1]).unwrap();
+        let form = alternating.closed_form().unwrap();
+        assert_eq!(form.term(101), Ok(-(1_i128 << 101)));
+        assert_eq!(alternating.term(101).to_i128(), Some(-(1 << 101)));
+        // (-2)^127 = i128::MIN まで i128 に収まる
+        assert_eq!(form.max_n(), 127);
+    }
+
+    #[test]
+    fn test_from_str_and_display() {
+        assert_eq!("lucas".parse(), Ok(LinearRecurrence::lucas()));
+        let custom: LinearRecurrence = "2, 1:0, 1".parse().unwrap();
+        assert_eq!(custom.terms(5), LinearRecurrence::pell().terms(5));
+        assert_eq!(
+            LinearRecurrence::tribonacci().to_string(),
+            "a(n) = a(n-1) + a(n-2) + a(n-3)（初項 0, 0, 1）"
+        );
+        assert_eq!(
+            LinearRecurrence::pell().to_string(),
+            "a(n) = 2a(n-1) + a(n-2)（初項 0, 1）"
+        );
+        assert_eq!(
+            "-1,0,-3:1,-2,0"
+                .parse::<LinearRecurrence>()
+                .unwrap()
+                .to_string(),
+            "a(n) = -a(n-1) - 3a(n-3)（初項 1, -2, 0）"
+        );
+
+        assert_eq!(
+            "1,1:0".parse::<LinearRecurrence>(),
+            Err(RecurrenceError::LengthMismatch {
+                coefficients: 2,
+                initial: 1
+            })
+        );
+        assert_eq!(
+            "fib".parse::<LinearRecurrence>(),
+            Err(RecurrenceError::Parse("fib".to_string()))
+        );
+        assert_eq!(
+            LinearRecurrence::new("empty", vec![], vec![]),
+            Err(RecurrenceError::Empty)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! フィボナッチ数列のイテレータと関連する関数
+//!
+//! `Fibonacci<T>` は型 `T` に収まる間だけ F(0), F(1), … を返し、
+//! `BigFibonacci` は多倍長整数で F(`BIG_MAX_N`) まで返す。どちらも `nth` は
+//! 途中の項を順にたどらず、高速倍加法で O(log n) で飛ぶ。
+
+use crate::bigint::BigUint;
+use crate::checked::{self, FibInt};
+use crate::{fib_big, BIG_MAX_N};
+use std::iter::FusedIterator;
+
+/// 型 `T` に収まるフィボナッチ数を順に返すイテレータ
+///
+/// F(`max_n::<T>()`) を返した後は `None` を返し続ける
+///
+/// ```
+/// use functions::Fibonacci;
+///
+/// let small: Vec<u8> = Fibonacci::new().collect();
+/// assert_eq!(small.len(), 14);
+/// assert_eq!(Fibonacci::<u64>::new().nth(90), Some(2_880_067_194_370_816_120));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fibonacci<T> {
+    /// 次に返す項の番号
+    index: u64,
+    /// F(index)（`None` なら終わり）
+    current: Option<T>,
+    /// F(index + 1)（`T` に収まらなければ `None`）
+    next: Option<T>,
+    /// `T` に収まる最大の番号
+    max_n: u64,
+}
+
+impl<T: FibInt> Fibonacci<T> {
+    /// F(0) から始めるイテレータ
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            index: 0,
+            current: Some(T::ZERO),
+            next: Some(T::ONE),
+            max_n: checked::max_n::<T>(),
+        }
+    }
+}
+
+impl<T: FibInt> Default for Fibonacci<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: FibInt> Iterator for Fibonacci<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = self.current.take()?;
+        self.current = self.next;
+        self.next = self.next.and_then(|next| value.checked_add(next));
+        self.index += 1;
+        Some(value)
+    }
+
+    /// n 個先の項へ O(log n) で飛ぶ
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.current?;
+        let target = u64::try_from(n)
+            .ok()
+            .and_then(|n| self.index.checked_add(n))
+            .filter(|&target| target <= self.max_n);
+        let Some(target) = target else {
+            self.current = None;
+            self.next = None;
+            return None;
+        };
+        let to_t = |n| fib_big(n).to_u128().and_then(T::from_u128);
+        self.current = to_t(target);
+        self.next = to_t(target + 1);
+        self.index = target;
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.current {
+            Some(_) => usize::try_from(self.max_n - self.index + 1).unwrap_or(usize::MAX),
+            None => 0,
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: FibInt> ExactSizeIterator for Fibonacci<T> {}
+
+impl<T: FibInt> FusedIterator for Fibonacci<T> {}
+
+/// 多倍長整数でフィボナッチ数を返すイテレータ
+///
+/// 多倍長版の上限 F(`BIG_MAX_N`) を返した後は `None` を返し続ける
+#[derive(Debug, Clone)]
+pub struct BigFibonacci {
+    /// 次に返す項の番号
+    index: u64,
+    /// F(index)（`None` なら終わり）
+    current: Option<BigUint>,
+    /// F(index + 1)（`BIG_MAX_N` を超えるなら `None`）
+    next: Option<BigUint>,
+}
+
+impl BigFibonacci {
+    /// F(0) から始めるイテレータ
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            index: 0,
+            current: Some(BigUint::zero()),
+            next: Some(BigUint::one()),
+        }
+    }
+}
+
+impl Default for BigFibonacci {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for BigFibonacci {
+    type Item = BigUint;
+
+    fn next(&mut self) -> Option<BigUint> {
+        let value = self.current.take()?;
+        self.current = self.next.take();
+        self.index += 1;
+        if self.index < BIG_MAX_N {
+            self.next = self.current.as_ref().map(|current| &value + current);
+        }
+        Some(value)
+    }
+
+    /// n 個先の項へ O(log n) で飛ぶ
+    fn nth(&mut self, n: usize) -> Option<BigUint> {
+        self.current.as_ref()?;
+        let target = u64::try_from(n)
+            .ok()
+            .and_then(|n| self.index.checked_add(n))
+            .filter(|&target| target <= BIG_MAX_N);
+        let Some(target) = target else {
+            self.current = None;
+            self.next = None;
+            return None;
+        };
+        self.current = Some(fib_big(target));
+        self.next = (target < BIG_MAX_N).then(|| fib_big(target + 1));
+        self.index = target;
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.current {
+            Some(_) => usize::try_from(BIG_MAX_N - self.index + 1).unwrap_or(usize::MAX),
+            None => 0,
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl FusedIterator for BigFibonacci {}
+
+/// x = F(n) となる最小の n（x がフィボナッチ数でなければ `None`）
+///
+/// F(n) ≈ φⁿ / √5 から n を見積もり、前後の項と比べる
+#[must_use]
+pub fn index_of(x: u64) -> Option<u64> {
+    if x <= 1 {
+        return Some(x);
+    }
+    // 誤差は 1 未満なので前後 1 つずつ調べれば足りる
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::manual_midpoint
+    )]
+    let estimate = {
+        let phi = (1.0 + 5.0_f64.sqrt()) / 2.0;
+        ((x as f64 * 5.0_f64.sqrt()).ln() / phi.ln()).round() as u64
+    };
+    (estimate.saturating_sub(1)..=estimate + 1).find(|&n| {
+        usize::try_from(n)
+            .ok()
+            .and_then(|n| Fibonacci::<u64>::new().nth(n))
+            == Some(x)
+    })
+}
+
+/// x がフィボナッチ数かどうか
+#[must_use]
+pub fn is_fibonacci(x: u64) -> bool {
+    index_of(x).is_some()
+}
+
+/// ゼッケンドルフ表現: x を連続しないフィボナッチ数の和で表す（大きい順）
+///
+/// 表せる最大のフィボナッチ数を貪欲に引いていけば、表現は一意に定まる
+///
+/// ```
+/// assert_eq!(functions::sequence::zeckendorf(100), [89, 8, 3]);
+/// ```
+#[must_use]
+pub fn zeckendorf(mut x: u64) -> Vec<u64> {
+    // F(2) = 1 から（F(1) = 1 と重複させない）
+    let fibs: Vec<u64> = Fibonacci::<u64>::new()
+        .skip(2)
+        .take_while(|&f| f <= x)
+        .collect();
+    let mut terms = Vec::new();
+    for &f in fibs.iter().rev() {
+        if f <= x {
+            terms.push(f);
+            x -= f;
+        }
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_iterator_stops_at_width_limit() {
+        let values: Vec<u8> = Fibonacci::new().collect();
+        assert_eq!(values, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233]);
+
+        let mut iter = Fibonacci::<u64>::new();
+        assert_eq!(iter.len(), 94);
+        assert_eq!(iter.by_ref().last(), Some(12_200_160_415_121_876_738));
+        assert_eq!(iter.next(), None);
+        assert_eq!(Fibonacci::<u128>::new().count(), 187);
+    }
+
+    #[test]
+    fn test_checked_nth_jumps() {
+        let mut iter = Fibonacci::<u64>::new();
+        assert_eq!(iter.nth(10), Some(55));
+        assert_eq!(iter.next(), Some(89));
+        assert_eq!(iter.nth(80), Some(7_540_113_804_746_346_429));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next(), Some(12_200_160_415_121_876_738));
+        assert_eq!(iter.nth(1), None);
+
+        let mut iter = Fibonacci::<u32>::new();
+        assert_eq!(iter.nth(48), None);
+        assert_eq!(iter.next(), None);
+
+        // 足し算で順に求めた値と同じになる
+        for n in 0..=187 {
+            let expected = checked::fib::<u128>(n as u64);
+            assert_eq!(Fibonacci::<u128>::new().nth(n), expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_big_iterator() {
+        let first: Vec<String> = BigFibonacci::new().take(8).map(|f| f.to_string()).collect();
+        assert_eq!(first, ["0", "1", "1", "2", "3", "5", "8", "13"]);
+
+        let mut iter = BigFibonacci::new();
+        assert_eq!(iter.nth(100), Some(fib_big(100)));
+        assert_eq!(iter.next(), Some(fib_big(101)));
+        assert_eq!(iter.nth(10_000), Some(fib_big(10_102)));
+        for (n, f) in (0..300).zip(BigFibonacci::new()) {
+            assert_eq!(f, fib_big(n));
+        }
+
+        // 番号が桁あふれする・上限を超える飛び方は終わりになる
+        assert_eq!(iter.nth(usize::MAX), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        let mut iter = BigFibonacci::new();
+        assert_eq!(iter.size_hint().1, usize::try_from(BIG_MAX_N + 1).ok());
+        assert_eq!(iter.nth(usize::try_from(BIG_MAX_N + 1).unwrap()), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_index_of_and_is_fibonacci() {
+        for (n, f) in (0..).zip(Fibonacci::<u64>::new()) {
+            // F(1) = F(2) = 1 は小さい方の番号
+            let expected = if n == 2 { 1 } else { n };
+            assert_eq!(index_of(f), Some(expected), "F({n}) = {f}");
+            if f > 3 {
+                assert!(!is_fibonacci(f - 1) && !is_fibonacci(f + 1), "{f} ± 1");
+            }
+        }
+        assert!(!is_fibonacci(4));
+        assert!(!is_fibonacci(u64::MAX));
+    }
+
+    #[test]
+    fn test_zeckendorf() {
+        assert_eq!(zeckendorf(0), []);
+        assert_eq!(zeckendorf(1), [1]);
+        assert_eq!(zeckendorf(4), [3, 1]);
+        assert_eq!(zeckendorf(100), [89, 8, 3]);
+        for x in (0..10_000).chain([u64::MAX - 1, u64::MAX]) {
+            let terms = zeckendorf(x);
+            assert_eq!(
+                terms.iter().map(|&t| u128::from(t)).sum::<u128>(),
+                u128::from(x)
+            );
+            // 連続するフィボナッチ数を含まない
+            for pair in terms.windows(2) {
+                let (i, j) = (index_of(pair[0]).unwrap(), index_of(pair[1]).unwrap());
+                assert!(i >= j + 2, "{x}: {terms:?}");
+            }
+        }
+    }
+}
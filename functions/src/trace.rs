@@ -0,0 +1,334 @@
+//! 再帰版 fib の呼び出しの計測
+//!
+//! `fib` と同じ再帰で計算しながら、呼び出し回数・再帰の最大の深さを数え、
+//! 小さな n では呼び出しの木を記録してテキストや DOT (Graphviz) で出力する。
+//! メモ化すると同じ部分木が2度目からは1回の参照になり、木が縮むことが分かる。
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// u64 で計算できる最大の n
+pub const MAX_N: u32 = 93;
+
+/// メモ化なしで計測する最大の n（呼び出しは 2F(36) − 1 ≈ 3000 万回）
+pub const PLAIN_MAX_N: u32 = 35;
+
+/// 呼び出しの木を記録する最大のノード数（メモ化なしでは n = 15 まで）
+pub const MAX_TREE_NODES: usize = 2_000;
+
+/// 再帰の仕方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// `fib` と同じ素朴な再帰
+    Plain,
+    /// 計算済みの値を覚えておく再帰
+    Memoized,
+}
+
+impl Strategy {
+    /// 名前
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Plain => "plain",
+            Strategy::Memoized => "memoized",
+        }
+    }
+}
+
+/// 計測結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trace {
+    /// F(n)
+    pub value: u64,
+    /// 関数の呼び出し回数（最初の呼び出しを含む）
+    pub calls: u64,
+    /// 再帰の最大の深さ（最初の呼び出しが 1）
+    pub max_depth: u32,
+    /// メモから値を返した回数
+    pub cache_hits: u64,
+}
+
+/// 呼び出しの木のノード（1回の呼び出し）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallNode {
+    /// 引数
+    pub n: u32,
+    /// 返した値
+    pub value: u64,
+    /// 深さ（根が 1）
+    pub depth: u32,
+    /// 呼び出し元のノードの番号（根は `None`）
+    pub parent: Option<usize>,
+    /// メモから値を返したか
+    pub cached: bool,
+}
+
+/// 呼び出しの木（ノードは呼び出された順）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallTree {
+    nodes: Vec<CallNode>,
+}
+
+/// 再帰しながら数え、必要ならノードを記録する
+struct Recorder {
+    strategy: Strategy,
+    memo: HashMap<u32, u64>,
+    trace: Trace,
+    nodes: Option<Vec<CallNode>>,
+}
+
+impl Recorder {
+    fn new(strategy: Strategy, record: bool) -> Self {
+        Self {
+            strategy,
+            memo: HashMap::new(),
+            trace: Trace {
+                value: 0,
+                calls: 0,
+                max_depth: 0,
+                cache_hits: 0,
+            },
+            nodes: record.then(Vec::new),
+        }
+    }
+
+    /// ノード数が上限を超えたら記録をやめて `None` にする
+    fn push(&mut self, node: CallNode) -> Option<usize> {
+        let nodes = self.nodes.as_mut()?;
+        if nodes.len() >= MAX_TREE_NODES {
+            self.nodes = None;
+            return None;
+        }
+        nodes.push(node);
+        Some(nodes.len() - 1)
+    }
+
+    fn fib(&mut self, n: u32, depth: u32, parent: Option<usize>) -> u64 {
+        self.trace.calls += 1;
+        self.trace.max_depth = self.trace.max_depth.max(depth);
+
+        if let Some(&value) = self.memo.get(&n) {
+            self.trace.cache_hits += 1;
+            self.push(CallNode {
+                n,
+                value,
+                depth,
+                parent,
+                cached: true,
+            });
+            return value;
+        }
+
+        let index = self.push(CallNode {
+            n,
+            value: 0,
+            depth,
+            parent,
+            cached: false,
+        });
+        let value = match n {
+            0 | 1 => u64::from(n),
+            _ => self.fib(n - 1, depth + 1, index) + self.fib(n - 2, depth + 1, index),
+        };
+        if let (Some(nodes), Some(index)) = (self.nodes.as_mut(), index) {
+            nodes[index].value = value;
+        }
+        if self.strategy == Strategy::Memoized && n >= 2 {
+            self.memo.insert(n, value);
+        }
+        value
+    }
+}
+
+/// F(n) を再帰で計算し、呼び出し回数と深さを数える
+///
+/// メモ化なしの呼び出し回数は 2F(n+1) − 1 回なので、n が 40 を超えると時間がかかる
+///
+/// # Panics
+/// n が `MAX_N` を超える場合（F(n) が u64 に収まらない）
+///
+/// ```
+/// use functions::trace::{trace, Strategy};
+///
+/// assert_eq!(trace(20, Strategy::Plain).calls, 21_891);
+/// assert_eq!(trace(20, Strategy::Memoized).calls, 39);
+/// ```
+#[must_use]
+pub fn trace(n: u32, strategy: Strategy) -> Trace {
+    assert!(n <= MAX_N, "n = {n} は u64 に収まりません（n ≤ {MAX_N}）");
+    let mut recorder = Recorder::new(strategy, false);
+    recorder.trace.value = recorder.fib(n, 1, None);
+    recorder.trace
+}
+
+/// F(n) の呼び出しの木を記録する
+///
+/// ノード数が `MAX_TREE_NODES` を超える場合は `None` を返す
+///
+/// # Panics
+/// n が `MAX_N` を超える場合
+#[must_use]
+pub fn call_tree(n: u32, strategy: Strategy) -> Option<CallTree> {
+    assert!(n <= MAX_N, "n = {n} は u64 に収まりません（n ≤ {MAX_N}）");
+    // メモ化なしの木は 2F(n+1) − 1 ノードなので、明らかに大きい n は計算しない
+    if strategy == Strategy::Plain && n > 25 {
+        return None;
+    }
+    let mut recorder = Recorder::new(strategy, true);
+    recorder.fib(n, 1, None);
+    recorder.nodes.map(|nodes| CallTree { nodes })
+}
+
+impl CallTree {
+    /// ノード（呼び出された順）
+    #[must_use]
+    pub fn nodes(&self) -> &[CallNode] {
+        &self.nodes
+    }
+
+    fn label(node: &CallNode) -> String {
+        let memo = if node.cached { " (memo)" } else { "" };
+        format!("fib({}) = {}{memo}", node.n, node.value)
+    }
+
+    fn children(&self) -> Vec<Vec<usize>> {
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(parent) = node.parent {
+                children[parent].push(i);
+            }
+        }
+        children
+    }
+
+    /// 罫線で描いた木
+    ///
+    /// ```text
+    /// fib(3) = 2
+    /// ├── fib(2) = 1
+    /// │   ├── fib(1) = 1
+    /// │   └── fib(0) = 0
+    /// └── fib(1) = 1
+    /// ```
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        fn walk(
+            tree: &CallTree,
+            children: &[Vec<usize>],
+            i: usize,
+            prefix: &str,
+            out: &mut String,
+        ) {
+            let kids = &children[i];
+            for (k, &child) in kids.iter().enumerate() {
+                let last = k + 1 == kids.len();
+                let (branch, indent) = if last {
+                    ("└── ", "    ")
+                } else {
+                    ("├── ", "│   ")
+                };
+                let _ = writeln!(
+                    out,
+                    "{prefix}{branch}{}",
+                    CallTree::label(&tree.nodes[child])
+                );
+                walk(tree, children, child, &format!("{prefix}{indent}"), out);
+            }
+        }
+
+        let mut out = String::new();
+        if let Some(root) = self.nodes.first() {
+            let _ = writeln!(out, "{}", Self::label(root));
+            walk(self, &self.children(), 0, "", &mut out);
+        }
+        out
+    }
+
+    /// Graphviz の DOT 形式（メモから返した呼び出しは破線）
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph fib {\n    node [shape=box];\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let style = if node.cached { ", style=dashed" } else { "" };
+            let _ = writeln!(out, "    n{i} [label=\"{}\"{style}];", Self::label(node));
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(parent) = node.parent {
+                let _ = writeln!(out, "    n{parent} -> n{i};");
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fib, fib_big};
+
+    #[test]
+    fn test_plain_counts_match_formula() {
+        for n in 0..=20 {
+            let t = trace(n, Strategy::Plain);
+            assert_eq!(u64::from(fib(n)), t.value);
+            // 呼び出し回数は 2F(n+1) − 1、深さは n（n = 0 でも 1）
+            assert_eq!(t.calls, 2 * u64::from(fib(n + 1)) - 1, "n = {n}");
+            assert_eq!(t.max_depth, n.max(1));
+            assert_eq!(t.cache_hits, 0);
+        }
+    }
+
+    #[test]
+    fn test_memoized_counts_are_linear() {
+        for n in 2..=MAX_N {
+            let t = trace(n, Strategy::Memoized);
+            assert_eq!(t.value.to_string(), fib_big(u64::from(n)).to_string());
+            assert_eq!(t.calls, 2 * u64::from(n) - 1, "n = {n}");
+            assert_eq!(t.max_depth, n);
+            assert_eq!(t.cache_hits, u64::from(n.saturating_sub(3)));
+        }
+    }
+
+    #[test]
+    fn test_call_tree_text() {
+        let tree = call_tree(3, Strategy::Plain).unwrap();
+        assert_eq!(
+            tree.to_text(),
+            "\
+fib(3) = 2
+├── fib(2) = 1
+│   ├── fib(1) = 1
+│   └── fib(0) = 0
+└── fib(1) = 1
+"
+        );
+
+        let tree = call_tree(5, Strategy::Memoized).unwrap();
+        assert_eq!(tree.nodes().len(), 9);
+        assert!(tree.to_text().ends_with("└── fib(3) = 2 (memo)\n"));
+    }
+
+    #[test]
+    fn test_call_tree_dot() {
+        let dot = call_tree(4, Strategy::Memoized).unwrap().to_dot();
+        assert!(dot.starts_with("digraph fib {\n"));
+        assert!(dot.contains("    n0 [label=\"fib(4) = 3\"];\n"));
+        assert!(dot.contains("[label=\"fib(2) = 1 (memo)\", style=dashed];"));
+        assert!(dot.contains("    n0 -> n1;\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_call_tree_size_limit() {
+        // 2F(16) − 1 = 1973 ノードまでは記録できる
+        assert_eq!(call_tree(15, Strategy::Plain).unwrap().nodes().len(), 1_973);
+        assert!(call_tree(16, Strategy::Plain).is_none());
+        assert!(call_tree(40, Strategy::Plain).is_none());
+        assert_eq!(
+            call_tree(MAX_N, Strategy::Memoized).unwrap().nodes().len(),
+            185
+        );
+    }
+}
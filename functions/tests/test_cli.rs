@@ -0,0 +1,145 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Output, Stdio};
+
+/// `fib` コマンドを引数つきで実行する
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fib"))
+        .args(args)
+        .output()
+        .expect("fib を実行できません")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).expect("標準出力は UTF-8")
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).expect("標準エラー出力は UTF-8")
+}
+
+// =============================================================================
+// 出力テスト
+// =============================================================================
+
+#[test]
+fn test_nth_prints_big_value() {
+    let output = run(&["nth", "100"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("354224848179261915075"));
+}
+
+#[test]
+fn test_range_csv() {
+    let output = run(&["range", "0..5", "--format", "csv"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "n,value\n0,0\n1,1\n2,1\n3,2\n4,3\n");
+
+    // 最初の2項の後は足し算で求める
+    let output = run(&["range", "100..=102", "--format", "csv", "--algo", "matrix"]);
+    assert_eq!(
+        stdout(&output),
+        "n,value\n\
+         100,354224848179261915075\n\
+         101,573147844013817084101\n\
+         102,927372692193078999176\n"
+    );
+}
+
+#[test]
+fn test_range_streams_until_pipe_closes() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fib"))
+        .args(["range", "0..100000", "--format", "csv"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("fib を実行できません");
+    let mut lines = BufReader::new(child.stdout.take().expect("標準出力")).lines();
+    assert_eq!(lines.next().unwrap().unwrap(), "n,value");
+    assert_eq!(lines.next().unwrap().unwrap(), "0,0");
+    // 読む側が閉じたら、残りを計算せずに正常終了する
+    drop(lines);
+    assert!(child.wait().expect("fib の終了を待てません").success());
+}
+
+#[test]
+fn test_mod_json() {
+    let output = run(&[
+        "mod",
+        "123456789012345678901234567890",
+        "1000000007",
+        "--format",
+        "json",
+    ]);
+    assert_eq!(
+        stdout(&output),
+        "[\n  {\"n\": \"123456789012345678901234567890\", \"m\": 1000000007, \"value\": 244030532}\n]\n"
+    );
+}
+
+#[test]
+fn test_trace_tree() {
+    let output = run(&["trace", "3", "--tree"]);
+    assert_eq!(
+        stdout(&output),
+        "\
+fib(3) = 2
+├── fib(2) = 1
+│   ├── fib(1) = 1
+│   └── fib(0) = 0
+└── fib(1) = 1
+"
+    );
+}
+
+#[test]
+fn test_help() {
+    let output = run(&["--help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("使い方: fib"));
+}
+
+// =============================================================================
+// 終了コードテスト
+// =============================================================================
+
+#[test]
+fn test_out_of_range_exits_with_1() {
+    let output = run(&["nth", "48", "--algo", "recursive"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).starts_with("Error: n = 48 は recursive の上限を超えています"));
+
+    let output = run(&["nth", "100001", "--algo", "iterative", "--digits"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: n = 100001 は iterative の上限を超えています"));
+}
+
+#[test]
+fn test_too_large_n_exits_with_2() {
+    let output = run(&["nth", "18446744073709551615"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output)
+        .starts_with("Error: <n> が大きすぎます: 18446744073709551615（10000000 まで）"));
+
+    let output = run(&["range", "0..10000000"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output)
+        .starts_with("Error: <a>..<b> の項数 が大きすぎます: 10000000（100000 まで）"));
+}
+
+#[test]
+fn test_invalid_arguments_exit_with_2() {
+    for args in [
+        &["nth"][..],
+        &["nth", "10", "--algo", "bogo"],
+        &["bench", "--digits"],
+        &["unknown"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        let message = stderr(&output);
+        assert!(message.starts_with("Error: "), "{args:?}");
+        assert!(message.contains("使い方: fib"), "{args:?}");
+    }
+}
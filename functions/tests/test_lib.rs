@@ -0,0 +1,234 @@
+use functions::algorithms::{all_algorithms, find_algorithm, Complexity};
+use functions::bigint::BigInt;
+use functions::cli::{self, Command, Format};
+use functions::recurrence::LinearRecurrence;
+use functions::{
+    binet, checked, fib, fib_big, fib_binet, lookup, modular, parallel, sequence, trace, BigUint,
+    Fibonacci,
+};
+
+// =============================================================================
+// fib テスト
+// =============================================================================
+
+#[test]
+fn test_fib_base_cases() {
+    assert_eq!(fib(0), 0);
+    assert_eq!(fib(1), 1);
+}
+
+#[test]
+fn test_fib_known_values() {
+    assert_eq!(fib(2), 1);
+    assert_eq!(fib(3), 2);
+    assert_eq!(fib(4), 3);
+    assert_eq!(fib(5), 5);
+    assert_eq!(fib(10), 55);
+    assert_eq!(fib(20), 6765);
+}
+
+// =============================================================================
+// fib_binet テスト
+// =============================================================================
+
+#[test]
+fn test_fib_binet_base_cases() {
+    assert_eq!(fib_binet(0), Ok(0));
+    assert_eq!(fib_binet(1), Ok(1));
+}
+
+#[test]
+fn test_fib_binet_known_values() {
+    assert_eq!(fib_binet(2), Ok(1));
+    assert_eq!(fib_binet(3), Ok(2));
+    assert_eq!(fib_binet(4), Ok(3));
+    assert_eq!(fib_binet(5), Ok(5));
+    assert_eq!(fib_binet(10), Ok(55));
+    assert_eq!(fib_binet(20), Ok(6765));
+}
+
+// =============================================================================
+// fib と fib_binet の一致テスト
+// =============================================================================
+
+#[test]
+fn test_fib_and_fib_binet_match() {
+    for n in 0..25 {
+        assert_eq!(
+            Ok(u64::from(fib(n))),
+            fib_binet(n),
+            "fib({n}) と fib_binet({n}) が一致しない"
+        );
+    }
+}
+
+// =============================================================================
+// fib_big テスト
+// =============================================================================
+
+#[test]
+fn test_fib_big_matches_fib() {
+    for n in 0..25 {
+        assert_eq!(fib_big(u64::from(n)).to_string(), fib(n).to_string());
+    }
+}
+
+#[test]
+fn test_fib_big_fast_path_boundary() {
+    assert_eq!(fib_big(93).to_string(), "12200160415121876738");
+    assert_eq!(fib_big(94).to_string(), "19740274219868223167");
+    assert_eq!(fib_big(100).to_string(), "354224848179261915075");
+    // 表を引く範囲（n ≤ 186）と高速倍加法の境目
+    assert_eq!(
+        fib_big(186).to_string(),
+        "332825110087067562321196029789634457848"
+    );
+    assert_eq!(
+        fib_big(187).to_string(),
+        "538522340430300790495419781092981030533"
+    );
+}
+
+#[test]
+fn test_fib_big_matches_addition() {
+    // 掛け算を使わない足し算だけの計算と比べる
+    let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+    for n in 0..=2_000 {
+        assert_eq!(fib_big(n), a, "fib_big({n})");
+        (a, b) = (b.clone(), &a + &b);
+    }
+}
+
+#[test]
+fn test_fib_big_large_n() {
+    let f = fib_big(1_000);
+    assert_eq!(f.digit_count(), 209);
+    assert!(f.to_string().starts_with("434665576869374564356885276750406258025646605173717804024817290895365554179490518904038798400792551692959225930803226347752096896232398733224711616429964409065331879382989696499285160037044761377951668492288"));
+    assert!(f.to_string().ends_with("849228875"));
+
+    assert_eq!(fib_big(100_000).digit_count(), 20_899);
+}
+
+#[test]
+fn test_binet_large_n() {
+    println!("\n=== ビネの公式の大きな n ===");
+    for n in [50, 75, 76, 100, 1000] {
+        let result = fib_binet(n);
+        match result {
+            Ok(value) => println!("fib_binet({n:4}) = {value:20}"),
+            Err(e) => println!("fib_binet({n:4}) → {e}"),
+        }
+        // 精度の範囲外は値ではなくエラーになる
+        assert_eq!(result.is_ok(), n <= binet::F64_MAX_N);
+    }
+}
+
+// =============================================================================
+// モジュールをまたぐテスト（公開 API だけを使う）
+// =============================================================================
+
+#[test]
+fn test_all_algorithms_agree_with_fib_big() {
+    for algo in all_algorithms(2) {
+        let limit = match algo.complexity() {
+            Complexity::Exponential => 25,
+            _ => 300,
+        };
+        for n in 0..=algo.max_n().min(limit) {
+            assert_eq!(algo.fib(n), Some(fib_big(n)), "{}({n})", algo.name());
+        }
+        assert_eq!(
+            find_algorithm(algo.name(), 1).map(|a| a.name()),
+            Some(algo.name())
+        );
+    }
+}
+
+#[test]
+fn test_cassini_identity() {
+    // F(n-1)F(n+1) - F(n)² = (-1)^n を多倍長整数で確かめる
+    for n in [1_u64, 2, 10, 187, 1_000, 12_345] {
+        let (prev, cur, next) = (fib_big(n - 1), fib_big(n), fib_big(n + 1));
+        let outer = &prev * &next;
+        let square = &cur * &cur;
+        if n % 2 == 0 {
+            assert_eq!(&outer - &square, BigUint::one(), "n = {n}");
+        } else {
+            assert_eq!(&square - &outer, BigUint::one(), "n = {n}");
+        }
+    }
+}
+
+#[test]
+fn test_modular_agrees_with_big_values() {
+    for m in [2, 10, 1_000, 1_000_000_007] {
+        for n in [0_u64, 1, 50, 999, 10_000] {
+            assert_eq!(
+                modular::fib_mod(u128::from(n), m),
+                fib_big(n).rem_u64(m),
+                "F({n}) mod {m}"
+            );
+        }
+    }
+    // ピサノ周期ごとに同じ値に戻る
+    for m in [10_u64, 1_000, 1_000_000_007] {
+        let period = modular::pisano_period(m);
+        for n in [0_u128, 7, 12_345] {
+            assert_eq!(modular::fib_mod(n + period, m), modular::fib_mod(n, m));
+        }
+    }
+}
+
+#[test]
+fn test_iterator_tables_and_checked_agree() {
+    let values: Vec<u64> = Fibonacci::new().collect();
+    assert_eq!(values, lookup::FIB_TABLE);
+    for (n, &value) in (0_u64..).zip(&values) {
+        assert_eq!(checked::fib::<u64>(n), Some(value));
+        assert_eq!(sequence::index_of(value), Some(if n == 2 { 1 } else { n }));
+    }
+    assert_eq!(checked::fib::<u64>(94), None);
+}
+
+#[test]
+fn test_recurrence_presets_match_fib_big() {
+    let fibonacci = LinearRecurrence::fibonacci();
+    let lucas: LinearRecurrence = "lucas".parse().unwrap();
+    for n in [1_u64, 10, 100, 1_000] {
+        assert_eq!(fibonacci.term(n), BigInt::from(fib_big(n)));
+        // L(n) = F(n-1) + F(n+1)
+        assert_eq!(
+            lucas.term(n),
+            BigInt::from(&fib_big(n - 1) + &fib_big(n + 1))
+        );
+    }
+}
+
+#[test]
+fn test_parallel_and_trace_agree_with_fib() {
+    assert_eq!(parallel::fib_big_parallel(200_000, 4), fib_big(200_000));
+    assert_eq!(parallel::fib_recursive(32, 4), fib(32));
+
+    let plain = trace::trace(25, trace::Strategy::Plain);
+    assert_eq!(plain.value, u64::from(fib(25)));
+    assert_eq!(plain.calls, 2 * u64::from(fib(26)) - 1);
+}
+
+#[test]
+fn test_cli_parse_args() {
+    let args = [
+        "compare",
+        "70..80",
+        "--algos",
+        "binet,binet-dd",
+        "--format",
+        "json",
+    ];
+    let options = cli::parse_args(args.iter().map(ToString::to_string))
+        .unwrap()
+        .unwrap();
+    assert_eq!(options.command, Command::Compare(70..=79));
+    assert_eq!(options.algorithms, ["binet", "binet-dd"]);
+    assert_eq!(options.format, Format::Json);
+    assert_eq!(cli::parse_args(["--help".to_string()]), Ok(None));
+}
@@ -0,0 +1,392 @@
+//! 数字当て（Bulls and Cows / ヒット・アンド・ブロー）モード
+//!
+//! 秘密の答えは「同じ数字を含まない4桁の数字」。予想に対して
+//! 位置も数字も合っている個数（ブル）と、数字だけ合っている個数（カウ）を返す。
+
+use crate::input_loop;
+use crate::messages::{Message, Style};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// コードの桁数
+pub const CODE_LENGTH: usize = 4;
+
+/// 使用できる数字の種類（0〜9）
+const DIGIT_COUNT: u8 = 10;
+
+/// 桁数と数字の種類から決まるコードの総数（10 × 9 × 8 × 7）
+pub const CODE_COUNT: usize = 5040;
+
+/// 同じ数字を含まない4桁のコード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Code {
+    digits: [u8; CODE_LENGTH],
+    /// 使用している数字のビットマスク（カウの高速計算用）
+    mask: u16,
+}
+
+impl Code {
+    /// 各桁の数字からコードを作成する
+    ///
+    /// 桁が 0〜9 の範囲外、または同じ数字が含まれる場合は `None` を返す
+    #[must_use]
+    pub fn new(digits: [u8; CODE_LENGTH]) -> Option<Self> {
+        let mut mask = 0_u16;
+        for &d in &digits {
+            if d >= DIGIT_COUNT || mask & (1 << d) != 0 {
+                return None;
+            }
+            mask |= 1 << d;
+        }
+        Some(Self { digits, mask })
+    }
+
+    /// 入力文字列をコードに変換する
+    ///
+    /// 前後の空白は無視する。4桁ちょうどで同じ数字を含まない場合のみ `Some` を返す
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.len() != CODE_LENGTH {
+            return None;
+        }
+
+        let mut digits = [0; CODE_LENGTH];
+        for (slot, c) in digits.iter_mut().zip(input.chars()) {
+            *slot = u8::try_from(c.to_digit(10)?).ok()?;
+        }
+        Self::new(digits)
+    }
+
+    /// ランダムなコードを生成する
+    pub fn random<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        let mut pool: Vec<u8> = (0..DIGIT_COUNT).collect();
+        pool.shuffle(rng);
+        let mut digits = [0; CODE_LENGTH];
+        let mut mask = 0_u16;
+        for (slot, &d) in digits.iter_mut().zip(&pool) {
+            *slot = d;
+            mask |= 1 << d;
+        }
+        Self { digits, mask }
+    }
+
+    /// 各桁の数字
+    #[must_use]
+    pub fn digits(&self) -> [u8; CODE_LENGTH] {
+        self.digits
+    }
+
+    /// `guess` をこのコードと比較した結果を返す
+    #[must_use]
+    pub fn score(&self, guess: &Code) -> Score {
+        let bulls = self
+            .digits
+            .iter()
+            .zip(&guess.digits)
+            .filter(|(a, b)| a == b)
+            .count();
+        let common = (self.mask & guess.mask).count_ones() as usize;
+        Score {
+            bulls,
+            cows: common - bulls,
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for d in self.digits {
+            write!(f, "{d}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 予想の判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score {
+    /// 位置も数字も合っている個数
+    pub bulls: usize,
+    /// 数字は合っているが位置が違う個数
+    pub cows: usize,
+}
+
+impl Score {
+    /// 正解かどうか
+    #[must_use]
+    pub fn is_correct(&self) -> bool {
+        self.bulls == CODE_LENGTH
+    }
+
+    /// 判定結果ごとに振り分けるためのインデックス（0〜24）
+    fn index(self) -> usize {
+        self.bulls * (CODE_LENGTH + 1) + self.cows
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}ブル {}カウ", self.bulls, self.cows)
+    }
+}
+
+/// あり得るすべてのコードを昇順で返す
+#[must_use]
+pub fn all_codes() -> Vec<Code> {
+    let mut codes = Vec::with_capacity(CODE_COUNT);
+    for a in 0..DIGIT_COUNT {
+        for b in 0..DIGIT_COUNT {
+            for c in 0..DIGIT_COUNT {
+                for d in 0..DIGIT_COUNT {
+                    if let Some(code) = Code::new([a, b, c, d]) {
+                        codes.push(code);
+                    }
+                }
+            }
+        }
+    }
+    codes
+}
+
+/// ランダムなコードで数字当てゲームを実行する
+///
+/// # Errors
+/// 入出力操作が失敗した場合に `io::Error` を返す
+#[cfg(feature = "cli")]
+pub fn run_game_internal<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    style: Style,
+) -> io::Result<()> {
+    let secret = Code::random(&mut rand::rng());
+    run_game_with_code(reader, writer, secret, style)
+}
+
+/// 秘密のコードを指定して数字当てゲームを実行する
+///
+/// 入力ループと共通メッセージは `run_game_with_style` と共有している。
+///
+/// # Errors
+/// 入出力操作が失敗した場合に `io::Error` を返す
+pub fn run_game_with_code<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    secret: Code,
+    style: Style,
+) -> io::Result<()> {
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] 秘密のコードは {secret} やで！");
+
+    let say = |writer: &mut W, message: Message| writeln!(writer, "{}", style.render(&message));
+
+    say(writer, Message::CodeGameStart)?;
+    say(writer, Message::Prompt)?;
+
+    input_loop(reader, writer, |input, writer| {
+        let Some(guess) = Code::parse(input) else {
+            say(writer, Message::InvalidCode)?;
+            return Ok(false);
+        };
+
+        say(writer, Message::CodeGuessed(guess))?;
+        let score = secret.score(&guess);
+        if score.is_correct() {
+            say(writer, Message::Correct)?;
+            return Ok(true);
+        }
+        say(writer, Message::CodeScore(score))?;
+        Ok(false)
+    })
+}
+
+/// 最悪手数を最小化する（ミニマックス）ソルバー
+///
+/// 各手で「最も大きく残る候補グループ」が最小になる予想を選ぶ。
+/// 候補に残っていないコードも予想に使うことで、すべての答えを
+/// 最悪 7 手で当てる（この問題の最適な最悪手数）。
+#[derive(Debug, Clone)]
+pub struct Solver {
+    all: Vec<Code>,
+    candidates: Vec<Code>,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    /// 全コードを候補とするソルバーを作成する
+    #[must_use]
+    pub fn new() -> Self {
+        let all = all_codes();
+        Self {
+            candidates: all.clone(),
+            all,
+        }
+    }
+
+    /// まだ答えの可能性がある候補
+    #[must_use]
+    pub fn candidates(&self) -> &[Code] {
+        &self.candidates
+    }
+
+    /// 次に予想すべきコードを返す
+    ///
+    /// 候補が尽きている（判定結果に矛盾がある）場合は `None` を返す
+    #[must_use]
+    pub fn next_guess(&self) -> Option<Code> {
+        best_guess(&self.all, &self.candidates)
+    }
+
+    /// 予想と判定結果から候補を絞り込む
+    pub fn update(&mut self, guess: &Code, score: Score) {
+        self.candidates.retain(|c| c.score(guess) == score);
+    }
+
+    /// `secret` を当てるまでの予想と判定結果の履歴を返す
+    #[must_use]
+    pub fn solve(mut self, secret: &Code) -> Vec<(Code, Score)> {
+        let mut history = Vec::new();
+        while let Some(guess) = self.next_guess() {
+            let score = secret.score(&guess);
+            history.push((guess, score));
+            if score.is_correct() {
+                break;
+            }
+            self.update(&guess, score);
+        }
+        history
+    }
+}
+
+/// ソルバーの判断を事前にすべて展開した決定木
+///
+/// 全 5040 通りの答えに対する手順をまとめて計算するときは、
+/// 毎回 `Solver` で探索し直すよりもこちらの方がはるかに速い。
+#[derive(Debug, Clone)]
+pub struct Strategy {
+    guess: Code,
+    branches: BTreeMap<Score, Strategy>,
+}
+
+impl Strategy {
+    /// 全コードを候補として決定木を構築する
+    ///
+    /// # Panics
+    /// 候補が空の場合のみパニックするが、全コードから始めるため実際には起こらない
+    #[must_use]
+    pub fn build() -> Self {
+        let all = all_codes();
+        Self::build_from(&all, &all).expect("候補が空になることはない")
+    }
+
+    fn build_from(all: &[Code], candidates: &[Code]) -> Option<Self> {
+        let guess = best_guess(all, candidates)?;
+
+        let mut groups: BTreeMap<Score, Vec<Code>> = BTreeMap::new();
+        for candidate in candidates {
+            let score = candidate.score(&guess);
+            if !score.is_correct() {
+                groups.entry(score).or_default().push(*candidate);
+            }
+        }
+
+        let branches = groups
+            .into_iter()
+            .filter_map(|(score, group)| Some((score, Self::build_from(all, &group)?)))
+            .collect();
+        Some(Self { guess, branches })
+    }
+
+    /// 最初に予想するコード
+    #[must_use]
+    pub fn first_guess(&self) -> Code {
+        self.guess
+    }
+
+    /// 最悪の場合に必要な手数
+    #[must_use]
+    pub fn worst_case(&self) -> usize {
+        1 + self
+            .branches
+            .values()
+            .map(Strategy::worst_case)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// `secret` を当てるまでの予想と判定結果の履歴を返す
+    #[must_use]
+    pub fn solve(&self, secret: &Code) -> Vec<(Code, Score)> {
+        let mut history = Vec::new();
+        let mut node = self;
+        loop {
+            let score = secret.score(&node.guess);
+            history.push((node.guess, score));
+            match node.branches.get(&score) {
+                Some(next) if !score.is_correct() => node = next,
+                _ => break,
+            }
+        }
+        history
+    }
+}
+
+/// 候補の中で最悪の残り候補数が最小になる予想を選ぶ
+fn best_guess(all: &[Code], candidates: &[Code]) -> Option<Code> {
+    match candidates.len() {
+        0 => None,
+        1 | 2 => Some(candidates[0]),
+        // 初手はどのコードも対称なので計算を省略する
+        CODE_COUNT => all.first().copied(),
+        _ => all
+            .iter()
+            .map(|guess| {
+                let worst = worst_partition(candidates, *guess);
+                // 同じ最悪値なら、正解の可能性がある候補を優先する
+                // （`all` から順に絞り込んだ候補は昇順に並んでいる）
+                let not_candidate = candidates.binary_search(guess).is_err();
+                ((worst, not_candidate), *guess)
+            })
+            .min_by_key(|&(key, _)| key)
+            .map(|(_, guess)| guess),
+    }
+}
+
+/// `guess` を予想したときに残る候補グループの最大サイズ
+fn worst_partition(candidates: &[Code], guess: Code) -> usize {
+    let mut counts = [0_usize; (CODE_LENGTH + 1) * (CODE_LENGTH + 1)];
+    for candidate in candidates {
+        counts[candidate.score(&guess).index()] += 1;
+    }
+    counts.into_iter().max().unwrap_or(0)
+}
+
+/// ソルバーが `secret` を解く過程を出力する
+///
+/// # Errors
+/// 書き込みに失敗した場合に `io::Error` を返す
+pub fn run_solver<W: Write>(writer: &mut W, secret: &Code, style: Style) -> io::Result<()> {
+    writeln!(writer, "{}", style.render(&Message::SolverStart(*secret)))?;
+    let history = Solver::new().solve(secret);
+    for (turn, &(guess, score)) in history.iter().enumerate() {
+        let step = Message::SolverStep {
+            turn: turn + 1,
+            guess,
+            score,
+        };
+        writeln!(writer, "{}", style.render(&step))?;
+    }
+    writeln!(
+        writer,
+        "{}",
+        style.render(&Message::SolverDone(history.len()))
+    )
+}
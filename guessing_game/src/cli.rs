@@ -0,0 +1,205 @@
+//! コマンドライン引数の解析
+
+use crate::{liar, Style};
+use std::fmt;
+use std::path::PathBuf;
+
+/// ゲームモード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// 1〜100 の数当て（通常モード）
+    #[default]
+    Number,
+    /// 同じ数字を含まない4桁のコードを当てる数字当て
+    BullsAndCows,
+    /// ヒントが最大 K 回ウソになる数当て
+    Liar,
+}
+
+/// コマンドラインオプション
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// 実行するゲームモード
+    pub mode: Mode,
+    /// 人間の代わりにソルバーに解かせる
+    pub solve: bool,
+    /// ウソつきヒントモードでのウソの上限回数
+    pub lies: u32,
+    /// 入出力を記録するファイル
+    pub record: Option<PathBuf>,
+    /// 再生する記録ファイル
+    pub replay: Option<PathBuf>,
+    /// メッセージの文体
+    pub style: Style,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            solve: false,
+            lies: DEFAULT_LIES,
+            record: None,
+            replay: None,
+            style: Style::default(),
+        }
+    }
+}
+
+/// ウソつきヒントモードでのウソの上限回数の既定値
+pub const DEFAULT_LIES: u32 = 1;
+
+/// 引数解析のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// 値が必要なオプションに値がない
+    MissingValue(&'static str),
+    /// オプションの値が不正
+    InvalidValue(&'static str, String),
+    /// 不明なモード名
+    UnknownMode(String),
+    /// 同時に指定できないオプション
+    Conflict(&'static str, &'static str),
+    /// 不明なオプション
+    UnknownOption(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::MissingValue(option) => write!(f, "{option} には値が必要です"),
+            ArgError::InvalidValue(option, value) => {
+                write!(f, "{option} の値が不正です: {value}")
+            }
+            ArgError::UnknownMode(mode) => {
+                write!(
+                    f,
+                    "不明なモードです: {mode}（number, bulls, liar のいずれか）"
+                )
+            }
+            ArgError::Conflict(a, b) => write!(f, "{a} と {b} は同時に指定できません"),
+            ArgError::UnknownOption(option) => write!(f, "不明なオプションです: {option}"),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// 使い方の説明
+pub const USAGE: &str = "\
+使い方: guessing_game [オプション]
+
+オプション:
+  --mode <number|bulls|liar>  ゲームモード（既定: number）
+  --lies <K>                  liar モードでのウソの上限回数（0〜10、既定: 1）
+  --solve                     ソルバーに解かせる（bulls / liar モード）
+  --record <file>             入出力をタイムスタンプ付きで記録する（number モードのみ）
+  --replay <file>             記録を再生し、出力の差分を表示する
+  --accessible                スクリーンリーダー向けの短いメッセージで表示する
+  --bell                      正解時にベルを鳴らす（--accessible と併用）
+  --plain                     ゲーム中のメッセージを ASCII のみで表示する
+  -h, --help                  このヘルプを表示";
+
+/// コマンドライン引数（プログラム名を除く）を解析する
+///
+/// `-h` / `--help` が指定された場合は `Ok(None)` を返す
+///
+/// # Errors
+/// 不明なオプションや値の不足がある場合に `ArgError` を返す
+pub fn parse_args<I>(args: I) -> Result<Option<Options>, ArgError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    let (mut accessible, mut bell, mut plain) = (false, false, false);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--mode" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--mode"))?;
+                options.mode = match value.as_str() {
+                    "number" => Mode::Number,
+                    "bulls" => Mode::BullsAndCows,
+                    "liar" => Mode::Liar,
+                    _ => return Err(ArgError::UnknownMode(value)),
+                };
+            }
+            "--lies" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--lies"))?;
+                options.lies = value
+                    .parse()
+                    .ok()
+                    .filter(|&lies| lies <= liar::MAX_LIES)
+                    .ok_or(ArgError::InvalidValue("--lies", value))?;
+            }
+            "--solve" => options.solve = true,
+            "--record" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--record"))?;
+                options.record = Some(PathBuf::from(value));
+            }
+            "--replay" => {
+                let value = args.next().ok_or(ArgError::MissingValue("--replay"))?;
+                options.replay = Some(PathBuf::from(value));
+            }
+            "--accessible" => accessible = true,
+            "--bell" => bell = true,
+            "--plain" => plain = true,
+            _ => return Err(ArgError::UnknownOption(arg)),
+        }
+    }
+
+    // --bell は --accessible の追加オプションなので、単独で指定されても accessible にする
+    options.style = match (accessible || bell, plain) {
+        (true, true) => {
+            let option = if accessible { "--accessible" } else { "--bell" };
+            return Err(ArgError::Conflict(option, "--plain"));
+        }
+        (true, false) => Style::Accessible { bell },
+        (false, true) => Style::Plain,
+        (false, false) => Style::Osaka,
+    };
+
+    // 記録できるのは人間が遊ぶ number モードだけ（再生は記録の内容で動く）
+    if options.record.is_some() {
+        let conflict = if options.replay.is_some() {
+            Some("--replay")
+        } else if options.solve {
+            Some("--solve")
+        } else {
+            match options.mode {
+                Mode::Number => None,
+                Mode::BullsAndCows => Some("--mode bulls"),
+                Mode::Liar => Some("--mode liar"),
+            }
+        };
+        if let Some(option) = conflict {
+            return Err(ArgError::Conflict("--record", option));
+        }
+    }
+
+    // 再生は記録したモードと文体で動くので、それらを変えるオプションとは同時に使えない
+    if options.replay.is_some() {
+        let conflict = if options.solve {
+            Some("--solve")
+        } else if accessible {
+            Some("--accessible")
+        } else if bell {
+            Some("--bell")
+        } else if plain {
+            Some("--plain")
+        } else {
+            match options.mode {
+                Mode::Number => None,
+                Mode::BullsAndCows => Some("--mode bulls"),
+                Mode::Liar => Some("--mode liar"),
+            }
+        };
+        if let Some(option) = conflict {
+            return Err(ArgError::Conflict("--replay", option));
+        }
+    }
+
+    Ok(Some(options))
+}
@@ -0,0 +1,141 @@
+//! 入出力に依存しないゲームの中核
+//!
+//! 入力を1つ受け取るたびに `Event` の列を返す状態機械として実装しているため、
+//! 標準入出力のないブラウザ（WASM）からも同じロジックを使える。
+
+use crate::messages::{Message, Style};
+use crate::{config, parse_guess};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// ゲーム中に発生するイベント
+///
+/// `Display` で大阪弁のメッセージ1行に、`render` で任意の文体の1行に変換される
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// ゲーム開始
+    Started,
+    /// 予想の入力を促す
+    Prompt,
+    /// 有効な予想を受け付けた
+    Guessed(u32),
+    /// 予想が秘密の数字より小さい
+    TooSmall,
+    /// 予想が秘密の数字より大きい
+    TooBig,
+    /// 正解
+    Correct,
+    /// 数字として解釈できない、または範囲外の入力
+    InvalidInput,
+}
+
+impl Event {
+    /// 対応するメッセージ
+    #[must_use]
+    pub fn message(self) -> Message {
+        match self {
+            Event::Started => Message::GameStart,
+            Event::Prompt => Message::Prompt,
+            Event::Guessed(num) => Message::Guessed(num),
+            Event::TooSmall => Message::TooSmall,
+            Event::TooBig => Message::TooBig,
+            Event::Correct => Message::Correct,
+            Event::InvalidInput => Message::InvalidInput,
+        }
+    }
+
+    /// 指定した文体のメッセージ1行に変換する
+    #[must_use]
+    pub fn render(self, style: Style) -> String {
+        style.render(&self.message())
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(Style::Osaka))
+    }
+}
+
+/// 1回分の数当てゲームの状態
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    secret: u32,
+    attempts: u32,
+    finished: bool,
+}
+
+impl Game {
+    /// 秘密の数字を指定してゲームを作成する
+    #[must_use]
+    pub fn new(secret: u32) -> Self {
+        Self {
+            secret,
+            attempts: 0,
+            finished: false,
+        }
+    }
+
+    /// 乱数生成器から秘密の数字を決めてゲームを作成する
+    pub fn from_rng<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        Self::new(rng.random_range(config::MIN_NUMBER..=config::MAX_NUMBER))
+    }
+
+    /// シード値から秘密の数字を決めてゲームを作成する
+    ///
+    /// OS の乱数源を使わないため、エントロピーを外部（ブラウザなど）から注入できる
+    #[must_use]
+    pub fn from_seed(seed: u64) -> Self {
+        Self::from_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// 秘密の数字
+    #[must_use]
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    /// 有効な予想の回数
+    #[must_use]
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// 正解済みかどうか
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// ゲーム開始時のイベント
+    #[must_use]
+    pub fn start(&self) -> Vec<Event> {
+        vec![Event::Started, Event::Prompt]
+    }
+
+    /// 入力1行を処理し、発生したイベントを返す
+    ///
+    /// 正解後の入力は無視して空の列を返す
+    pub fn submit(&mut self, input: &str) -> Vec<Event> {
+        if self.finished {
+            return Vec::new();
+        }
+
+        let Some(num) = parse_guess(input) else {
+            return vec![Event::InvalidInput];
+        };
+
+        self.attempts += 1;
+        let hint = match num.cmp(&self.secret) {
+            Ordering::Less => Event::TooSmall,
+            Ordering::Greater => Event::TooBig,
+            Ordering::Equal => {
+                self.finished = true;
+                Event::Correct
+            }
+        };
+        vec![Event::Guessed(num), hint]
+    }
+}
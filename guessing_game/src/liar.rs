@@ -0,0 +1,367 @@
+//! ウソつきヒントモード（ウラムのゲーム）
+//!
+//! 通常の数当てと同じだが、ヒント（「もっと大きいで！」「もっと小さいで！」）が
+//! 最大 K 回まで逆になる。正解の判定だけは常に正しい。
+
+use crate::messages::{Message, Style};
+use crate::{config, input_loop, parse_guess};
+use rand::Rng;
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+/// ウソをつける機会があるときに実際にウソをつく確率
+pub const LIE_PROBABILITY: f64 = 0.3;
+
+/// ウソの上限回数として指定できる最大値（コマンドラインの `--lies`）
+///
+/// これより大きい K では `--solve` のシミュレーションに時間がかかりすぎる。
+/// ソルバーの体積の計算は u128 で飽和するので、`Solver` に大きな K を渡してもパニックはしない
+pub const MAX_LIES: u32 = 10;
+
+/// 体積の計算で考える残り質問数の上限（2^q が u128 に収まる範囲）
+const MAX_QUESTIONS: u32 = 127;
+
+/// 予想が外れて答えではなくなった候補の矛盾数
+const EXCLUDED: u32 = u32::MAX;
+
+/// 予想できる数字の個数
+const NUMBER_COUNT: usize = (config::MAX_NUMBER - config::MIN_NUMBER + 1) as usize;
+
+/// ヒントを返す出題者
+///
+/// 正解していない予想に対して、残り回数がある間は `probability` の確率でウソをつく
+#[derive(Debug, Clone, PartialEq)]
+pub struct Liar {
+    max_lies: u32,
+    lies_told: u32,
+    probability: f64,
+}
+
+impl Liar {
+    /// 最大 `max_lies` 回、`probability` の確率でウソをつく出題者を作成する
+    #[must_use]
+    pub fn new(max_lies: u32, probability: f64) -> Self {
+        Self {
+            max_lies,
+            lies_told: 0,
+            probability: probability.clamp(0.0, 1.0),
+        }
+    }
+
+    /// これまでについたウソの回数
+    #[must_use]
+    pub fn lies_told(&self) -> u32 {
+        self.lies_told
+    }
+
+    /// 予想 `guess` に対する（ウソかもしれない）比較結果を返す
+    ///
+    /// 正解（`Ordering::Equal`）のときはウソをつかない
+    pub fn answer<G: Rng + ?Sized>(&mut self, guess: u32, secret: u32, rng: &mut G) -> Ordering {
+        let truth = guess.cmp(&secret);
+        if truth == Ordering::Equal || self.lies_told >= self.max_lies {
+            return truth;
+        }
+        if rng.random_bool(self.probability) {
+            self.lies_told += 1;
+            truth.reverse()
+        } else {
+            truth
+        }
+    }
+}
+
+/// ランダムな秘密の数字でウソつきヒントモードを実行する
+///
+/// # Errors
+/// 入出力操作が失敗した場合に `io::Error` を返す
+#[cfg(feature = "cli")]
+pub fn run_game_internal<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    max_lies: u32,
+    style: Style,
+) -> io::Result<()> {
+    let mut rng = rand::rng();
+    let secret_number = rng.random_range(config::MIN_NUMBER..=config::MAX_NUMBER);
+    let mut liar = Liar::new(max_lies, LIE_PROBABILITY);
+    run_game_with_lies(reader, writer, secret_number, &mut liar, &mut rng, style)
+}
+
+/// 秘密の数字と出題者を指定してウソつきヒントモードを実行する
+///
+/// 入力ループとメッセージは `run_game_with_style` と共有し、
+/// ヒントだけを `liar` に決めさせる。
+///
+/// # Errors
+/// 入出力操作が失敗した場合に `io::Error` を返す
+pub fn run_game_with_lies<R, W, G>(
+    reader: &mut R,
+    writer: &mut W,
+    secret_number: u32,
+    liar: &mut Liar,
+    rng: &mut G,
+    style: Style,
+) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    G: Rng + ?Sized,
+{
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] 秘密の数字は {secret_number} やで！");
+
+    let say = |writer: &mut W, message: Message| writeln!(writer, "{}", style.render(&message));
+
+    say(writer, Message::GameStart)?;
+    say(writer, Message::LieNotice(liar.max_lies))?;
+    say(writer, Message::Prompt)?;
+
+    input_loop(reader, writer, |input, writer| {
+        let Some(num) = parse_guess(input) else {
+            say(writer, Message::InvalidInput)?;
+            return Ok(false);
+        };
+
+        say(writer, Message::Guessed(num))?;
+        match liar.answer(num, secret_number, rng) {
+            Ordering::Less => say(writer, Message::TooSmall)?,
+            Ordering::Greater => say(writer, Message::TooBig)?,
+            Ordering::Equal => {
+                say(writer, Message::Correct)?;
+                say(writer, Message::LiesTold(liar.lies_told()))?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    })
+}
+
+/// ウラムのゲームのソルバー（バーレカンプの体積法）
+///
+/// 各候補について「その数字が答えなら何回ウソをつかれたことになるか」を数え、
+/// `max_lies` を超えた候補を除外する。予想は、残り質問数 q に対する
+/// 候補の「体積」Σ C(q, j)（j ≤ 残りウソ回数）が、どちらのヒントでも
+/// なるべく均等に分かれるものを選ぶ。
+#[derive(Debug, Clone)]
+pub struct Solver {
+    max_lies: u32,
+    /// 候補ごとの矛盾したヒントの数（インデックス 0 が `MIN_NUMBER`）
+    lies: Vec<u32>,
+}
+
+impl Solver {
+    /// 最大 `max_lies` 回のウソを想定したソルバーを作成する
+    #[must_use]
+    pub fn new(max_lies: u32) -> Self {
+        Self {
+            max_lies,
+            lies: vec![0; NUMBER_COUNT],
+        }
+    }
+
+    /// まだ答えの可能性がある数字
+    #[must_use]
+    pub fn candidates(&self) -> Vec<u32> {
+        (config::MIN_NUMBER..=config::MAX_NUMBER)
+            .zip(&self.lies)
+            .filter(|&(_, &e)| e != EXCLUDED && e <= self.max_lies)
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    /// 次に予想すべき数字を返す
+    ///
+    /// 候補が尽きている（想定以上のウソがあった）場合は `None` を返す
+    #[must_use]
+    pub fn next_guess(&self) -> Option<u32> {
+        let candidates = self.candidates();
+        match candidates.as_slice() {
+            [] => None,
+            [only] => Some(*only),
+            _ => {
+                let table = self.volume_table(self.required_questions() - 1);
+                (config::MIN_NUMBER..=config::MAX_NUMBER).min_by_key(|&guess| {
+                    let less = self.volume_after(guess, Ordering::Less, &table);
+                    let greater = self.volume_after(guess, Ordering::Greater, &table);
+                    // 同じ体積なら候補を優先する（当たるか、確実に候補が減る）
+                    (less.max(greater), candidates.binary_search(&guess).is_err())
+                })
+            }
+        }
+    }
+
+    /// 予想とヒントから各候補の矛盾数を更新する
+    ///
+    /// `hint` は出題者が返した比較結果（予想が答えより小さければ `Ordering::Less`）
+    pub fn update(&mut self, guess: u32, hint: Ordering) {
+        for (n, e) in (config::MIN_NUMBER..=config::MAX_NUMBER).zip(self.lies.iter_mut()) {
+            match hint {
+                // 正解でなかった以上、その数字は答えではない
+                _ if n == guess => *e = EXCLUDED,
+                Ordering::Equal => {}
+                _ if guess.cmp(&n) != hint => *e = e.saturating_add(1),
+                _ => {}
+            }
+        }
+    }
+
+    /// `secret` を当てるまでに必要な予想回数を返す
+    ///
+    /// 想定以上のウソで候補が尽きた場合は `None` を返す
+    pub fn solve<G: Rng + ?Sized>(
+        mut self,
+        secret: u32,
+        liar: &mut Liar,
+        rng: &mut G,
+    ) -> Option<u32> {
+        let mut attempts = 0;
+        loop {
+            let guess = self.next_guess()?;
+            attempts += 1;
+            let hint = liar.answer(guess, secret, rng);
+            if hint == Ordering::Equal {
+                return Some(attempts);
+            }
+            self.update(guess, hint);
+        }
+    }
+
+    /// 全候補の体積が 2^q 以下に収まる最小の残り質問数 q（バーレカンプの下限）
+    fn required_questions(&self) -> u32 {
+        (1..MAX_QUESTIONS)
+            .find(|&q| {
+                let table = self.volume_table(q);
+                let volume = self.lies.iter().fold(0_u128, |sum, &e| {
+                    sum.saturating_add(self.volume_of(&table, e))
+                });
+                volume <= 1_u128 << q
+            })
+            .unwrap_or(MAX_QUESTIONS)
+    }
+
+    /// `hint` を受け取った後の全候補の体積
+    fn volume_after(&self, guess: u32, hint: Ordering, table: &[u128]) -> u128 {
+        (config::MIN_NUMBER..=config::MAX_NUMBER)
+            .zip(&self.lies)
+            .filter(|&(n, _)| n != guess)
+            .map(|(n, &e)| {
+                let e = if guess.cmp(&n) == hint {
+                    e
+                } else {
+                    e.saturating_add(1)
+                };
+                self.volume_of(table, e)
+            })
+            .fold(0, u128::saturating_add)
+    }
+
+    /// 残り質問数 `q` のときの、残りウソ回数 r（0〜min(K, q)）ごとの体積 Σ_{j=0}^{r} C(q, j)
+    ///
+    /// r ≥ q の体積はすべて 2^q なので、表の長さは K ではなく q で抑えられる。
+    /// u128 に収まらない体積は `u128::MAX` に飽和させる
+    fn volume_table(&self, q: u32) -> Vec<u128> {
+        let mut table = Vec::new();
+        let mut binomial = 1_u128;
+        let mut sum = 1_u128;
+        table.push(sum);
+        for j in 1..=self.max_lies.min(q) {
+            // C(q, j) = C(q, j - 1) * (q - j + 1) / j（掛け算があふれたら飽和）
+            binomial = binomial
+                .checked_mul(u128::from(q - j + 1))
+                .map_or(u128::MAX, |product| product / u128::from(j));
+            sum = sum.saturating_add(binomial);
+            table.push(sum);
+        }
+        table
+    }
+
+    /// 体積表から矛盾数 `e` の候補の体積を引く（除外済みの候補は 0）
+    fn volume_of(&self, table: &[u128], e: u32) -> u128 {
+        if e == EXCLUDED || e > self.max_lies {
+            return 0;
+        }
+        let remaining = usize::try_from(self.max_lies - e).unwrap_or(usize::MAX);
+        table[remaining.min(table.len() - 1)]
+    }
+}
+
+/// シミュレーション結果（ウソの上限 K ごとの集計）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    /// ウソの上限回数
+    pub max_lies: u32,
+    /// プレイしたゲーム数
+    pub games: u32,
+    /// 平均予想回数
+    pub average: f64,
+    /// 最大予想回数
+    pub worst: u32,
+}
+
+/// ソルバーに `rounds` 周分、全ての秘密の数字（1〜100）を解かせて集計する
+///
+/// # Panics
+/// ソルバーが想定内のウソで答えを見失った場合にパニックする（ソルバーの不具合）
+pub fn simulate<G: Rng + ?Sized>(
+    max_lies: u32,
+    probability: f64,
+    rounds: u32,
+    rng: &mut G,
+) -> Summary {
+    let mut total = 0_u64;
+    let mut worst = 0;
+    let mut games = 0;
+
+    for _ in 0..rounds {
+        for secret in config::MIN_NUMBER..=config::MAX_NUMBER {
+            let mut liar = Liar::new(max_lies, probability);
+            let attempts = Solver::new(max_lies)
+                .solve(secret, &mut liar, rng)
+                .expect("ウソの回数は上限以内なので必ず当たる");
+            total += u64::from(attempts);
+            worst = worst.max(attempts);
+            games += 1;
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let average = total as f64 / f64::from(games.max(1));
+    Summary {
+        max_lies,
+        games,
+        average,
+        worst,
+    }
+}
+
+/// ウソの上限 0〜`max_lies` についてシミュレーションし、比較表を出力する
+///
+/// # Errors
+/// 書き込みに失敗した場合に `io::Error` を返す
+pub fn run_simulation<W: Write, G: Rng + ?Sized>(
+    writer: &mut W,
+    max_lies: u32,
+    rounds: u32,
+    rng: &mut G,
+    style: Style,
+) -> io::Result<()> {
+    let say = |writer: &mut W, message: Message| writeln!(writer, "{}", style.render(&message));
+
+    say(writer, Message::SimulationTitle(LIE_PROBABILITY))?;
+    say(writer, Message::SimulationHeader)?;
+    say(writer, Message::SimulationRule)?;
+    for k in 0..=max_lies {
+        let summary = simulate(k, LIE_PROBABILITY, rounds, rng);
+        say(
+            writer,
+            Message::SimulationRow {
+                max_lies: summary.max_lies,
+                games: summary.games,
+                average: summary.average,
+                worst: summary.worst,
+            },
+        )?;
+    }
+    Ok(())
+}
@@ -1,7 +1,18 @@
-use rand::Rng;
-use std::cmp::Ordering;
 use std::io::{self, BufRead, Write};
 
+pub mod bulls_and_cows;
+pub mod cli;
+pub mod game;
+pub mod liar;
+pub mod messages;
+#[cfg(feature = "cli")]
+pub mod transcript;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use game::{Event, Game};
+pub use messages::Style;
+
 /// ゲームの設定値
 pub mod config {
     /// 予想可能な数字の最小値
@@ -10,21 +21,12 @@ pub mod config {
     pub const MAX_NUMBER: u32 = 100;
 }
 
-/// ゲームで使用するメッセージ（大阪弁）
-mod messages {
-    pub const GAME_START: &str = "1から100の数字を当ててみぃや！";
-    pub const PROMPT: &str = "ほな、予想入れてみて！";
-    pub const TOO_SMALL: &str = "もっと大きいで！";
-    pub const TOO_BIG: &str = "もっと小さいで！";
-    pub const CORRECT: &str = "正解や！やったな！";
-    pub const INVALID_INPUT: &str = "ちゃんとした数字入れてや！";
-}
-
 /// ゲームのメインロジック（引数なし版）
 /// 標準入出力を使用するシンプルなAPI
 ///
 /// # Errors
 /// 入出力操作が失敗した場合に `io::Error` を返す
+#[cfg(feature = "cli")]
 pub fn run_game() -> io::Result<()> {
     let stdin = io::stdin();
     let stdout = io::stdout();
@@ -35,8 +37,9 @@ pub fn run_game() -> io::Result<()> {
 ///
 /// # Errors
 /// 入出力操作が失敗した場合に `io::Error` を返す
+#[cfg(feature = "cli")]
 pub fn run_game_internal<R: BufRead, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
-    let secret_number = rand::rng().random_range(config::MIN_NUMBER..=config::MAX_NUMBER);
+    let secret_number = Game::from_rng(&mut rand::rng()).secret();
     run_game_with_secret(reader, writer, secret_number)
 }
 
@@ -48,35 +51,57 @@ pub fn run_game_with_secret<R: BufRead, W: Write>(
     reader: &mut R,
     writer: &mut W,
     secret_number: u32,
+) -> io::Result<()> {
+    run_game_with_style(reader, writer, secret_number, Style::default())
+}
+
+/// 秘密の数字とメッセージの文体を指定してゲームを実行する
+///
+/// # Errors
+/// 入出力操作が失敗した場合に `io::Error` を返す
+pub fn run_game_with_style<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    secret_number: u32,
+    style: Style,
 ) -> io::Result<()> {
     #[cfg(debug_assertions)]
     eprintln!("[DEBUG] 秘密の数字は {secret_number} やで！");
 
-    writeln!(writer, "{}", messages::GAME_START)?;
-    writeln!(writer, "{}", messages::PROMPT)?;
+    let mut game = Game::new(secret_number);
+    for event in game.start() {
+        writeln!(writer, "{}", event.render(style))?;
+    }
 
-    let mut guess = String::new();
+    input_loop(reader, writer, |input, writer| {
+        for event in game.submit(input) {
+            writeln!(writer, "{}", event.render(style))?;
+        }
+        Ok(game.is_finished())
+    })
+}
+
+/// 1行ずつ入力を読み込み、`judge` に渡す共通の入力ループ
+///
+/// `judge` が `true`（正解）を返すか、EOF に達した時点で終了する。
+/// 各ゲームモードはこのループを共有し、判定とメッセージ出力だけを差し替える。
+fn input_loop<R, W, F>(reader: &mut R, writer: &mut W, mut judge: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str, &mut W) -> io::Result<bool>,
+{
+    let mut line = String::new();
 
     loop {
-        guess.clear();
-        let bytes_read = reader.read_line(&mut guess)?;
+        line.clear();
+        let bytes_read = reader.read_line(&mut line)?;
         if bytes_read == 0 {
             break; // EOF
         }
 
-        match parse_guess(&guess) {
-            Some(num) => {
-                writeln!(writer, "あんたの予想は {num} やな！")?;
-                match num.cmp(&secret_number) {
-                    Ordering::Less => writeln!(writer, "{}", messages::TOO_SMALL)?,
-                    Ordering::Greater => writeln!(writer, "{}", messages::TOO_BIG)?,
-                    Ordering::Equal => {
-                        writeln!(writer, "{}", messages::CORRECT)?;
-                        break;
-                    }
-                }
-            }
-            None => writeln!(writer, "{}", messages::INVALID_INPUT)?,
+        if judge(&line, writer)? {
+            break;
         }
     }
 
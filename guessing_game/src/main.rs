@@ -1,4 +1,13 @@
-use guessing_game::run_game;
+use guessing_game::bulls_and_cows::{self, Code};
+use guessing_game::cli::{self, Mode, Options};
+use guessing_game::transcript::{self, Transcript};
+use guessing_game::{liar, run_game_with_style, Game};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+/// liar モードのシミュレーションで全ての秘密の数字を解かせる周回数
+const SIMULATION_ROUNDS: u32 = 20;
 
 fn main() {
     // Ctrl-C シグナルハンドラを設定
@@ -8,8 +17,87 @@ fn main() {
     })
     .expect("Ctrl-C ハンドラの設定に失敗しました");
 
-    if let Err(e) = run_game() {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            eprintln!("{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(path) = &options.replay {
+        match replay(path) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Err(e) = run(&options) {
         eprintln!("Error: {e}");
         std::process::exit(1);
     }
 }
+
+/// 記録ファイルを再生して差分を表示し、一致したかどうかを返す
+fn replay(path: &Path) -> io::Result<bool> {
+    let transcript = Transcript::parse(&fs::read_to_string(path)?)?;
+    let report = transcript.replay()?;
+    println!("{report}");
+    Ok(report.is_match())
+}
+
+/// オプションに応じたゲームモードを実行する
+fn run(options: &Options) -> io::Result<()> {
+    let style = options.style;
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let (mut reader, mut writer) = (stdin.lock(), stdout.lock());
+
+    match (options.mode, options.solve) {
+        (Mode::Number, false) => {
+            let secret = Game::from_rng(&mut rand::rng()).secret();
+            match &options.record {
+                Some(path) => {
+                    let sink = BufWriter::new(File::create(path)?);
+                    transcript::record_game_with_secret(
+                        &mut reader,
+                        &mut writer,
+                        secret,
+                        style,
+                        sink,
+                    )
+                }
+                None => run_game_with_style(&mut reader, &mut writer, secret, style),
+            }
+        }
+        (Mode::Number, true) => Err(io::Error::other(
+            "--solve は bulls / liar モードでのみ使えます",
+        )),
+        (Mode::BullsAndCows, false) => {
+            bulls_and_cows::run_game_internal(&mut reader, &mut writer, style)
+        }
+        (Mode::BullsAndCows, true) => {
+            let secret = Code::random(&mut rand::rng());
+            bulls_and_cows::run_solver(&mut writer, &secret, style)
+        }
+        (Mode::Liar, false) => {
+            liar::run_game_internal(&mut reader, &mut writer, options.lies, style)
+        }
+        (Mode::Liar, true) => liar::run_simulation(
+            &mut writer,
+            options.lies,
+            SIMULATION_ROUNDS,
+            &mut rand::rng(),
+            style,
+        ),
+    }
+}
@@ -0,0 +1,282 @@
+//! ゲームが出力するメッセージ
+//!
+//! すべての出力文はここで組み立てる。文体（`Style`）を切り替えることで、
+//! 大阪弁・スクリーンリーダー向け・ASCII のみの出力を選べる。
+
+use crate::bulls_and_cows::{Code, Score};
+use std::fmt;
+use std::str::FromStr;
+
+/// 端末のベル（正解時に鳴らす）
+const BELL: char = '\u{7}';
+
+/// Markdown 表の見出しの下の区切り線
+const TABLE_RULE: &str = "| --- | --- | --- | --- |";
+
+/// 出力メッセージの文体
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// 大阪弁による親しみやすいメッセージ（既定）
+    #[default]
+    Osaka,
+    /// スクリーンリーダー向けの、短く記号の少ない一定の形式のメッセージ
+    Accessible {
+        /// 正解時にベルを鳴らす
+        bell: bool,
+    },
+    /// 日本語フォントのない端末向けの ASCII のみのメッセージ
+    ///
+    /// ゲーム中のメッセージだけが対象で、ヘルプや引数のエラーなどは日本語のまま
+    Plain,
+}
+
+impl Style {
+    /// 記録ファイルなどで使う文体の名前
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Style::Osaka => "osaka",
+            Style::Accessible { bell: false } => "accessible",
+            Style::Accessible { bell: true } => "accessible-bell",
+            Style::Plain => "plain",
+        }
+    }
+
+    /// メッセージをこの文体の1行に変換する
+    #[must_use]
+    pub fn render(self, message: &Message) -> String {
+        match self {
+            Style::Osaka => osaka(message),
+            Style::Accessible { bell } => {
+                let text = accessible(message);
+                if bell && message.is_correct() {
+                    format!("{BELL}{text}")
+                } else {
+                    text
+                }
+            }
+            Style::Plain => plain(message),
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 不明な文体の名前
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownStyle(pub String);
+
+impl fmt::Display for UnknownStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "不明な文体です: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownStyle {}
+
+impl FromStr for Style {
+    type Err = UnknownStyle;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "osaka" => Ok(Style::Osaka),
+            "accessible" => Ok(Style::Accessible { bell: false }),
+            "accessible-bell" => Ok(Style::Accessible { bell: true }),
+            "plain" => Ok(Style::Plain),
+            _ => Err(UnknownStyle(s.to_string())),
+        }
+    }
+}
+
+/// 出力するメッセージの種類
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// 数当てゲームの開始
+    GameStart,
+    /// 予想の入力を促す
+    Prompt,
+    /// 数当ての予想を受け付けた
+    Guessed(u32),
+    /// 予想が小さい
+    TooSmall,
+    /// 予想が大きい
+    TooBig,
+    /// 正解
+    Correct,
+    /// 数当ての入力が不正
+    InvalidInput,
+    /// 数字当て（Bulls and Cows）の開始
+    CodeGameStart,
+    /// 数字当ての予想を受け付けた
+    CodeGuessed(Code),
+    /// 数字当ての判定結果
+    CodeScore(Score),
+    /// 数字当ての入力が不正
+    InvalidCode,
+    /// ソルバーが解き始める
+    SolverStart(Code),
+    /// ソルバーの1手
+    SolverStep {
+        /// 何手目か（1 始まり）
+        turn: usize,
+        /// 予想
+        guess: Code,
+        /// 判定結果
+        score: Score,
+    },
+    /// ソルバーが正解した
+    SolverDone(usize),
+    /// ウソつきヒントモードの注意書き
+    LieNotice(u32),
+    /// 正解後に明かすウソの回数
+    LiesTold(u32),
+    /// シミュレーション結果の表題
+    SimulationTitle(f64),
+    /// シミュレーション結果の表の見出し
+    SimulationHeader,
+    /// シミュレーション結果の表の見出しと各行の区切り
+    SimulationRule,
+    /// シミュレーション結果の表の1行
+    SimulationRow {
+        /// ウソの上限回数
+        max_lies: u32,
+        /// ゲーム数
+        games: u32,
+        /// 平均予想回数
+        average: f64,
+        /// 最大予想回数
+        worst: u32,
+    },
+}
+
+impl Message {
+    fn is_correct(&self) -> bool {
+        matches!(self, Message::Correct)
+    }
+}
+
+/// 大阪弁
+fn osaka(message: &Message) -> String {
+    match message {
+        Message::GameStart => "1から100の数字を当ててみぃや！".to_string(),
+        Message::Prompt => "ほな、予想入れてみて！".to_string(),
+        Message::Guessed(num) => format!("あんたの予想は {num} やな！"),
+        Message::TooSmall => "もっと大きいで！".to_string(),
+        Message::TooBig => "もっと小さいで！".to_string(),
+        Message::Correct => "正解や！やったな！".to_string(),
+        Message::InvalidInput => "ちゃんとした数字入れてや！".to_string(),
+        Message::CodeGameStart => "同じ数字を使わん4桁の数字を当ててみぃや！".to_string(),
+        Message::CodeGuessed(code) => format!("あんたの予想は {code} やな！"),
+        Message::CodeScore(score) => format!("{}ブル {}カウやで！", score.bulls, score.cows),
+        Message::InvalidCode => "同じ数字を使わん4桁の数字入れてや！".to_string(),
+        Message::SolverStart(code) => {
+            format!("秘密のコードは {code} や。ソルバーに解かせてみるで！")
+        }
+        Message::SolverStep { turn, guess, score } => format!(
+            "{turn:2}手目: {guess} → {}ブル {}カウ",
+            score.bulls, score.cows
+        ),
+        Message::SolverDone(turns) => format!("{turns}手で正解や！"),
+        Message::LieNotice(k) => format!("ただし、ヒントは最大 {k} 回ウソつくかもしれんで！"),
+        Message::LiesTold(n) => format!("ウソは {n} 回ついとったで！"),
+        Message::SimulationTitle(p) => format!("ウソの回数とソルバーの予想回数（ウソの確率 {p}）"),
+        Message::SimulationHeader => "| K | ゲーム数 | 平均手数 | 最大手数 |".to_string(),
+        Message::SimulationRule => TABLE_RULE.to_string(),
+        Message::SimulationRow {
+            max_lies,
+            games,
+            average,
+            worst,
+        } => table_row(*max_lies, *games, *average, *worst),
+    }
+}
+
+/// スクリーンリーダー向け（短く、記号を最小限に、同じ種類の情報は同じ形式で）
+fn accessible(message: &Message) -> String {
+    match message {
+        Message::GameStart => "数当て 1から100".to_string(),
+        Message::Prompt => "予想を入力".to_string(),
+        Message::Guessed(num) => format!("予想 {num}"),
+        Message::TooSmall => "もっと大きい".to_string(),
+        Message::TooBig => "もっと小さい".to_string(),
+        Message::Correct => "正解".to_string(),
+        Message::InvalidInput => "無効 1から100の数字を入力".to_string(),
+        Message::CodeGameStart => "数字当て 重複なし4桁".to_string(),
+        Message::CodeGuessed(code) => format!("予想 {}", spaced_digits(*code)),
+        Message::CodeScore(score) => format!("ブル {} カウ {}", score.bulls, score.cows),
+        Message::InvalidCode => "無効 重複なし4桁の数字を入力".to_string(),
+        Message::SolverStart(code) => format!("答え {} ソルバー開始", spaced_digits(*code)),
+        Message::SolverStep { turn, guess, score } => format!(
+            "{turn}手目 予想 {} ブル {} カウ {}",
+            spaced_digits(*guess),
+            score.bulls,
+            score.cows
+        ),
+        Message::SolverDone(turns) => format!("正解 {turns}手"),
+        Message::LieNotice(k) => format!("ヒントのうそ 最大{k}回"),
+        Message::LiesTold(n) => format!("うそ {n}回"),
+        Message::SimulationTitle(p) => format!("シミュレーション うその確率 {p}"),
+        Message::SimulationHeader => "K ゲーム数 平均手数 最大手数".to_string(),
+        Message::SimulationRule => "以下 K ごとの結果".to_string(),
+        Message::SimulationRow {
+            max_lies,
+            games,
+            average,
+            worst,
+        } => format!("K {max_lies} ゲーム数 {games} 平均 {average:.2} 最大 {worst}"),
+    }
+}
+
+/// ASCII のみ
+fn plain(message: &Message) -> String {
+    match message {
+        Message::GameStart => "Guess the number from 1 to 100!".to_string(),
+        Message::Prompt => "Enter your guess.".to_string(),
+        Message::Guessed(num) => format!("You guessed {num}."),
+        Message::TooSmall => "Too small. Go higher.".to_string(),
+        Message::TooBig => "Too big. Go lower.".to_string(),
+        Message::Correct => "Correct! You got it!".to_string(),
+        Message::InvalidInput => "Please enter a number from 1 to 100.".to_string(),
+        Message::CodeGameStart => "Guess the 4-digit code with no repeated digits!".to_string(),
+        Message::CodeGuessed(code) => format!("You guessed {code}."),
+        Message::CodeScore(score) => format!("{} bulls, {} cows.", score.bulls, score.cows),
+        Message::InvalidCode => "Please enter 4 digits with no repeats.".to_string(),
+        Message::SolverStart(code) => format!("The secret code is {code}. Let the solver try!"),
+        Message::SolverStep { turn, guess, score } => format!(
+            "Turn {turn:2}: {guess} -> {} bulls, {} cows",
+            score.bulls, score.cows
+        ),
+        Message::SolverDone(turns) => format!("Solved in {turns} turns!"),
+        Message::LieNotice(k) => format!("Careful: up to {k} hints may be lies!"),
+        Message::LiesTold(n) => format!("I lied {n} times."),
+        Message::SimulationTitle(p) => {
+            format!("Solver attempts by number of lies (lie probability {p})")
+        }
+        Message::SimulationHeader => "| K | games | average | worst |".to_string(),
+        Message::SimulationRule => TABLE_RULE.to_string(),
+        Message::SimulationRow {
+            max_lies,
+            games,
+            average,
+            worst,
+        } => table_row(*max_lies, *games, *average, *worst),
+    }
+}
+
+/// シミュレーション結果の Markdown 表の1行
+fn table_row(max_lies: u32, games: u32, average: f64, worst: u32) -> String {
+    format!("| {max_lies} | {games} | {average:.2} | {worst} |")
+}
+
+/// 読み上げで1つの数として扱われないよう、コードの各桁を空白で区切る
+fn spaced_digits(code: Code) -> String {
+    code.digits()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
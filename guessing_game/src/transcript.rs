@@ -0,0 +1,363 @@
+//! セッションの記録（`--record`）と再生（`--replay`）
+//!
+//! 記録ファイルはタブ区切りのテキスト形式（`<TAB>` はタブ文字）:
+//!
+//! ```text
+//! # guessing_game transcript v1
+//! secret<TAB>42
+//! style<TAB>osaka
+//! 0<TAB>out<TAB>1から100の数字を当ててみぃや！
+//! 0<TAB>out<TAB>ほな、予想入れてみて！
+//! 1520<TAB>in<TAB>50
+//! 1520<TAB>out<TAB>あんたの予想は 50 やな！
+//! ```
+//!
+//! 各行は「記録開始からの経過ミリ秒」「方向（`in` / `out`）」「1行分のテキスト」。
+//! 再生時は記録された入力を同じ秘密の数字・文体で `run_game_with_style` に流し、
+//! 出力を記録と突き合わせる。`style` 行は省略でき、その場合は既定の文体になる。
+
+use crate::{run_game_with_style, Style};
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use std::rc::Rc;
+use std::time::Instant;
+
+/// 記録ファイルの1行目
+const HEADER: &str = "# guessing_game transcript v1";
+
+/// 入出力の方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// プレイヤーの入力
+    Input,
+    /// ゲームの出力
+    Output,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Input => "in",
+            Direction::Output => "out",
+        }
+    }
+}
+
+/// 記録された1行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// 記録開始からの経過ミリ秒
+    pub elapsed_ms: u64,
+    /// 入出力の方向
+    pub direction: Direction,
+    /// 改行を除いた1行分のテキスト
+    pub line: String,
+}
+
+/// 読み込んだ記録
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    /// 記録時の秘密の数字
+    pub secret: u32,
+    /// 記録時のメッセージの文体
+    pub style: Style,
+    /// 記録された入出力（記録順）
+    pub entries: Vec<Entry>,
+}
+
+/// 記録ファイルの解析エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptError {
+    /// 1行目が記録ファイルのヘッダーではない
+    MissingHeader,
+    /// 秘密の数字の行がない、または不正
+    InvalidSecret,
+    /// 文体の行が不正
+    InvalidStyle,
+    /// 入出力の行が不正（行番号は 1 始まり）
+    InvalidEntry(usize),
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::MissingHeader => write!(f, "記録ファイルのヘッダーがありません"),
+            TranscriptError::InvalidSecret => write!(f, "秘密の数字の行が不正です"),
+            TranscriptError::InvalidStyle => write!(f, "文体の行が不正です"),
+            TranscriptError::InvalidEntry(line) => write!(f, "{line} 行目が不正です"),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+impl From<TranscriptError> for io::Error {
+    fn from(e: TranscriptError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl Transcript {
+    /// 記録ファイルの内容を解析する
+    ///
+    /// # Errors
+    /// 形式が不正な場合に `TranscriptError` を返す
+    pub fn parse(text: &str) -> Result<Self, TranscriptError> {
+        let mut lines = text.lines().enumerate().peekable();
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(TranscriptError::MissingHeader);
+        }
+
+        let secret = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix("secret\t"))
+            .and_then(|s| s.parse().ok())
+            .ok_or(TranscriptError::InvalidSecret)?;
+
+        let style = match lines.next_if(|(_, line)| line.starts_with("style\t")) {
+            Some((_, line)) => line["style\t".len()..]
+                .parse()
+                .map_err(|_| TranscriptError::InvalidStyle)?,
+            None => Style::default(),
+        };
+
+        let entries = lines
+            .map(|(i, line)| parse_entry(line).ok_or(TranscriptError::InvalidEntry(i + 1)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            secret,
+            style,
+            entries,
+        })
+    }
+
+    /// 記録された入力行
+    pub fn inputs(&self) -> impl Iterator<Item = &str> {
+        self.lines(Direction::Input)
+    }
+
+    /// 記録された出力行
+    pub fn outputs(&self) -> impl Iterator<Item = &str> {
+        self.lines(Direction::Output)
+    }
+
+    fn lines(&self, direction: Direction) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(move |e| e.direction == direction)
+            .map(|e| e.line.as_str())
+    }
+
+    /// 記録された入力を同じ秘密の数字・文体で再生し、出力を記録と比較する
+    ///
+    /// # Errors
+    /// 再生中の入出力操作が失敗した場合に `io::Error` を返す
+    pub fn replay(&self) -> io::Result<ReplayReport> {
+        let mut input = String::new();
+        for line in self.inputs() {
+            input.push_str(line);
+            input.push('\n');
+        }
+        let mut output = Vec::new();
+        run_game_with_style(&mut input.as_bytes(), &mut output, self.secret, self.style)?;
+
+        let actual = String::from_utf8_lossy(&output);
+        let expected: Vec<&str> = self.outputs().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+
+        let differences = (0..expected.len().max(actual.len()))
+            .filter_map(|i| {
+                let (e, a) = (expected.get(i), actual.get(i));
+                (e != a).then(|| Difference {
+                    line: i + 1,
+                    expected: e.map(ToString::to_string),
+                    actual: a.map(ToString::to_string),
+                })
+            })
+            .collect();
+
+        Ok(ReplayReport { differences })
+    }
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut fields = line.splitn(3, '\t');
+    let elapsed_ms = fields.next()?.parse().ok()?;
+    let direction = match fields.next()? {
+        "in" => Direction::Input,
+        "out" => Direction::Output,
+        _ => return None,
+    };
+    let line = fields.next()?.to_string();
+    Some(Entry {
+        elapsed_ms,
+        direction,
+        line,
+    })
+}
+
+/// 出力の1行の違い
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// 出力の何行目か（1 始まり）
+    pub line: usize,
+    /// 記録された出力（記録の方が短い場合は `None`）
+    pub expected: Option<String>,
+    /// 再生した出力（再生の方が短い場合は `None`）
+    pub actual: Option<String>,
+}
+
+/// 再生結果
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReplayReport {
+    /// 記録と異なる出力行
+    pub differences: Vec<Difference>,
+}
+
+impl ReplayReport {
+    /// 出力が記録と完全に一致したかどうか
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_match() {
+            return write!(f, "再生結果: 記録と一致しました");
+        }
+        writeln!(
+            f,
+            "再生結果: {} 行が記録と異なります",
+            self.differences.len()
+        )?;
+        for d in &self.differences {
+            writeln!(f, "@@ {} 行目 @@", d.line)?;
+            if let Some(expected) = &d.expected {
+                writeln!(f, "-{expected}")?;
+            }
+            if let Some(actual) = &d.actual {
+                writeln!(f, "+{actual}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 入出力を1行ずつ記録ファイルへ書き出す
+///
+/// 中断（Ctrl-C）されても途中までの記録が残るよう、1行ごとに flush する
+#[derive(Debug)]
+struct Recorder<S: Write> {
+    sink: S,
+    start: Instant,
+}
+
+impl<S: Write> Recorder<S> {
+    fn new(mut sink: S, secret: u32, style: Style) -> io::Result<Self> {
+        writeln!(sink, "{HEADER}")?;
+        writeln!(sink, "secret\t{secret}")?;
+        writeln!(sink, "style\t{style}")?;
+        sink.flush()?;
+        Ok(Self {
+            sink,
+            start: Instant::now(),
+        })
+    }
+
+    fn log(&mut self, direction: Direction, line: &str) -> io::Result<()> {
+        let elapsed_ms = u64::try_from(self.start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        writeln!(self.sink, "{elapsed_ms}\t{}\t{line}", direction.as_str())?;
+        self.sink.flush()
+    }
+}
+
+/// 読み込んだ行を記録する Reader
+///
+/// ゲームは `read_line` でしか入力を読まないため、`read_line` だけを記録する
+struct RecordingReader<'a, R, S: Write> {
+    inner: &'a mut R,
+    recorder: Rc<RefCell<Recorder<S>>>,
+}
+
+impl<R: BufRead, S: Write> Read for RecordingReader<'_, R, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: BufRead, S: Write> BufRead for RecordingReader<'_, R, S> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let start = buf.len();
+        let bytes_read = self.inner.read_line(buf)?;
+        if bytes_read > 0 {
+            let line = buf[start..].trim_end_matches(['\n', '\r']);
+            self.recorder.borrow_mut().log(Direction::Input, line)?;
+        }
+        Ok(bytes_read)
+    }
+}
+
+/// 書き込んだ内容を1行ずつ記録する Writer
+struct RecordingWriter<'a, W, S: Write> {
+    inner: &'a mut W,
+    recorder: Rc<RefCell<Recorder<S>>>,
+    pending: Vec<u8>,
+}
+
+impl<W: Write, S: Write> Write for RecordingWriter<'_, W, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.pending.extend_from_slice(&buf[..written]);
+        while let Some(pos) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line[..pos]);
+            self.recorder.borrow_mut().log(Direction::Output, &line)?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 入出力を `sink` に記録しながら、秘密の数字と文体を指定してゲームを実行する
+///
+/// # Errors
+/// ゲームの入出力、または記録の書き込みが失敗した場合に `io::Error` を返す
+pub fn record_game_with_secret<R, W, S>(
+    reader: &mut R,
+    writer: &mut W,
+    secret_number: u32,
+    style: Style,
+    sink: S,
+) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    S: Write,
+{
+    let recorder = Rc::new(RefCell::new(Recorder::new(sink, secret_number, style)?));
+    let mut reader = RecordingReader {
+        inner: reader,
+        recorder: Rc::clone(&recorder),
+    };
+    let mut writer = RecordingWriter {
+        inner: writer,
+        recorder,
+        pending: Vec::new(),
+    };
+    run_game_with_style(&mut reader, &mut writer, secret_number, style)
+}
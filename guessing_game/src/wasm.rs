@@ -0,0 +1,121 @@
+//! ブラウザ（`wasm32-unknown-unknown`）向けの JavaScript API
+//!
+//! 標準入力の代わりに `submitGuess` で1回ずつ予想を渡し、
+//! `takeEvents` で溜まったイベントを取り出す。
+//! 乱数のシードは JavaScript 側（`crypto.getRandomValues` など）から渡す。
+//!
+//! ```js
+//! import init, { WebGame } from "./pkg/guessing_game.js";
+//!
+//! await init();
+//! const game = new WebGame(BigInt(Date.now()));
+//! game.submitGuess("50");
+//! for (const event of game.takeEvents()) {
+//!   console.log(event.kind, event.message);
+//! }
+//! ```
+
+use crate::{Event, Game};
+use wasm_bindgen::prelude::*;
+
+/// JavaScript に渡すイベント
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebEvent {
+    kind: &'static str,
+    message: String,
+    guess: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl WebEvent {
+    /// イベントの種類（`started`, `prompt`, `guessed`, `too_small`, `too_big`, `correct`, `invalid_input`）
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn kind(&self) -> String {
+        self.kind.to_string()
+    }
+
+    /// 表示用のメッセージ
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// 受け付けた予想（`guessed` イベントのみ）
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn guess(&self) -> Option<u32> {
+        self.guess
+    }
+}
+
+impl From<Event> for WebEvent {
+    fn from(event: Event) -> Self {
+        let (kind, guess) = match event {
+            Event::Started => ("started", None),
+            Event::Prompt => ("prompt", None),
+            Event::Guessed(num) => ("guessed", Some(num)),
+            Event::TooSmall => ("too_small", None),
+            Event::TooBig => ("too_big", None),
+            Event::Correct => ("correct", None),
+            Event::InvalidInput => ("invalid_input", None),
+        };
+        Self {
+            kind,
+            message: event.to_string(),
+            guess,
+        }
+    }
+}
+
+/// JavaScript から操作する数当てゲーム
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct WebGame {
+    game: Game,
+    events: Vec<Event>,
+}
+
+#[wasm_bindgen]
+impl WebGame {
+    /// シード値から新しいゲームを開始する
+    ///
+    /// 開始時のイベント（`started`, `prompt`）は `takeEvents` で取り出せる
+    #[wasm_bindgen(constructor)]
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        let game = Game::from_seed(seed);
+        let events = game.start();
+        Self { game, events }
+    }
+
+    /// 予想を1つ送信し、正解したかどうかを返す
+    #[wasm_bindgen(js_name = submitGuess)]
+    pub fn submit_guess(&mut self, input: &str) -> bool {
+        let events = self.game.submit(input);
+        self.events.extend(events);
+        self.game.is_finished()
+    }
+
+    /// 溜まっているイベントをすべて取り出す
+    #[wasm_bindgen(js_name = takeEvents)]
+    pub fn take_events(&mut self) -> Vec<WebEvent> {
+        self.events.drain(..).map(WebEvent::from).collect()
+    }
+
+    /// 正解済みかどうか
+    #[wasm_bindgen(getter, js_name = isFinished)]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.game.is_finished()
+    }
+
+    /// 有効な予想の回数
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn attempts(&self) -> u32 {
+        self.game.attempts()
+    }
+}
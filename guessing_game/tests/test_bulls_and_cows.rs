@@ -0,0 +1,207 @@
+use guessing_game::bulls_and_cows::{
+    all_codes, run_game_with_code, run_solver, Code, Score, Solver, Strategy, CODE_COUNT,
+};
+use guessing_game::cli::{parse_args, ArgError, Mode, Options};
+use guessing_game::Style;
+use std::io::Cursor;
+
+fn code(s: &str) -> Code {
+    Code::parse(s).unwrap()
+}
+
+// =============================================================================
+// Code / Score テスト
+// =============================================================================
+
+#[test]
+fn test_code_parse_valid() {
+    assert_eq!(code("1234").digits(), [1, 2, 3, 4]);
+    assert_eq!(code("  0987\n").digits(), [0, 9, 8, 7]);
+}
+
+#[test]
+fn test_code_parse_invalid() {
+    assert_eq!(Code::parse("1123"), None); // 重複あり
+    assert_eq!(Code::parse("123"), None); // 桁不足
+    assert_eq!(Code::parse("12345"), None); // 桁超過
+    assert_eq!(Code::parse("12a4"), None); // 数字以外
+    assert_eq!(Code::parse("１２３４"), None); // 全角数字
+    assert_eq!(Code::parse(""), None);
+}
+
+#[test]
+fn test_code_display() {
+    assert_eq!(code("0123").to_string(), "0123");
+}
+
+#[test]
+fn test_score() {
+    let secret = code("1234");
+    assert_eq!(secret.score(&code("1234")), Score { bulls: 4, cows: 0 });
+    assert_eq!(secret.score(&code("4321")), Score { bulls: 0, cows: 4 });
+    assert_eq!(secret.score(&code("1243")), Score { bulls: 2, cows: 2 });
+    assert_eq!(secret.score(&code("5678")), Score { bulls: 0, cows: 0 });
+    assert_eq!(secret.score(&code("1567")), Score { bulls: 1, cows: 0 });
+    assert!(secret.score(&code("1234")).is_correct());
+    assert!(!secret.score(&code("1243")).is_correct());
+}
+
+#[test]
+fn test_all_codes() {
+    let codes = all_codes();
+    assert_eq!(codes.len(), CODE_COUNT);
+    assert_eq!(codes.first().unwrap().to_string(), "0123");
+    assert_eq!(codes.last().unwrap().to_string(), "9876");
+}
+
+#[test]
+fn test_code_random_is_valid() {
+    let mut rng = rand::rng();
+    for _ in 0..100 {
+        let c = Code::random(&mut rng);
+        assert_eq!(Code::new(c.digits()), Some(c));
+    }
+}
+
+// =============================================================================
+// run_game_with_code テスト
+// =============================================================================
+
+#[test]
+fn test_run_game_with_code_feedback_and_correct() {
+    let mut input = Cursor::new("1243\n1234\n");
+    let mut output = Vec::new();
+
+    run_game_with_code(&mut input, &mut output, code("1234"), Style::default()).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(output_str.contains("同じ数字を使わん4桁の数字を当ててみぃや！"));
+    assert!(output_str.contains("ほな、予想入れてみて！"));
+    assert!(output_str.contains("あんたの予想は 1243 やな！"));
+    assert!(output_str.contains("2ブル 2カウやで！"));
+    assert!(output_str.contains("正解や！やったな！"));
+}
+
+#[test]
+fn test_run_game_with_code_invalid_input() {
+    let mut input = Cursor::new("1123\nabc\n");
+    let mut output = Vec::new();
+
+    run_game_with_code(&mut input, &mut output, code("1234"), Style::default()).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert_eq!(
+        output_str
+            .matches("同じ数字を使わん4桁の数字入れてや！")
+            .count(),
+        2
+    );
+    assert!(!output_str.contains("正解や！やったな！"));
+}
+
+#[test]
+fn test_run_game_with_code_stops_after_correct() {
+    let mut input = Cursor::new("1234\n5678\n");
+    let mut output = Vec::new();
+
+    run_game_with_code(&mut input, &mut output, code("1234"), Style::default()).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(!output_str.contains("5678"));
+}
+
+// =============================================================================
+// ソルバーテスト
+// =============================================================================
+
+#[test]
+fn test_solver_update_narrows_candidates() {
+    let mut solver = Solver::new();
+    assert_eq!(solver.candidates().len(), CODE_COUNT);
+
+    let guess = solver.next_guess().unwrap();
+    solver.update(&guess, Score { bulls: 0, cows: 0 });
+    // 0123 を使わないコードは 6 × 5 × 4 × 3 通り
+    assert_eq!(solver.candidates().len(), 360);
+}
+
+#[test]
+fn test_solver_solves_within_seven_guesses() {
+    for secret in ["0123", "9876", "5096", "3210", "1357"] {
+        let history = Solver::new().solve(&code(secret));
+        assert!(history.len() <= 7, "{secret}: {} 手", history.len());
+        assert_eq!(history.last().unwrap().0, code(secret));
+        assert!(history.last().unwrap().1.is_correct());
+    }
+}
+
+#[test]
+fn test_solver_inconsistent_feedback() {
+    let mut solver = Solver::new();
+    let guess = solver.next_guess().unwrap();
+    solver.update(&guess, Score { bulls: 3, cows: 1 }); // あり得ない判定
+    assert_eq!(solver.next_guess(), None);
+}
+
+#[test]
+fn test_run_solver_output() {
+    let mut output = Vec::new();
+    run_solver(&mut output, &code("0123"), Style::default()).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(output_str.contains(" 1手目: 0123 → 4ブル 0カウ"));
+    assert!(output_str.contains("1手で正解や！"));
+}
+
+#[test]
+#[ignore = "全 5040 通りを解くため release ビルドで実行する"]
+fn test_strategy_worst_case_is_seven() {
+    let strategy = Strategy::build();
+    assert_eq!(strategy.first_guess(), code("0123"));
+    assert_eq!(strategy.worst_case(), 7);
+
+    for secret in all_codes() {
+        let history = strategy.solve(&secret);
+        assert!(history.len() <= 7);
+        assert_eq!(history.last().unwrap().0, secret);
+    }
+}
+
+// =============================================================================
+// コマンドライン引数テスト
+// =============================================================================
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_parse_args_default() {
+    assert_eq!(parse_args(args(&[])), Ok(Some(Options::default())));
+}
+
+#[test]
+fn test_parse_args_mode() {
+    let options = parse_args(args(&["--mode", "bulls", "--solve"]))
+        .unwrap()
+        .unwrap();
+    assert_eq!(options.mode, Mode::BullsAndCows);
+    assert!(options.solve);
+}
+
+#[test]
+fn test_parse_args_errors() {
+    assert_eq!(parse_args(args(&["--help"])), Ok(None));
+    assert_eq!(
+        parse_args(args(&["--mode"])),
+        Err(ArgError::MissingValue("--mode"))
+    );
+    assert_eq!(
+        parse_args(args(&["--mode", "chess"])),
+        Err(ArgError::UnknownMode("chess".to_string()))
+    );
+    assert_eq!(
+        parse_args(args(&["--verbose"])),
+        Err(ArgError::UnknownOption("--verbose".to_string()))
+    );
+}
@@ -0,0 +1,62 @@
+use guessing_game::{config, Event, Game};
+
+// =============================================================================
+// Game テスト（入出力に依存しないゲームの中核）
+// =============================================================================
+
+#[test]
+fn test_game_start_events() {
+    let game = Game::new(50);
+    assert_eq!(game.start(), vec![Event::Started, Event::Prompt]);
+    assert_eq!(game.attempts(), 0);
+    assert!(!game.is_finished());
+}
+
+#[test]
+fn test_game_submit_hints() {
+    let mut game = Game::new(50);
+    assert_eq!(game.submit("25"), vec![Event::Guessed(25), Event::TooSmall]);
+    assert_eq!(game.submit("75\n"), vec![Event::Guessed(75), Event::TooBig]);
+    assert_eq!(game.submit("50"), vec![Event::Guessed(50), Event::Correct]);
+    assert_eq!(game.attempts(), 3);
+    assert!(game.is_finished());
+}
+
+#[test]
+fn test_game_invalid_input_is_not_counted() {
+    let mut game = Game::new(50);
+    assert_eq!(game.submit("abc"), vec![Event::InvalidInput]);
+    assert_eq!(game.submit("101"), vec![Event::InvalidInput]);
+    assert_eq!(game.attempts(), 0);
+}
+
+#[test]
+fn test_game_ignores_input_after_correct() {
+    let mut game = Game::new(42);
+    game.submit("42");
+    assert!(game.submit("10").is_empty());
+    assert_eq!(game.attempts(), 1);
+}
+
+#[test]
+fn test_game_from_seed_is_deterministic() {
+    for seed in 0..100 {
+        let secret = Game::from_seed(seed).secret();
+        assert_eq!(Game::from_seed(seed).secret(), secret);
+        assert!((config::MIN_NUMBER..=config::MAX_NUMBER).contains(&secret));
+    }
+}
+
+#[test]
+fn test_event_display() {
+    assert_eq!(Event::Started.to_string(), "1から100の数字を当ててみぃや！");
+    assert_eq!(Event::Prompt.to_string(), "ほな、予想入れてみて！");
+    assert_eq!(Event::Guessed(42).to_string(), "あんたの予想は 42 やな！");
+    assert_eq!(Event::TooSmall.to_string(), "もっと大きいで！");
+    assert_eq!(Event::TooBig.to_string(), "もっと小さいで！");
+    assert_eq!(Event::Correct.to_string(), "正解や！やったな！");
+    assert_eq!(
+        Event::InvalidInput.to_string(),
+        "ちゃんとした数字入れてや！"
+    );
+}
@@ -0,0 +1,212 @@
+use guessing_game::cli::{parse_args, ArgError, Mode};
+use guessing_game::liar::{run_game_with_lies, run_simulation, simulate, Liar, Solver, MAX_LIES};
+use guessing_game::Style;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::io::Cursor;
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(42)
+}
+
+// =============================================================================
+// Liar テスト
+// =============================================================================
+
+#[test]
+fn test_liar_never_lies_with_zero_budget() {
+    let mut liar = Liar::new(0, 1.0);
+    let mut rng = rng();
+    assert_eq!(liar.answer(25, 50, &mut rng), Ordering::Less);
+    assert_eq!(liar.answer(75, 50, &mut rng), Ordering::Greater);
+    assert_eq!(liar.lies_told(), 0);
+}
+
+#[test]
+fn test_liar_respects_budget() {
+    let mut liar = Liar::new(2, 1.0);
+    let mut rng = rng();
+    assert_eq!(liar.answer(25, 50, &mut rng), Ordering::Greater); // ウソ
+    assert_eq!(liar.answer(25, 50, &mut rng), Ordering::Greater); // ウソ
+    assert_eq!(liar.answer(25, 50, &mut rng), Ordering::Less); // 上限到達
+    assert_eq!(liar.lies_told(), 2);
+}
+
+#[test]
+fn test_liar_never_lies_about_correct_guess() {
+    let mut liar = Liar::new(5, 1.0);
+    let mut rng = rng();
+    assert_eq!(liar.answer(50, 50, &mut rng), Ordering::Equal);
+    assert_eq!(liar.lies_told(), 0);
+}
+
+// =============================================================================
+// run_game_with_lies テスト
+// =============================================================================
+
+#[test]
+fn test_run_game_with_lies_reversed_hint() {
+    let mut input = Cursor::new("25\n25\n50\n");
+    let mut output = Vec::new();
+    let mut liar = Liar::new(1, 1.0);
+
+    run_game_with_lies(
+        &mut input,
+        &mut output,
+        50,
+        &mut liar,
+        &mut rng(),
+        Style::default(),
+    )
+    .unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(output_str.contains("1から100の数字を当ててみぃや！"));
+    assert!(output_str.contains("ただし、ヒントは最大 1 回ウソつくかもしれんで！"));
+    // 1回目はウソ、2回目は本当のヒント
+    assert_eq!(output_str.matches("もっと小さいで！").count(), 1);
+    assert_eq!(output_str.matches("もっと大きいで！").count(), 1);
+    assert!(output_str.contains("正解や！やったな！"));
+    assert!(output_str.contains("ウソは 1 回ついとったで！"));
+}
+
+#[test]
+fn test_run_game_with_lies_invalid_input() {
+    let mut input = Cursor::new("abc\n");
+    let mut output = Vec::new();
+    let mut liar = Liar::new(1, 1.0);
+
+    run_game_with_lies(
+        &mut input,
+        &mut output,
+        50,
+        &mut liar,
+        &mut rng(),
+        Style::default(),
+    )
+    .unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(output_str.contains("ちゃんとした数字入れてや！"));
+}
+
+// =============================================================================
+// ソルバーテスト
+// =============================================================================
+
+#[test]
+fn test_solver_without_lies_is_binary_search() {
+    let mut rng = rng();
+    for secret in [1, 37, 50, 64, 100] {
+        let mut liar = Liar::new(0, 0.0);
+        let attempts = Solver::new(0).solve(secret, &mut liar, &mut rng).unwrap();
+        assert!(attempts <= 7, "{secret}: {attempts} 回");
+    }
+}
+
+#[test]
+fn test_solver_update_counts_contradictions() {
+    let mut solver = Solver::new(1);
+    solver.update(50, Ordering::Less); // 「答えは 50 より大きい」
+    solver.update(50, Ordering::Greater); // 矛盾するヒント
+    let candidates = solver.candidates();
+    // どちらのヒントも 1 回ずつウソの可能性があるので、50 以外は全て残る
+    assert_eq!(candidates.len(), 99);
+    assert!(!candidates.contains(&50));
+
+    solver.update(75, Ordering::Less); // 「答えは 75 より大きい」
+    let candidates = solver.candidates();
+    assert!(candidates.iter().all(|&n| n > 50));
+}
+
+#[test]
+fn test_solver_finds_secret_despite_lies() {
+    let mut rng = rng();
+    for max_lies in 1..=3 {
+        for secret in [1, 42, 100] {
+            let mut liar = Liar::new(max_lies, 1.0);
+            let attempts = Solver::new(max_lies).solve(secret, &mut liar, &mut rng);
+            assert!(attempts.is_some(), "K={max_lies}, secret={secret}");
+        }
+    }
+}
+
+#[test]
+fn test_solver_gives_up_on_too_many_lies() {
+    let mut rng = rng();
+    let mut liar = Liar::new(3, 1.0);
+    assert_eq!(Solver::new(0).solve(42, &mut liar, &mut rng), None);
+}
+
+#[test]
+fn test_solver_with_huge_lie_budget_does_not_overflow() {
+    // 体積が u128 を超える K でもパニックせず、候補から予想する
+    for max_lies in [60, 1_000, u32::MAX] {
+        let mut solver = Solver::new(max_lies);
+        let guess = solver.next_guess().unwrap();
+        solver.update(guess, Ordering::Less);
+        assert!(solver.next_guess().is_some(), "K={max_lies}");
+    }
+    let mut rng = rng();
+    let mut liar = Liar::new(60, 0.3);
+    assert!(Solver::new(60).solve(42, &mut liar, &mut rng).is_some());
+}
+
+#[test]
+fn test_simulate_more_lies_need_more_attempts() {
+    let mut rng = rng();
+    let honest = simulate(0, 0.3, 1, &mut rng);
+    let liar = simulate(2, 0.3, 1, &mut rng);
+    assert_eq!(honest.games, 100);
+    assert_eq!(honest.worst, 7);
+    assert!(liar.average > honest.average);
+    assert!(liar.worst > honest.worst);
+}
+
+#[test]
+fn test_run_simulation_output() {
+    let mut output = Vec::new();
+    run_simulation(&mut output, 1, 1, &mut rng(), Style::default()).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(
+        output_str.contains("| K | ゲーム数 | 平均手数 | 最大手数 |\n| --- | --- | --- | --- |\n")
+    );
+    assert!(output_str.contains("| 0 | 100 |"));
+    assert!(output_str.contains("| 1 | 100 |"));
+}
+
+// =============================================================================
+// コマンドライン引数テスト
+// =============================================================================
+
+#[test]
+fn test_parse_args_lies() {
+    let args = ["--mode", "liar", "--lies", "3"].map(String::from);
+    let options = parse_args(args).unwrap().unwrap();
+    assert_eq!(options.mode, Mode::Liar);
+    assert_eq!(options.lies, 3);
+
+    let args = ["--lies", &MAX_LIES.to_string()].map(String::from);
+    assert_eq!(parse_args(args).unwrap().unwrap().lies, MAX_LIES);
+
+    // 上限を超える K はシミュレーションに時間がかかりすぎるので受け付けない
+    for value in [
+        (MAX_LIES + 1).to_string(),
+        "60".to_string(),
+        "-1".to_string(),
+    ] {
+        let args = ["--lies".to_string(), value.clone()];
+        assert_eq!(
+            parse_args(args),
+            Err(ArgError::InvalidValue("--lies", value))
+        );
+    }
+
+    let args = ["--lies", "many"].map(String::from);
+    assert_eq!(
+        parse_args(args),
+        Err(ArgError::InvalidValue("--lies", "many".to_string()))
+    );
+}
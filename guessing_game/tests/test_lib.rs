@@ -148,10 +148,7 @@ struct FailingWriter;
 
 impl Write for FailingWriter {
     fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            "simulated write error",
-        ))
+        Err(io::Error::other("simulated write error"))
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -164,7 +161,7 @@ struct FailingReader;
 
 impl BufRead for FailingReader {
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
-        Err(io::Error::new(io::ErrorKind::Other, "simulated read error"))
+        Err(io::Error::other("simulated read error"))
     }
 
     fn consume(&mut self, _amt: usize) {}
@@ -172,7 +169,7 @@ impl BufRead for FailingReader {
 
 impl io::Read for FailingReader {
     fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
-        Err(io::Error::new(io::ErrorKind::Other, "simulated read error"))
+        Err(io::Error::other("simulated read error"))
     }
 }
 
@@ -194,10 +191,7 @@ impl FailAfterNWritesWriter {
 impl Write for FailAfterNWritesWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         if self.write_count >= self.writes_before_fail {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
-                "simulated write error",
-            ))
+            Err(io::Error::other("simulated write error"))
         } else {
             self.write_count += 1;
             Ok(buf.len())
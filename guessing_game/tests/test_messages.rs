@@ -0,0 +1,201 @@
+use guessing_game::bulls_and_cows::{run_game_with_code, Code, Score};
+use guessing_game::cli::{parse_args, ArgError};
+use guessing_game::messages::{Message, UnknownStyle};
+use guessing_game::{run_game_with_style, Event, Style};
+use std::io::Cursor;
+
+const ALL_STYLES: [Style; 4] = [
+    Style::Osaka,
+    Style::Accessible { bell: false },
+    Style::Accessible { bell: true },
+    Style::Plain,
+];
+
+fn sample_messages() -> Vec<Message> {
+    let code = Code::parse("0123").unwrap();
+    let score = Score { bulls: 1, cows: 2 };
+    vec![
+        Message::GameStart,
+        Message::Prompt,
+        Message::Guessed(42),
+        Message::TooSmall,
+        Message::TooBig,
+        Message::Correct,
+        Message::InvalidInput,
+        Message::CodeGameStart,
+        Message::CodeGuessed(code),
+        Message::CodeScore(score),
+        Message::InvalidCode,
+        Message::SolverStart(code),
+        Message::SolverStep {
+            turn: 1,
+            guess: code,
+            score,
+        },
+        Message::SolverDone(5),
+        Message::LieNotice(2),
+        Message::LiesTold(1),
+        Message::SimulationTitle(0.3),
+        Message::SimulationHeader,
+        Message::SimulationRule,
+        Message::SimulationRow {
+            max_lies: 1,
+            games: 100,
+            average: 8.21,
+            worst: 11,
+        },
+    ]
+}
+
+fn play(input: &str, style: Style) -> String {
+    let mut reader = Cursor::new(input.to_string());
+    let mut output = Vec::new();
+    run_game_with_style(&mut reader, &mut output, 50, style).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+// =============================================================================
+// 文体ごとのメッセージテスト
+// =============================================================================
+
+#[test]
+fn test_osaka_style_matches_default_output() {
+    let input = "25\n75\nabc\n50\n";
+    assert_eq!(play(input, Style::Osaka), play(input, Style::default()));
+    assert!(play(input, Style::Osaka).contains("正解や！やったな！"));
+}
+
+#[test]
+fn test_accessible_style_is_short_and_punctuation_light() {
+    let full_width_punctuation = ['！', '、', '。', '（', '）', '：'];
+    for message in sample_messages() {
+        let text = Style::Accessible { bell: false }.render(&message);
+        assert!(
+            !text.contains(full_width_punctuation),
+            "{message:?}: {text}"
+        );
+        assert!(!text.contains(['!', '?']), "{message:?}: {text}");
+        assert!(text.chars().count() <= 40, "{message:?}: {text}");
+    }
+}
+
+#[test]
+fn test_accessible_style_game_output() {
+    let output = play("25\n75\nabc\n50\n", Style::Accessible { bell: false });
+    assert_eq!(
+        output,
+        "数当て 1から100\n予想を入力\n予想 25\nもっと大きい\n予想 75\nもっと小さい\n\
+         無効 1から100の数字を入力\n予想 50\n正解\n"
+    );
+}
+
+#[test]
+fn test_accessible_style_reads_code_digits_separately() {
+    let code = Code::parse("0427").unwrap();
+    assert_eq!(
+        Style::Accessible { bell: false }.render(&Message::CodeGuessed(code)),
+        "予想 0 4 2 7"
+    );
+}
+
+#[test]
+fn test_bell_only_on_correct() {
+    let bell = Style::Accessible { bell: true };
+    assert_eq!(bell.render(&Message::Correct), "\u{7}正解");
+    assert_eq!(bell.render(&Message::TooSmall), "もっと大きい");
+
+    let output = play("25\n50\n", bell);
+    assert_eq!(output.matches('\u{7}').count(), 1);
+    assert!(!play("25\n50\n", Style::Accessible { bell: false }).contains('\u{7}'));
+}
+
+#[test]
+fn test_plain_style_is_ascii_only() {
+    for message in sample_messages() {
+        let text = Style::Plain.render(&message);
+        assert!(text.is_ascii(), "{message:?}: {text}");
+    }
+
+    let output = play("25\n75\nabc\n50\n", Style::Plain);
+    assert!(output.is_ascii());
+    assert!(output.contains("Too small. Go higher."));
+    assert!(output.contains("Too big. Go lower."));
+    assert!(output.contains("Correct! You got it!"));
+}
+
+#[test]
+fn test_every_style_renders_every_message_on_one_line() {
+    for style in ALL_STYLES {
+        for message in sample_messages() {
+            let text = style.render(&message);
+            assert!(!text.is_empty(), "{style}: {message:?}");
+            assert!(!text.contains('\n'), "{style}: {message:?}");
+        }
+    }
+}
+
+#[test]
+fn test_event_render_uses_message_layer() {
+    assert_eq!(Event::Guessed(7).render(Style::Plain), "You guessed 7.");
+    assert_eq!(Event::Guessed(7).to_string(), "あんたの予想は 7 やな！");
+}
+
+#[test]
+fn test_bulls_and_cows_uses_style() {
+    let mut input = Cursor::new("1243\n1234\n");
+    let mut output = Vec::new();
+    let secret = Code::parse("1234").unwrap();
+
+    run_game_with_code(&mut input, &mut output, secret, Style::Plain).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(output_str.is_ascii());
+    assert!(output_str.contains("2 bulls, 2 cows."));
+}
+
+// =============================================================================
+// 文体の名前テスト
+// =============================================================================
+
+#[test]
+fn test_style_name_round_trip() {
+    for style in ALL_STYLES {
+        assert_eq!(style.name().parse(), Ok(style));
+    }
+    assert_eq!(
+        "loud".parse::<Style>(),
+        Err(UnknownStyle("loud".to_string()))
+    );
+}
+
+// =============================================================================
+// コマンドライン引数テスト
+// =============================================================================
+
+#[test]
+fn test_parse_args_styles() {
+    let parse = |list: &[&str]| parse_args(list.iter().map(ToString::to_string));
+
+    assert_eq!(parse(&[]).unwrap().unwrap().style, Style::Osaka);
+    assert_eq!(
+        parse(&["--accessible"]).unwrap().unwrap().style,
+        Style::Accessible { bell: false }
+    );
+    assert_eq!(
+        parse(&["--accessible", "--bell"]).unwrap().unwrap().style,
+        Style::Accessible { bell: true }
+    );
+    assert_eq!(
+        parse(&["--bell"]).unwrap().unwrap().style,
+        Style::Accessible { bell: true }
+    );
+    assert_eq!(parse(&["--plain"]).unwrap().unwrap().style, Style::Plain);
+    assert_eq!(
+        parse(&["--plain", "--accessible"]),
+        Err(ArgError::Conflict("--accessible", "--plain"))
+    );
+    assert_eq!(
+        parse(&["--bell", "--plain"]),
+        Err(ArgError::Conflict("--bell", "--plain"))
+    );
+}
@@ -0,0 +1,266 @@
+use guessing_game::cli::{parse_args, ArgError};
+use guessing_game::transcript::{
+    record_game_with_secret, Difference, Direction, Transcript, TranscriptError,
+};
+use guessing_game::Style;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+fn record(input: &str, secret: u32) -> (String, String) {
+    record_with_style(input, secret, Style::default())
+}
+
+fn record_with_style(input: &str, secret: u32, style: Style) -> (String, String) {
+    let mut reader = Cursor::new(input.to_string());
+    let mut output = Vec::new();
+    let mut sink = Vec::new();
+    record_game_with_secret(&mut reader, &mut output, secret, style, &mut sink).unwrap();
+    (
+        String::from_utf8(output).unwrap(),
+        String::from_utf8(sink).unwrap(),
+    )
+}
+
+// =============================================================================
+// ゴールデンファイルテスト
+// =============================================================================
+
+#[test]
+fn test_golden_transcripts_replay_without_differences() {
+    let mut count = 0;
+    for entry in fs::read_dir(GOLDEN_DIR).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) != Some("transcript") {
+            continue;
+        }
+        let transcript = Transcript::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        let report = transcript.replay().unwrap();
+        assert!(report.is_match(), "{}:\n{report}", path.display());
+        count += 1;
+    }
+    assert!(count > 0, "ゴールデンファイルが見つからない");
+}
+
+#[test]
+fn test_golden_transcript_contents() {
+    let path = Path::new(GOLDEN_DIR).join("hints_then_correct.transcript");
+    let transcript = Transcript::parse(&fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(transcript.secret, 42);
+    assert_eq!(transcript.inputs().collect::<Vec<_>>(), ["50", "25", "42"]);
+    assert_eq!(transcript.outputs().count(), 8);
+    assert_eq!(transcript.entries[2].elapsed_ms, 2310);
+    assert_eq!(transcript.entries[2].direction, Direction::Input);
+}
+
+// =============================================================================
+// 記録テスト
+// =============================================================================
+
+#[test]
+fn test_record_passes_through_io() {
+    let (output, _) = record("25\n50\n", 50);
+    assert!(output.contains("もっと大きいで！"));
+    assert!(output.contains("正解や！やったな！"));
+}
+
+#[test]
+fn test_record_then_replay_round_trip() {
+    let (_, recorded) = record("75\n\t60 \nabc\n50\n", 50);
+    let transcript = Transcript::parse(&recorded).unwrap();
+
+    assert_eq!(transcript.secret, 50);
+    assert_eq!(
+        transcript.inputs().collect::<Vec<_>>(),
+        ["75", "\t60 ", "abc", "50"]
+    );
+    assert_eq!(
+        transcript.outputs().next(),
+        Some("1から100の数字を当ててみぃや！")
+    );
+    assert!(transcript.replay().unwrap().is_match());
+}
+
+#[test]
+fn test_record_then_replay_keeps_style() {
+    let style = Style::Accessible { bell: true };
+    let (_, recorded) = record_with_style("25\n50\n", 50, style);
+    assert!(recorded.contains("\nstyle\taccessible-bell\n"));
+
+    let transcript = Transcript::parse(&recorded).unwrap();
+    assert_eq!(transcript.style, style);
+    assert_eq!(transcript.outputs().last(), Some("\u{7}正解"));
+    assert!(transcript.replay().unwrap().is_match());
+}
+
+#[test]
+fn test_record_keeps_entry_order() {
+    let (_, recorded) = record("50\n", 50);
+    let directions: Vec<Direction> = Transcript::parse(&recorded)
+        .unwrap()
+        .entries
+        .iter()
+        .map(|e| e.direction)
+        .collect();
+    assert_eq!(
+        directions,
+        [
+            Direction::Output,
+            Direction::Output,
+            Direction::Input,
+            Direction::Output,
+            Direction::Output,
+        ]
+    );
+}
+
+// =============================================================================
+// 再生（差分検出）テスト
+// =============================================================================
+
+#[test]
+fn test_replay_detects_changed_message() {
+    let (_, recorded) = record("25\n50\n", 50);
+    let edited = recorded.replace("もっと大きいで！", "もっと大きいよ！");
+
+    let report = Transcript::parse(&edited).unwrap().replay().unwrap();
+    assert!(!report.is_match());
+    assert_eq!(
+        report.differences,
+        [Difference {
+            line: 4,
+            expected: Some("もっと大きいよ！".to_string()),
+            actual: Some("もっと大きいで！".to_string()),
+        }]
+    );
+    let text = report.to_string();
+    assert!(text.contains("1 行が記録と異なります"));
+    assert!(text.contains("-もっと大きいよ！"));
+    assert!(text.contains("+もっと大きいで！"));
+}
+
+#[test]
+fn test_replay_detects_missing_and_extra_lines() {
+    let (_, recorded) = record("50\n", 50);
+    // 最後の出力行（正解メッセージ）を記録から取り除く
+    let lines: Vec<&str> = recorded.lines().collect();
+    let truncated = lines[..lines.len() - 1].join("\n");
+
+    let report = Transcript::parse(&truncated).unwrap().replay().unwrap();
+    assert_eq!(report.differences.len(), 1);
+    assert_eq!(report.differences[0].expected, None);
+    assert_eq!(
+        report.differences[0].actual.as_deref(),
+        Some("正解や！やったな！")
+    );
+}
+
+// =============================================================================
+// 解析エラーテスト
+// =============================================================================
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        Transcript::parse("hello"),
+        Err(TranscriptError::MissingHeader)
+    );
+    assert_eq!(
+        Transcript::parse("# guessing_game transcript v1\nsecret\tx\n"),
+        Err(TranscriptError::InvalidSecret)
+    );
+    assert_eq!(
+        Transcript::parse("# guessing_game transcript v1\nsecret\t5\n0\tout\tok\n0\tsideways\t?\n"),
+        Err(TranscriptError::InvalidEntry(4))
+    );
+}
+
+// =============================================================================
+// コマンドライン引数テスト
+// =============================================================================
+
+#[test]
+fn test_parse_args_record_and_replay() {
+    let args = ["--record", "session.transcript"].map(String::from);
+    let options = parse_args(args).unwrap().unwrap();
+    assert_eq!(options.record, Some(PathBuf::from("session.transcript")));
+
+    let args = ["--replay", "session.transcript"].map(String::from);
+    let options = parse_args(args).unwrap().unwrap();
+    assert_eq!(options.replay, Some(PathBuf::from("session.transcript")));
+
+    let args = ["--replay"].map(String::from);
+    assert_eq!(parse_args(args), Err(ArgError::MissingValue("--replay")));
+
+    // 記録は number モードで人間が遊ぶときだけ
+    let args = ["--mode", "number", "--record", "a", "--plain"].map(String::from);
+    assert!(parse_args(args).unwrap().is_some());
+}
+
+#[test]
+fn test_parse_args_transcript_conflicts() {
+    let cases: [(&[&str], ArgError); 12] = [
+        (
+            &["--record", "a", "--replay", "b"],
+            ArgError::Conflict("--record", "--replay"),
+        ),
+        (
+            &["--replay", "b", "--record", "a"],
+            ArgError::Conflict("--record", "--replay"),
+        ),
+        (
+            &["--mode", "bulls", "--record", "a"],
+            ArgError::Conflict("--record", "--mode bulls"),
+        ),
+        (
+            &["--record", "a", "--mode", "liar"],
+            ArgError::Conflict("--record", "--mode liar"),
+        ),
+        (
+            &["--mode", "liar", "--solve", "--record", "a"],
+            ArgError::Conflict("--record", "--solve"),
+        ),
+        // 再生は記録したモードと文体で動く
+        (
+            &["--replay", "b", "--mode", "bulls"],
+            ArgError::Conflict("--replay", "--mode bulls"),
+        ),
+        (
+            &["--mode", "liar", "--replay", "b"],
+            ArgError::Conflict("--replay", "--mode liar"),
+        ),
+        (
+            &["--replay", "b", "--solve"],
+            ArgError::Conflict("--replay", "--solve"),
+        ),
+        (
+            &["--replay", "b", "--plain"],
+            ArgError::Conflict("--replay", "--plain"),
+        ),
+        (
+            &["--accessible", "--replay", "b"],
+            ArgError::Conflict("--replay", "--accessible"),
+        ),
+        (
+            &["--replay", "b", "--bell"],
+            ArgError::Conflict("--replay", "--bell"),
+        ),
+        (
+            &["--replay", "b", "--accessible", "--plain"],
+            ArgError::Conflict("--accessible", "--plain"),
+        ),
+    ];
+    for (args, error) in cases {
+        assert_eq!(
+            parse_args(args.iter().map(ToString::to_string)),
+            Err(error),
+            "{args:?}"
+        );
+    }
+
+    // --mode number は既定なので再生と同時に指定してよい
+    let args = ["--mode", "number", "--replay", "b"].map(String::from);
+    assert!(parse_args(args).unwrap().is_some());
+}
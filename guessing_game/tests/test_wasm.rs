@@ -0,0 +1,60 @@
+//! WASM 向け API のテスト
+//!
+//! ヘッドレスの wasm ランナー（`wasm-bindgen-test-runner`）で実行する:
+//!
+//! ```bash
+//! cargo test --target wasm32-unknown-unknown --no-default-features --features wasm
+//! ```
+#![cfg(target_arch = "wasm32")]
+
+use guessing_game::wasm::WebGame;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// 二分探索で正解するまで予想を送る
+fn play_to_the_end(game: &mut WebGame) {
+    let (mut low, mut high) = (1, 100);
+    while !game.is_finished() {
+        let guess = (low + high) / 2;
+        game.submit_guess(&guess.to_string());
+        for event in game.take_events() {
+            match event.kind().as_str() {
+                "too_small" => low = guess + 1,
+                "too_big" => high = guess - 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_web_game_start_events() {
+    let mut game = WebGame::new(1);
+    let kinds: Vec<String> = game.take_events().iter().map(|e| e.kind()).collect();
+    assert_eq!(kinds, ["started", "prompt"]);
+    assert!(game.take_events().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_web_game_guess_events() {
+    let mut game = WebGame::new(1);
+    game.take_events();
+
+    assert!(!game.submit_guess("abc"));
+    let events = game.take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind(), "invalid_input");
+    assert_eq!(events[0].message(), "ちゃんとした数字入れてや！");
+
+    game.submit_guess("50");
+    let events = game.take_events();
+    assert_eq!(events[0].kind(), "guessed");
+    assert_eq!(events[0].guess(), Some(50));
+}
+
+#[wasm_bindgen_test]
+fn test_web_game_can_be_finished() {
+    let mut game = WebGame::new(12345);
+    play_to_the_end(&mut game);
+    assert!(game.is_finished());
+    assert!(game.attempts() <= 7);
+}